@@ -0,0 +1,17 @@
+// Lints fire inside the `construct_uint!` expansion
+#![allow(clippy::manual_div_ceil, clippy::assign_op_pattern)]
+
+use uint::construct_uint;
+
+construct_uint! {
+    /// 256-bit unsigned integer for intermediate products that overflow u128
+    pub struct U256(4);
+}
+
+/// Computes `a * b / c` without overflowing the intermediate product
+pub fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    if c == 0 {
+        return 0;
+    }
+    (U256::from(a) * U256::from(b) / U256::from(c)).as_u128()
+}
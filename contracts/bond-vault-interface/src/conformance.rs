@@ -0,0 +1,312 @@
+//! Conformance suite for `BondVault` implementations, run on near-sdk's mocked blockchain.
+//!
+//! A vault crate enables the `conformance` feature in its dev-dependencies and expands
+//! the suite inside a test module with a setup function that initializes the vault
+//! with `owner` as owner, `token_contract()` as its USDC contract and no fees:
+//!
+//! ```ignore
+//! #[cfg(test)]
+//! mod tests {
+//!     use super::*;
+//!
+//!     fn setup(owner: AccountId) -> VaultContract { /* ... */ }
+//!
+//!     bond_vault_interface::bond_vault_conformance_tests!(setup);
+//! }
+//! ```
+//!
+//! Vaults with `DepositMode::Simulated` expand `bond_vault_conformance_tests!(setup, Simulated)`.
+
+use near_sdk::json_types::U128;
+use near_sdk::test_utils::{accounts, VMContextBuilder};
+use near_sdk::{testing_env, AccountId};
+
+use crate::{
+    BondVault, DepositMode, DepositRequest, EventQuery, VaultEventKind, VaultToken, WithdrawRequest,
+};
+
+const TOKEN: VaultToken = VaultToken::USDC;
+const DEPOSIT_AMOUNT: u128 = 1_000_000;
+
+pub fn owner() -> AccountId {
+    accounts(0)
+}
+
+pub fn user() -> AccountId {
+    accounts(1)
+}
+
+pub fn other_user() -> AccountId {
+    accounts(2)
+}
+
+/// USDC contract the setup function must configure; `DepositMode::TokenTransfer` deposits
+/// are submitted from it
+pub fn token_contract() -> AccountId {
+    "usdc.near".parse().unwrap()
+}
+
+/// Switches the predecessor while keeping contract storage
+pub fn set_caller(predecessor: AccountId, block_timestamp: u64) {
+    testing_env!(VMContextBuilder::new()
+        .current_account_id("vault.near".parse().unwrap())
+        .predecessor_account_id(predecessor)
+        .block_timestamp(block_timestamp)
+        .build());
+}
+
+fn init<V: BondVault>(setup: impl FnOnce(AccountId) -> V) -> V {
+    set_caller(owner(), 0);
+    setup(owner())
+}
+
+/// Account allowed to deposit for `account_id` under the vault's deposit mode
+fn depositor<V: BondVault>(vault: &V, account_id: &AccountId) -> AccountId {
+    match vault.vault_status().deposit_mode {
+        DepositMode::TokenTransfer => token_contract(),
+        DepositMode::Simulated => account_id.clone(),
+    }
+}
+
+fn deposit<V: BondVault>(vault: &mut V, account_id: AccountId, amount: u128, block_timestamp: u64) -> U128 {
+    set_caller(depositor(vault, &account_id), block_timestamp);
+    vault
+        .vault_deposit(DepositRequest { account_id, token: TOKEN, amount: U128(amount) })
+        .vault_shares_minted
+}
+
+fn withdraw<V: BondVault>(vault: &mut V, account_id: AccountId, vault_shares: u128, block_timestamp: u64) -> U128 {
+    set_caller(account_id, block_timestamp);
+    vault
+        .vault_withdraw(WithdrawRequest { token: TOKEN, vault_shares: U128(vault_shares) })
+        .amount
+}
+
+pub fn deposit_mints_shares<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    let supply_before = vault.vault_status().total_supply.0;
+
+    let minted = deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+
+    assert_eq!(minted.0, DEPOSIT_AMOUNT);
+    assert_eq!(vault.vault_shares(user(), TOKEN).0, DEPOSIT_AMOUNT);
+    assert_eq!(vault.vault_shares(other_user(), TOKEN).0, 0);
+    assert_eq!(vault.vault_token_reserves(TOKEN).0, DEPOSIT_AMOUNT);
+    assert_eq!(vault.vault_status().total_supply.0, supply_before + DEPOSIT_AMOUNT);
+}
+
+pub fn deposit_rejects_zero_amount<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), 0, 1);
+}
+
+pub fn deposit_rejects_unauthorized_caller<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    let caller = match vault.vault_status().deposit_mode {
+        DepositMode::TokenTransfer => user(),
+        DepositMode::Simulated => other_user(),
+    };
+    set_caller(caller, 1);
+    vault.vault_deposit(DepositRequest { account_id: user(), token: TOKEN, amount: U128(DEPOSIT_AMOUNT) });
+}
+
+pub fn withdraw_burns_shares<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+    let supply_before = vault.vault_status().total_supply.0;
+
+    let amount = withdraw(&mut vault, user(), DEPOSIT_AMOUNT / 4, 2);
+
+    assert_eq!(amount.0, DEPOSIT_AMOUNT / 4);
+    assert_eq!(vault.vault_shares(user(), TOKEN).0, DEPOSIT_AMOUNT - DEPOSIT_AMOUNT / 4);
+    assert_eq!(vault.vault_token_reserves(TOKEN).0, DEPOSIT_AMOUNT - DEPOSIT_AMOUNT / 4);
+    assert_eq!(vault.vault_status().total_supply.0, supply_before - DEPOSIT_AMOUNT / 4);
+}
+
+pub fn withdraw_rejects_zero_amount<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+    withdraw(&mut vault, user(), 0, 2);
+}
+
+pub fn withdraw_rejects_insufficient_shares<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+    withdraw(&mut vault, user(), DEPOSIT_AMOUNT + 1, 2);
+}
+
+pub fn withdraw_rejects_other_accounts_shares<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+    withdraw(&mut vault, other_user(), 1, 2);
+}
+
+pub fn pause_blocks_deposit<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    vault.vault_pause();
+    assert!(vault.vault_status().is_paused);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+}
+
+pub fn pause_blocks_withdraw<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+    set_caller(owner(), 2);
+    vault.vault_pause();
+    withdraw(&mut vault, user(), DEPOSIT_AMOUNT, 3);
+}
+
+pub fn unpause_restores_operations<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    vault.vault_pause();
+    vault.vault_unpause();
+    assert!(!vault.vault_status().is_paused);
+
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 1);
+    withdraw(&mut vault, user(), DEPOSIT_AMOUNT, 2);
+    assert_eq!(vault.vault_shares(user(), TOKEN).0, 0);
+}
+
+pub fn only_owner_can_pause<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    set_caller(user(), 1);
+    vault.vault_pause();
+}
+
+pub fn events_record_deposits_and_withdrawals<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), DEPOSIT_AMOUNT, 10);
+    withdraw(&mut vault, user(), DEPOSIT_AMOUNT / 2, 20);
+
+    let deposits = vault.vault_events(EventQuery {
+        account_id: Some(user()),
+        kind: Some(VaultEventKind::Deposit),
+        limit: None,
+    });
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].account_id, user());
+    assert_eq!(deposits[0].token, TOKEN);
+    assert_eq!(deposits[0].amount.0, DEPOSIT_AMOUNT);
+    assert_eq!(deposits[0].vault_shares.0, DEPOSIT_AMOUNT);
+    assert_eq!(deposits[0].timestamp, 10);
+
+    let withdrawals = vault.vault_events(EventQuery {
+        account_id: Some(user()),
+        kind: Some(VaultEventKind::Withdraw),
+        limit: None,
+    });
+    assert_eq!(withdrawals.len(), 1);
+    assert_eq!(withdrawals[0].amount.0, DEPOSIT_AMOUNT / 2);
+    assert_eq!(withdrawals[0].vault_shares.0, DEPOSIT_AMOUNT / 2);
+    assert_eq!(withdrawals[0].timestamp, 20);
+}
+
+pub fn events_filter_and_limit<V: BondVault>(setup: impl FnOnce(AccountId) -> V) {
+    let mut vault = init(setup);
+    deposit(&mut vault, user(), 100, 1);
+    deposit(&mut vault, other_user(), 200, 2);
+    deposit(&mut vault, user(), 300, 3);
+
+    let user_events = vault.vault_events(EventQuery { account_id: Some(user()), ..Default::default() });
+    let amounts: Vec<u128> = user_events.iter().map(|event| event.amount.0).collect();
+    assert_eq!(amounts, vec![100, 300]);
+
+    // Oldest first, keeping the most recent matches
+    let latest = vault.vault_events(EventQuery { limit: Some(2), ..Default::default() });
+    let amounts: Vec<u128> = latest.iter().map(|event| event.amount.0).collect();
+    assert_eq!(amounts, vec![200, 300]);
+}
+
+/// Expands the conformance suite into `#[test]` functions for the given setup function,
+/// checking the deposit authorization of the vault's `DepositMode` (`TokenTransfer` if omitted)
+#[macro_export]
+macro_rules! bond_vault_conformance_tests {
+    ($setup:expr) => {
+        $crate::bond_vault_conformance_tests!(@suite $setup, "Only the token contract can deposit");
+    };
+    ($setup:expr, TokenTransfer) => {
+        $crate::bond_vault_conformance_tests!(@suite $setup, "Only the token contract can deposit");
+    };
+    ($setup:expr, Simulated) => {
+        $crate::bond_vault_conformance_tests!(@suite $setup, "Only the depositing account can deposit");
+    };
+    (@suite $setup:expr, $unauthorized_deposit:literal) => {
+        mod bond_vault_conformance {
+            use super::*;
+            use $crate::conformance;
+
+            #[test]
+            fn deposit_mints_shares() {
+                conformance::deposit_mints_shares($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Amount must be greater than zero")]
+            fn deposit_rejects_zero_amount() {
+                conformance::deposit_rejects_zero_amount($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = $unauthorized_deposit)]
+            fn deposit_rejects_unauthorized_caller() {
+                conformance::deposit_rejects_unauthorized_caller($setup);
+            }
+
+            #[test]
+            fn withdraw_burns_shares() {
+                conformance::withdraw_burns_shares($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Amount must be greater than zero")]
+            fn withdraw_rejects_zero_amount() {
+                conformance::withdraw_rejects_zero_amount($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Insufficient vault shares")]
+            fn withdraw_rejects_insufficient_shares() {
+                conformance::withdraw_rejects_insufficient_shares($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Insufficient vault shares")]
+            fn withdraw_rejects_other_accounts_shares() {
+                conformance::withdraw_rejects_other_accounts_shares($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Vault is paused")]
+            fn pause_blocks_deposit() {
+                conformance::pause_blocks_deposit($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Vault is paused")]
+            fn pause_blocks_withdraw() {
+                conformance::pause_blocks_withdraw($setup);
+            }
+
+            #[test]
+            fn unpause_restores_operations() {
+                conformance::unpause_restores_operations($setup);
+            }
+
+            #[test]
+            #[should_panic(expected = "Only owner can pause vault")]
+            fn only_owner_can_pause() {
+                conformance::only_owner_can_pause($setup);
+            }
+
+            #[test]
+            fn events_record_deposits_and_withdrawals() {
+                conformance::events_record_deposits_and_withdrawals($setup);
+            }
+
+            #[test]
+            fn events_filter_and_limit() {
+                conformance::events_filter_and_limit($setup);
+            }
+        }
+    };
+}
@@ -0,0 +1,230 @@
+//! Common interface implemented by every Bond.Credit vault contract.
+//!
+//! The vault contracts grew separate method shapes (`get_deposit_events` takes
+//! `Option<u64>`, `Option<u32>` or `(account_id, u32)` depending on the vault).
+//! Every vault also exposes `BondVault` on-chain under `vault_`-prefixed methods, so
+//! integrators call one set of methods on any vault, through `ext_bond_vault` from
+//! other contracts, and tests run one suite against all of them.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{ext_contract, AccountId, Timestamp};
+
+#[cfg(feature = "conformance")]
+pub mod conformance;
+
+/// Default number of events returned when `EventQuery::limit` is not set
+pub const DEFAULT_EVENT_LIMIT: u32 = 50;
+
+/// Token accepted by a vault. Vaults that do not support a token panic with
+/// "Unsupported token" when it is used.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum VaultToken {
+    WNEAR,
+    USDC,
+    USDT,
+}
+
+/// Credit `amount` of `token` for `account_id`. Who may submit it depends on the vault's
+/// `DepositMode`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositRequest {
+    pub account_id: AccountId,
+    pub token: VaultToken,
+    pub amount: U128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DepositResponse {
+    pub vault_shares_minted: U128,
+}
+
+/// Burn `vault_shares` of `token` held by the predecessor and pay out the underlying
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WithdrawRequest {
+    pub token: VaultToken,
+    pub vault_shares: U128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WithdrawResponse {
+    pub vault_shares_burned: U128,
+    pub amount: U128,
+}
+
+/// Filter for `BondVault::vault_events`. Events come back oldest first, capped to the
+/// most recent `limit` matches.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EventQuery {
+    pub account_id: Option<AccountId>,
+    pub kind: Option<VaultEventKind>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum VaultEventKind {
+    Deposit,
+    Withdraw,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VaultEvent {
+    pub kind: VaultEventKind,
+    pub account_id: AccountId,
+    pub token: VaultToken,
+    pub amount: U128,
+    pub vault_shares: U128,
+    pub timestamp: Timestamp,
+}
+
+/// Who may submit a `vault_deposit`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DepositMode {
+    /// Only the token's contract, for tokens that already reached the vault, so shares are
+    /// never minted without a transfer
+    TokenTransfer,
+    /// Only the depositing account itself; nothing is transferred, as in paper trading vaults
+    Simulated,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VaultStatus {
+    pub owner_id: AccountId,
+    pub total_supply: U128,
+    pub is_paused: bool,
+    pub deposit_mode: DepositMode,
+}
+
+/// Operations every vault exposes on-chain, through `#[near_bindgen]` on its
+/// implementation, with the same semantics:
+///
+/// - `vault_deposit` and `vault_withdraw` panic with "Vault is paused" while paused and
+///   with "Amount must be greater than zero" for zero amounts
+/// - with `DepositMode::TokenTransfer`, `vault_deposit` panics with "Only the token contract
+///   can deposit" unless the predecessor is the contract of the requested token; users
+///   deposit with `ft_transfer_call`
+/// - with `DepositMode::Simulated`, `vault_deposit` panics with "Only the depositing account
+///   can deposit" unless the predecessor is `account_id`
+/// - `vault_withdraw` panics with "Insufficient vault shares" when the predecessor holds
+///   fewer shares than requested, including shares held only by other accounts
+/// - `vault_pause` and `vault_unpause` panic with "Only owner can pause vault" and
+///   "Only owner can unpause vault" unless called by the owner
+/// - every successful `vault_deposit` and `vault_withdraw` is visible through `vault_events`
+#[ext_contract(ext_bond_vault)]
+pub trait BondVault {
+    fn vault_status(&self) -> VaultStatus;
+
+    fn vault_token_reserves(&self, token: VaultToken) -> U128;
+
+    fn vault_shares(&self, account_id: AccountId, token: VaultToken) -> U128;
+
+    fn vault_events(&self, query: EventQuery) -> Vec<VaultEvent>;
+
+    fn vault_deposit(&mut self, request: DepositRequest) -> DepositResponse;
+
+    fn vault_withdraw(&mut self, request: WithdrawRequest) -> WithdrawResponse;
+
+    fn vault_pause(&mut self);
+
+    fn vault_unpause(&mut self);
+}
+
+/// Applies an `EventQuery` to events already in chronological order
+pub fn filter_events(events: impl Iterator<Item = VaultEvent>, query: &EventQuery) -> Vec<VaultEvent> {
+    let limit = query.limit.unwrap_or(DEFAULT_EVENT_LIMIT) as usize;
+    let mut matches: Vec<VaultEvent> = events
+        .filter(|event| query.account_id.as_ref().is_none_or(|account_id| &event.account_id == account_id))
+        .filter(|event| query.kind.as_ref().is_none_or(|kind| &event.kind == kind))
+        .collect();
+
+    let start = matches.len().saturating_sub(limit);
+    matches.drain(..start);
+    matches
+}
+
+/// Merges a vault's deposit and withdraw events into chronological order and applies an `EventQuery`
+pub fn merge_events(
+    deposits: impl Iterator<Item = VaultEvent>,
+    withdrawals: impl Iterator<Item = VaultEvent>,
+    query: &EventQuery,
+) -> Vec<VaultEvent> {
+    let mut events: Vec<VaultEvent> = deposits.chain(withdrawals).collect();
+    events.sort_by_key(|event| event.timestamp);
+    filter_events(events.into_iter(), query)
+}
+
+/// Implements the conversions between a vault's own token and event types and the interface's.
+/// The token's variants must share `VaultToken`'s names; `VaultToken`s it doesn't list panic
+/// with "Unsupported token". Both event types need `account_id`, `token_type`, `amount` and
+/// `timestamp` fields, plus `vault_shares_minted` on deposits and `vault_shares_burned` on
+/// withdrawals.
+///
+/// ```ignore
+/// bond_vault_interface::impl_vault_conversions! {
+///     token: TokenType { WNEAR, USDC, USDT },
+///     deposit_event: DepositEvent,
+///     withdraw_event: WithdrawEvent,
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_vault_conversions {
+    (
+        token: $token:ident { $($variant:ident),+ $(,)? },
+        deposit_event: $deposit:ty,
+        withdraw_event: $withdraw:ty $(,)?
+    ) => {
+        impl From<$crate::VaultToken> for $token {
+            fn from(token: $crate::VaultToken) -> Self {
+                match token {
+                    $($crate::VaultToken::$variant => $token::$variant,)+
+                    #[allow(unreachable_patterns)]
+                    _ => panic!("Unsupported token"),
+                }
+            }
+        }
+
+        impl From<$token> for $crate::VaultToken {
+            fn from(token_type: $token) -> Self {
+                match token_type {
+                    $($token::$variant => $crate::VaultToken::$variant,)+
+                }
+            }
+        }
+
+        impl From<&$deposit> for $crate::VaultEvent {
+            fn from(event: &$deposit) -> Self {
+                $crate::VaultEvent {
+                    kind: $crate::VaultEventKind::Deposit,
+                    account_id: event.account_id.clone(),
+                    token: event.token_type.clone().into(),
+                    amount: event.amount,
+                    vault_shares: event.vault_shares_minted,
+                    timestamp: event.timestamp,
+                }
+            }
+        }
+
+        impl From<&$withdraw> for $crate::VaultEvent {
+            fn from(event: &$withdraw) -> Self {
+                $crate::VaultEvent {
+                    kind: $crate::VaultEventKind::Withdraw,
+                    account_id: event.account_id.clone(),
+                    token: event.token_type.clone().into(),
+                    amount: event.amount,
+                    vault_shares: event.vault_shares_burned,
+                    timestamp: event.timestamp,
+                }
+            }
+        }
+    };
+}
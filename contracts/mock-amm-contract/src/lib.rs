@@ -0,0 +1,165 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, near_bindgen, AccountId, PanicOnDefault, require, log
+};
+use mock_utils::{impl_failure_injection, FailureInjection};
+
+// Constants
+const FEE_PRECISION: u128 = 1_000_000_000_000_000_000_000_000; // 1e24
+const BPS_DENOMINATOR: u128 = 10_000;
+
+// Data structures
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LiquidityPosition {
+    pub account_id: AccountId,
+    pub shares: U128,
+    pub unclaimed_fees: U128,
+    pub fee_per_share_paid: U128,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolInfo {
+    pub reserve: U128,
+    pub total_shares: U128,
+    pub fee_bps: u16,
+    pub total_fees: U128,
+    pub swap_volume: U128,
+}
+
+// Main contract
+/// Bookkeeping-only liquidity pool standing in for liquidity-pool.testnet. Accepts the
+/// `add_liquidity` / `claim_fees` calls made by the opportunity contracts. Fees come
+/// from simulated `swap` volume and are shared between LPs pro rata. No tokens move.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockAmmContract {
+    pub owner_id: AccountId,
+    pub fee_bps: u16,
+    pub reserve: U128,
+    pub total_shares: U128,
+    pub fee_per_share: U128, // Scaled by FEE_PRECISION
+    pub total_fees: U128,
+    pub swap_volume: U128,
+    pub positions: UnorderedMap<AccountId, LiquidityPosition>,
+    pub failures: FailureInjection,
+}
+
+#[near_bindgen]
+impl MockAmmContract {
+    #[init]
+    pub fn new(owner_id: AccountId, fee_bps: u16) -> Self {
+        require!(!env::state_exists(), "Already initialized");
+        require!(fee_bps < 10_000, "Fee must be below 10000 basis points");
+
+        Self {
+            owner_id,
+            fee_bps,
+            reserve: U128(0),
+            total_shares: U128(0),
+            fee_per_share: U128(0),
+            total_fees: U128(0),
+            swap_volume: U128(0),
+            positions: UnorderedMap::new(b"positions".to_vec()),
+            failures: FailureInjection::default(),
+        }
+    }
+
+    // View functions
+    pub fn get_pool(&self) -> PoolInfo {
+        PoolInfo {
+            reserve: self.reserve,
+            total_shares: self.total_shares,
+            fee_bps: self.fee_bps,
+            total_fees: self.total_fees,
+            swap_volume: self.swap_volume,
+        }
+    }
+
+    pub fn get_position(&self, account_id: AccountId) -> Option<LiquidityPosition> {
+        self.positions.get(&account_id).map(|position| self.settle(position))
+    }
+
+    // Liquidity
+    pub fn add_liquidity(&mut self, account_id: AccountId, amount: U128) -> U128 {
+        self.failures.assert_not_failing("add_liquidity");
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
+        let shares = if self.total_shares.0 == 0 || self.reserve.0 == 0 {
+            amount.0
+        } else {
+            amount.0 * self.total_shares.0 / self.reserve.0
+        };
+
+        let mut position = self.get_position(account_id.clone()).unwrap_or(LiquidityPosition {
+            account_id: account_id.clone(),
+            shares: U128(0),
+            unclaimed_fees: U128(0),
+            fee_per_share_paid: self.fee_per_share,
+        });
+        position.shares = U128(position.shares.0 + shares);
+        self.positions.insert(&account_id, &position);
+
+        self.reserve = U128(self.reserve.0 + amount.0);
+        self.total_shares = U128(self.total_shares.0 + shares);
+
+        log!("Added {} liquidity for {}, minted {} shares", amount.0, account_id, shares);
+        U128(shares)
+    }
+
+    pub fn remove_liquidity(&mut self, account_id: AccountId, shares: U128) -> U128 {
+        self.failures.assert_not_failing("remove_liquidity");
+
+        let mut position = self.get_position(account_id.clone()).expect("Position not found");
+        require!(shares.0 > 0 && position.shares.0 >= shares.0, "Insufficient shares");
+
+        let amount = shares.0 * self.reserve.0 / self.total_shares.0;
+        position.shares = U128(position.shares.0 - shares.0);
+        self.positions.insert(&account_id, &position);
+
+        self.reserve = U128(self.reserve.0 - amount);
+        self.total_shares = U128(self.total_shares.0 - shares.0);
+
+        log!("Removed {} liquidity for {}, burned {} shares", amount, account_id, shares.0);
+        U128(amount)
+    }
+
+    pub fn claim_fees(&mut self, account_id: AccountId) -> U128 {
+        self.failures.assert_not_failing("claim_fees");
+
+        let mut position = self.get_position(account_id.clone()).expect("Position not found");
+        let fees = position.unclaimed_fees;
+        position.unclaimed_fees = U128(0);
+        self.positions.insert(&account_id, &position);
+
+        log!("Claimed {} fees for {}", fees.0, account_id);
+        fees
+    }
+
+    /// Simulates a trade of `amount_in` through the pool, accruing the swap fee to LPs
+    pub fn swap(&mut self, amount_in: U128) -> U128 {
+        self.failures.assert_not_failing("swap");
+        require!(self.total_shares.0 > 0, "Pool has no liquidity");
+
+        let fee = amount_in.0 * self.fee_bps as u128 / BPS_DENOMINATOR;
+        self.fee_per_share = U128(self.fee_per_share.0 + fee * FEE_PRECISION / self.total_shares.0);
+        self.total_fees = U128(self.total_fees.0 + fee);
+        self.swap_volume = U128(self.swap_volume.0 + amount_in.0);
+
+        U128(amount_in.0 - fee)
+    }
+
+    // Helper functions
+    fn settle(&self, mut position: LiquidityPosition) -> LiquidityPosition {
+        let earned = position.shares.0 * (self.fee_per_share.0 - position.fee_per_share_paid.0) / FEE_PRECISION;
+        position.unclaimed_fees = U128(position.unclaimed_fees.0 + earned);
+        position.fee_per_share_paid = self.fee_per_share;
+        position
+    }
+}
+
+impl_failure_injection!(MockAmmContract);
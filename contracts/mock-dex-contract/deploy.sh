@@ -0,0 +1,48 @@
+#!/bin/bash
+
+# Mock DEX Contract Deployment Script
+set -e
+
+echo "🚀 Deploying Mock DEX Contract to NEAR Testnet..."
+
+# Configuration
+ACCOUNT_ID="mock-dex.testnet"
+CONTRACT_PATH="./target/wasm32-unknown-unknown/release/mock_dex_contract.wasm"
+
+# Check if contract is built
+if [ ! -f "$CONTRACT_PATH" ]; then
+    echo "❌ Contract not found. Building contract first..."
+    cargo build --release --target wasm32-unknown-unknown
+fi
+
+# Create account if it doesn't exist
+echo "📝 Creating account: $ACCOUNT_ID"
+near create-account $ACCOUNT_ID --masterAccount testnet --initialBalance 10
+
+# Deploy contract
+echo "📦 Deploying contract..."
+near deploy $ACCOUNT_ID $CONTRACT_PATH
+
+# Initialize contract
+echo "🔧 Initializing contract..."
+near call $ACCOUNT_ID new \
+    '{
+        "owner_id": "'$ACCOUNT_ID'"
+    }' \
+    --accountId $ACCOUNT_ID
+
+# Register a wNEAR/USDC pool at a fixed rate (1 wNEAR = 3 USDC, 0.3% fee)
+echo "🔧 Adding wNEAR/USDC pool..."
+near call $ACCOUNT_ID add_pool \
+    '{
+        "token_in": "wrap.testnet",
+        "token_out": "usdc.testnet",
+        "rate_numerator": "3000000",
+        "rate_denominator": "1000000000000000000000000",
+        "fee_bps": 30
+    }' \
+    --accountId $ACCOUNT_ID
+
+echo "✅ Mock DEX Contract deployed successfully!"
+echo "📋 Contract ID: $ACCOUNT_ID"
+echo "🌐 View on NEAR Explorer: https://explorer.testnet.near.org/accounts/$ACCOUNT_ID"
\ No newline at end of file
@@ -0,0 +1,187 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, ext_contract, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, require, log
+};
+
+// Constants
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_gas(10_000_000_000_000);
+
+// External contract interfaces
+#[allow(dead_code)]
+#[ext_contract(ext_fungible_token)]
+trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+// Data structures
+/// Fixed-rate swap pool from `token_in` to `token_out`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Pool {
+    pub id: u64,
+    pub token_in: AccountId,
+    pub token_out: AccountId,
+    pub rate_numerator: U128,
+    pub rate_denominator: U128,
+    pub fee_bps: u16,
+    pub volume_in: U128,
+}
+
+/// Ref-Finance swap action
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapAction {
+    pub pool_id: u64,
+    pub token_in: AccountId,
+    pub amount_in: Option<U128>,
+    pub token_out: AccountId,
+    pub min_amount_out: U128,
+}
+
+/// Ref-Finance instant swap message
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapMsg {
+    pub actions: Vec<SwapAction>,
+    pub swap_out_recipient: Option<AccountId>,
+}
+
+// Main contract
+/// Minimal Ref-Finance stand-in for exercising swap flows locally.
+/// Output tokens are paid from the contract's own balance, which must be pre-funded.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockDexContract {
+    pub owner_id: AccountId,
+    pub pools: UnorderedMap<u64, Pool>,
+    pub next_pool_id: u64,
+    pub fail_swaps: bool,
+}
+
+#[near_bindgen]
+impl MockDexContract {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        require!(!env::state_exists(), "Already initialized");
+
+        Self {
+            owner_id,
+            pools: UnorderedMap::new(b"pools".to_vec()),
+            next_pool_id: 0,
+            fail_swaps: false,
+        }
+    }
+
+    // View functions
+    pub fn get_pool(&self, pool_id: u64) -> Option<Pool> {
+        self.pools.get(&pool_id)
+    }
+
+    pub fn get_pools(&self) -> Vec<Pool> {
+        self.pools.values().collect()
+    }
+
+    pub fn get_return(&self, pool_id: u64, token_in: AccountId, amount_in: U128, token_out: AccountId) -> U128 {
+        let pool = self.pools.get(&pool_id).expect("Pool not found");
+        require!(pool.token_in == token_in && pool.token_out == token_out, "Token pair does not match pool");
+        U128(Self::amount_out(&pool, amount_in.0))
+    }
+
+    // Admin functions
+    pub fn add_pool(
+        &mut self,
+        token_in: AccountId,
+        token_out: AccountId,
+        rate_numerator: U128,
+        rate_denominator: U128,
+        fee_bps: u16,
+    ) -> u64 {
+        self.assert_owner();
+        require!(rate_denominator.0 > 0, "Rate denominator must be greater than zero");
+        require!(fee_bps < 10_000, "Fee must be below 10000 basis points");
+
+        let pool_id = self.next_pool_id;
+        let pool = Pool {
+            id: pool_id,
+            token_in,
+            token_out,
+            rate_numerator,
+            rate_denominator,
+            fee_bps,
+            volume_in: U128(0),
+        };
+        self.pools.insert(&pool_id, &pool);
+        self.next_pool_id += 1;
+
+        log!("Added pool {}: {} -> {}", pool_id, pool.token_in, pool.token_out);
+        pool_id
+    }
+
+    /// Makes every swap fail so callers' refund paths can be exercised
+    pub fn set_fail_swaps(&mut self, fail_swaps: bool) {
+        self.assert_owner();
+        self.fail_swaps = fail_swaps;
+        log!("Swap failure injection {}", if fail_swaps { "enabled" } else { "disabled" });
+    }
+
+    /// NEP-141 receiver executing the swap actions in `msg`. Panics on any failure
+    /// so the token contract refunds the full input.
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> U128 {
+        require!(!self.fail_swaps, "Swap failed: failure injection enabled");
+
+        let swap_msg: SwapMsg = near_sdk::serde_json::from_str(&msg)
+            .unwrap_or_else(|_| env::panic_str("Invalid swap message"));
+        require!(!swap_msg.actions.is_empty(), "No swap actions");
+
+        let mut token_in = env::predecessor_account_id();
+        let mut amount_in = amount.0;
+
+        for action in swap_msg.actions.iter() {
+            require!(action.token_in == token_in, "Action token_in does not match input");
+            if let Some(action_amount) = action.amount_in {
+                require!(action_amount.0 <= amount_in, "Action amount_in exceeds input");
+            }
+
+            let mut pool = self.pools.get(&action.pool_id).expect("Pool not found");
+            require!(
+                pool.token_in == action.token_in && pool.token_out == action.token_out,
+                "Token pair does not match pool"
+            );
+
+            let amount_out = Self::amount_out(&pool, amount_in);
+            require!(amount_out >= action.min_amount_out.0, "Slippage error");
+
+            pool.volume_in = U128(pool.volume_in.0 + amount_in);
+            self.pools.insert(&action.pool_id, &pool);
+
+            token_in = action.token_out.clone();
+            amount_in = amount_out;
+        }
+
+        let recipient = swap_msg.swap_out_recipient.unwrap_or(sender_id.clone());
+        log!("Swap by {}: sending {} {} to {}", sender_id, amount_in, token_in, recipient);
+
+        ext_fungible_token::ext(token_in)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(recipient, U128(amount_in), Some("Mock DEX swap".to_string()));
+
+        U128(0)
+    }
+
+    // Helper functions
+    fn amount_out(pool: &Pool, amount_in: u128) -> u128 {
+        let gross = amount_in * pool.rate_numerator.0 / pool.rate_denominator.0;
+        gross * (10_000 - pool.fee_bps as u128) / 10_000
+    }
+
+    fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can call this function"
+        );
+    }
+}
@@ -0,0 +1,249 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, ext_contract, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseOrValue,
+    PromiseResult, require
+};
+use mock_utils::{assert_owner, impl_failure_injection, FailureInjection};
+
+// Constants
+const GAS_FOR_FT_ON_TRANSFER: Gas = Gas::from_gas(50_000_000_000_000);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_gas(10_000_000_000_000);
+
+// External contract interfaces
+#[allow(dead_code)]
+#[ext_contract(ext_ft_receiver)]
+trait FungibleTokenReceiver {
+    fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtSelf {
+    fn ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128;
+}
+
+// Data structures
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FungibleTokenMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+// Main contract
+/// NEP-141 token for local testing. Stands in for wrap.testnet (`near_deposit` /
+/// `near_withdraw`) and usdc.testnet, with owner minting and a public faucet.
+/// Storage registration is not enforced.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockFtContract {
+    pub owner_id: AccountId,
+    pub metadata: FungibleTokenMetadata,
+    pub balances: LookupMap<AccountId, u128>,
+    pub total_supply: u128,
+    pub faucet_limit: U128,
+    pub failures: FailureInjection,
+}
+
+#[near_bindgen]
+impl MockFtContract {
+    #[init]
+    pub fn new(owner_id: AccountId, name: String, symbol: String, decimals: u8, faucet_limit: U128) -> Self {
+        require!(!env::state_exists(), "Already initialized");
+
+        Self {
+            owner_id,
+            metadata: FungibleTokenMetadata {
+                spec: "ft-1.0.0".to_string(),
+                name,
+                symbol,
+                decimals,
+            },
+            balances: LookupMap::new(b"balances".to_vec()),
+            total_supply: 0,
+            faucet_limit,
+            failures: FailureInjection::default(),
+        }
+    }
+
+    // NEP-141 views
+    pub fn ft_total_supply(&self) -> U128 {
+        U128(self.total_supply)
+    }
+
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).unwrap_or(0))
+    }
+
+    pub fn ft_metadata(&self) -> FungibleTokenMetadata {
+        self.metadata.clone()
+    }
+
+    // NEP-141 transfers
+    #[payable]
+    pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        self.failures.assert_not_failing("ft_transfer");
+        require!(env::attached_deposit() == NearToken::from_yoctonear(1), "Requires attached deposit of exactly 1 yoctoNEAR");
+
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0, memo);
+    }
+
+    #[payable]
+    pub fn ft_transfer_call(
+        &mut self,
+        receiver_id: AccountId,
+        amount: U128,
+        memo: Option<String>,
+        msg: String,
+    ) -> Promise {
+        self.failures.assert_not_failing("ft_transfer_call");
+        require!(env::attached_deposit() == NearToken::from_yoctonear(1), "Requires attached deposit of exactly 1 yoctoNEAR");
+
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0, memo);
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_FT_ON_TRANSFER)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount)
+            )
+    }
+
+    /// Refunds whatever the receiver did not use. Returns the amount kept by the receiver.
+    #[private]
+    pub fn ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128 {
+        let unused_amount = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value)
+                .map(|unused| unused.0.min(amount.0))
+                .unwrap_or(amount.0),
+            _ => amount.0,
+        };
+
+        if unused_amount > 0 {
+            let receiver_balance = self.balances.get(&receiver_id).unwrap_or(0);
+            let refund = unused_amount.min(receiver_balance);
+            if refund > 0 {
+                self.internal_transfer(&receiver_id, &sender_id, refund, Some("Refund".to_string()));
+            }
+            return U128(amount.0 - refund);
+        }
+
+        amount
+    }
+
+    // NEP-145 storage stubs, registration is free
+    #[payable]
+    pub fn storage_deposit(&mut self, account_id: Option<AccountId>, registration_only: Option<bool>) -> StorageBalance {
+        let _ = (account_id, registration_only);
+        let attached = env::attached_deposit();
+        if !attached.is_zero() {
+            Promise::new(env::predecessor_account_id()).transfer(attached);
+        }
+        StorageBalance { total: U128(0), available: U128(0) }
+    }
+
+    pub fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        let _ = account_id;
+        Some(StorageBalance { total: U128(0), available: U128(0) })
+    }
+
+    // wNEAR interface
+    #[payable]
+    pub fn near_deposit(&mut self) {
+        self.failures.assert_not_failing("near_deposit");
+
+        let amount = env::attached_deposit().as_yoctonear();
+        require!(amount > 0, "Requires positive attached deposit");
+        self.internal_mint(&env::predecessor_account_id(), amount);
+    }
+
+    #[payable]
+    pub fn near_withdraw(&mut self, amount: U128) -> Promise {
+        self.failures.assert_not_failing("near_withdraw");
+        require!(env::attached_deposit() == NearToken::from_yoctonear(1), "Requires attached deposit of exactly 1 yoctoNEAR");
+
+        let account_id = env::predecessor_account_id();
+        self.internal_burn(&account_id, amount.0);
+        Promise::new(account_id).transfer(NearToken::from_yoctonear(amount.0))
+    }
+
+    // Test helpers
+    pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+        assert_owner(&self.owner_id);
+        self.internal_mint(&account_id, amount.0);
+    }
+
+    /// Public faucet, up to `faucet_limit` per call
+    pub fn faucet(&mut self, amount: Option<U128>) -> U128 {
+        self.failures.assert_not_failing("faucet");
+
+        let amount = amount.unwrap_or(self.faucet_limit);
+        require!(amount.0 <= self.faucet_limit.0, "Faucet amount exceeds limit");
+        self.internal_mint(&env::predecessor_account_id(), amount.0);
+        amount
+    }
+
+    // Helper functions
+    fn internal_transfer(&mut self, sender_id: &AccountId, receiver_id: &AccountId, amount: u128, memo: Option<String>) {
+        require!(sender_id != receiver_id, "Sender and receiver should be different");
+        require!(amount > 0, "The amount should be a positive number");
+
+        let sender_balance = self.balances.get(sender_id).unwrap_or(0);
+        require!(sender_balance >= amount, "The account doesn't have enough balance");
+        self.balances.insert(sender_id, &(sender_balance - amount));
+
+        let receiver_balance = self.balances.get(receiver_id).unwrap_or(0);
+        self.balances.insert(receiver_id, &(receiver_balance + amount));
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep141\",\"version\":\"1.0.0\",\"event\":\"ft_transfer\",\"data\":[{{\"old_owner_id\":\"{}\",\"new_owner_id\":\"{}\",\"amount\":\"{}\"{}}}]}}",
+            sender_id,
+            receiver_id,
+            amount,
+            memo.map_or(String::new(), |memo| format!(",\"memo\":\"{}\"", memo))
+        ));
+    }
+
+    fn internal_mint(&mut self, account_id: &AccountId, amount: u128) {
+        let balance = self.balances.get(account_id).unwrap_or(0);
+        self.balances.insert(account_id, &(balance + amount));
+        self.total_supply += amount;
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep141\",\"version\":\"1.0.0\",\"event\":\"ft_mint\",\"data\":[{{\"owner_id\":\"{}\",\"amount\":\"{}\"}}]}}",
+            account_id,
+            amount
+        ));
+    }
+
+    fn internal_burn(&mut self, account_id: &AccountId, amount: u128) {
+        let balance = self.balances.get(account_id).unwrap_or(0);
+        require!(balance >= amount, "The account doesn't have enough balance");
+        self.balances.insert(account_id, &(balance - amount));
+        self.total_supply -= amount;
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"nep141\",\"version\":\"1.0.0\",\"event\":\"ft_burn\",\"data\":[{{\"owner_id\":\"{}\",\"amount\":\"{}\"}}]}}",
+            account_id,
+            amount
+        ));
+    }
+}
+
+impl_failure_injection!(MockFtContract);
@@ -0,0 +1,171 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, near_bindgen, AccountId, PanicOnDefault, Timestamp, require, log
+};
+use mock_utils::{assert_owner, impl_failure_injection, FailureInjection};
+
+// Constants
+const NANOS_PER_YEAR: u128 = 365 * 24 * 60 * 60 * 1_000_000_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+
+// Data structures
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Market {
+    pub token: String,
+    pub supply_apy: u16, // Basis points
+    pub total_supplied: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SupplyPosition {
+    pub account_id: AccountId,
+    pub token: String,
+    pub supplied: U128,
+    pub unclaimed_rewards: U128,
+    pub last_update: Timestamp,
+}
+
+// Main contract
+/// Bookkeeping-only lending market standing in for lending-protocol.testnet. Accepts
+/// the `supply` / `claim_rewards` calls made by the opportunity contracts and accrues
+/// interest per market at a fixed supply APY. No tokens move.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockLendingContract {
+    pub owner_id: AccountId,
+    pub markets: UnorderedMap<String, Market>,
+    pub positions: UnorderedMap<(AccountId, String), SupplyPosition>,
+    pub failures: FailureInjection,
+}
+
+#[near_bindgen]
+impl MockLendingContract {
+    #[init]
+    pub fn new(owner_id: AccountId) -> Self {
+        require!(!env::state_exists(), "Already initialized");
+
+        Self {
+            owner_id,
+            markets: UnorderedMap::new(b"markets".to_vec()),
+            positions: UnorderedMap::new(b"positions".to_vec()),
+            failures: FailureInjection::default(),
+        }
+    }
+
+    // View functions
+    pub fn get_market(&self, token: String) -> Option<Market> {
+        self.markets.get(&token)
+    }
+
+    pub fn get_markets(&self) -> Vec<Market> {
+        self.markets.values().collect()
+    }
+
+    pub fn get_position(&self, account_id: AccountId, token: String) -> Option<SupplyPosition> {
+        self.positions.get(&(account_id, token)).map(|position| self.accrue(position))
+    }
+
+    pub fn get_pending_rewards(&self, account_id: AccountId) -> U128 {
+        U128(
+            self.account_positions(&account_id)
+                .iter()
+                .map(|position| position.unclaimed_rewards.0)
+                .sum(),
+        )
+    }
+
+    // Lending
+    pub fn supply(&mut self, account_id: AccountId, amount: U128, token: String) -> U128 {
+        self.failures.assert_not_failing("supply");
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
+        let mut market = self.markets.get(&token).expect("Market not found");
+        let mut position = self.get_position(account_id.clone(), token.clone()).unwrap_or(SupplyPosition {
+            account_id: account_id.clone(),
+            token: token.clone(),
+            supplied: U128(0),
+            unclaimed_rewards: U128(0),
+            last_update: env::block_timestamp(),
+        });
+
+        position.supplied = U128(position.supplied.0 + amount.0);
+        market.total_supplied = U128(market.total_supplied.0 + amount.0);
+        self.positions.insert(&(account_id.clone(), token.clone()), &position);
+        self.markets.insert(&token, &market);
+
+        log!("Supplied {} {} for {}", amount.0, token, account_id);
+        position.supplied
+    }
+
+    pub fn withdraw(&mut self, account_id: AccountId, amount: U128, token: String) -> U128 {
+        self.failures.assert_not_failing("withdraw");
+
+        let mut market = self.markets.get(&token).expect("Market not found");
+        let mut position = self.get_position(account_id.clone(), token.clone()).expect("Position not found");
+        require!(position.supplied.0 >= amount.0, "Insufficient supplied balance");
+
+        position.supplied = U128(position.supplied.0 - amount.0);
+        market.total_supplied = U128(market.total_supplied.0 - amount.0);
+        self.positions.insert(&(account_id.clone(), token.clone()), &position);
+        self.markets.insert(&token, &market);
+
+        log!("Withdrew {} {} for {}", amount.0, token, account_id);
+        position.supplied
+    }
+
+    /// Claims interest across every market the account supplies to
+    pub fn claim_rewards(&mut self, account_id: AccountId) -> U128 {
+        self.failures.assert_not_failing("claim_rewards");
+
+        let mut rewards = 0u128;
+        for mut position in self.account_positions(&account_id) {
+            rewards += position.unclaimed_rewards.0;
+            position.unclaimed_rewards = U128(0);
+            self.positions.insert(&(account_id.clone(), position.token.clone()), &position);
+        }
+
+        log!("Claimed {} lending rewards for {}", rewards, account_id);
+        U128(rewards)
+    }
+
+    // Admin functions
+    pub fn set_market(&mut self, token: String, supply_apy: u16) {
+        assert_owner(&self.owner_id);
+
+        let market = match self.markets.get(&token) {
+            Some(mut market) => {
+                market.supply_apy = supply_apy;
+                market
+            }
+            None => Market { token: token.clone(), supply_apy, total_supplied: U128(0) },
+        };
+        self.markets.insert(&token, &market);
+        log!("Market {} supply APY set to {} bps", token, supply_apy);
+    }
+
+    // Helper functions
+    fn account_positions(&self, account_id: &AccountId) -> Vec<SupplyPosition> {
+        self.markets
+            .keys()
+            .filter_map(|token| self.get_position(account_id.clone(), token))
+            .collect()
+    }
+
+    fn accrue(&self, mut position: SupplyPosition) -> SupplyPosition {
+        let supply_apy = self.markets.get(&position.token).map_or(0, |market| market.supply_apy as u128);
+        let now = env::block_timestamp();
+        let elapsed = now.saturating_sub(position.last_update) as u128;
+        let earned = position.supplied.0 * supply_apy * elapsed / (BPS_DENOMINATOR * NANOS_PER_YEAR);
+
+        position.unclaimed_rewards = U128(position.unclaimed_rewards.0 + earned);
+        position.last_update = now;
+        position
+    }
+}
+
+impl_failure_injection!(MockLendingContract);
@@ -0,0 +1,133 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{
+    env, near_bindgen, AccountId, PanicOnDefault, Timestamp, require, log
+};
+use mock_utils::{assert_owner, impl_failure_injection, FailureInjection};
+
+// Constants
+const NANOS_PER_YEAR: u128 = 365 * 24 * 60 * 60 * 1_000_000_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+
+// Data structures
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeAccount {
+    pub account_id: AccountId,
+    pub staked_balance: U128,
+    pub unclaimed_rewards: U128,
+    pub last_update: Timestamp,
+}
+
+// Main contract
+/// Bookkeeping-only staking pool standing in for staking-pool.testnet. Accepts the
+/// `stake` / `claim_rewards` calls made by the opportunity contracts and accrues
+/// rewards at a fixed APY. No tokens move.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockStakingPoolContract {
+    pub owner_id: AccountId,
+    pub reward_apy: u16, // Basis points
+    pub accounts: UnorderedMap<AccountId, StakeAccount>,
+    pub total_staked: U128,
+    pub failures: FailureInjection,
+}
+
+#[near_bindgen]
+impl MockStakingPoolContract {
+    #[init]
+    pub fn new(owner_id: AccountId, reward_apy: u16) -> Self {
+        require!(!env::state_exists(), "Already initialized");
+
+        Self {
+            owner_id,
+            reward_apy,
+            accounts: UnorderedMap::new(b"accounts".to_vec()),
+            total_staked: U128(0),
+            failures: FailureInjection::default(),
+        }
+    }
+
+    // View functions
+    pub fn get_account(&self, account_id: AccountId) -> Option<StakeAccount> {
+        self.accounts.get(&account_id).map(|account| self.accrue(account))
+    }
+
+    pub fn get_account_staked_balance(&self, account_id: AccountId) -> U128 {
+        self.accounts.get(&account_id).map_or(U128(0), |account| account.staked_balance)
+    }
+
+    pub fn get_pending_rewards(&self, account_id: AccountId) -> U128 {
+        self.get_account(account_id).map_or(U128(0), |account| account.unclaimed_rewards)
+    }
+
+    pub fn get_total_staked(&self) -> U128 {
+        self.total_staked
+    }
+
+    // Staking
+    pub fn stake(&mut self, account_id: AccountId, amount: U128) -> U128 {
+        self.failures.assert_not_failing("stake");
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
+        let mut account = self.get_account(account_id.clone()).unwrap_or(StakeAccount {
+            account_id: account_id.clone(),
+            staked_balance: U128(0),
+            unclaimed_rewards: U128(0),
+            last_update: env::block_timestamp(),
+        });
+        account.staked_balance = U128(account.staked_balance.0 + amount.0);
+        self.accounts.insert(&account_id, &account);
+        self.total_staked = U128(self.total_staked.0 + amount.0);
+
+        log!("Staked {} for {}", amount.0, account_id);
+        account.staked_balance
+    }
+
+    pub fn unstake(&mut self, account_id: AccountId, amount: U128) -> U128 {
+        self.failures.assert_not_failing("unstake");
+
+        let mut account = self.get_account(account_id.clone()).expect("Account not found");
+        require!(account.staked_balance.0 >= amount.0, "Insufficient staked balance");
+        account.staked_balance = U128(account.staked_balance.0 - amount.0);
+        self.accounts.insert(&account_id, &account);
+        self.total_staked = U128(self.total_staked.0 - amount.0);
+
+        log!("Unstaked {} for {}", amount.0, account_id);
+        account.staked_balance
+    }
+
+    pub fn claim_rewards(&mut self, account_id: AccountId) -> U128 {
+        self.failures.assert_not_failing("claim_rewards");
+
+        let mut account = self.get_account(account_id.clone()).expect("Account not found");
+        let rewards = account.unclaimed_rewards;
+        account.unclaimed_rewards = U128(0);
+        self.accounts.insert(&account_id, &account);
+
+        log!("Claimed {} staking rewards for {}", rewards.0, account_id);
+        rewards
+    }
+
+    // Admin functions
+    pub fn set_reward_apy(&mut self, reward_apy: u16) {
+        assert_owner(&self.owner_id);
+        self.reward_apy = reward_apy;
+        log!("Reward APY set to {} bps", reward_apy);
+    }
+
+    // Helper functions
+    fn accrue(&self, mut account: StakeAccount) -> StakeAccount {
+        let now = env::block_timestamp();
+        let elapsed = now.saturating_sub(account.last_update) as u128;
+        let earned = account.staked_balance.0 * self.reward_apy as u128 * elapsed / (BPS_DENOMINATOR * NANOS_PER_YEAR);
+
+        account.unclaimed_rewards = U128(account.unclaimed_rewards.0 + earned);
+        account.last_update = now;
+        account
+    }
+}
+
+impl_failure_injection!(MockStakingPoolContract);
@@ -0,0 +1,66 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedSet;
+use near_sdk::{env, log, require, AccountId};
+
+/// Methods of a mock contract set to panic so callers' failure paths can be exercised
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FailureInjection {
+    failing_methods: UnorderedSet<String>,
+}
+
+impl Default for FailureInjection {
+    fn default() -> Self {
+        Self {
+            failing_methods: UnorderedSet::new(b"failing_methods".to_vec()),
+        }
+    }
+}
+
+impl FailureInjection {
+    pub fn failing_methods(&self) -> Vec<String> {
+        self.failing_methods.to_vec()
+    }
+
+    pub fn set(&mut self, method_name: String, enabled: bool) {
+        if enabled {
+            self.failing_methods.insert(&method_name);
+        } else {
+            self.failing_methods.remove(&method_name);
+        }
+        log!("Failure injection for {} {}", method_name, if enabled { "enabled" } else { "disabled" });
+    }
+
+    /// Panics with "Injected failure: <method_name>" while the method is set to fail
+    pub fn assert_not_failing(&self, method_name: &str) {
+        if self.failing_methods.contains(&method_name.to_string()) {
+            env::panic_str(&format!("Injected failure: {}", method_name));
+        }
+    }
+}
+
+pub fn assert_owner(owner_id: &AccountId) {
+    require!(
+        env::predecessor_account_id() == *owner_id,
+        "Only owner can call this function"
+    );
+}
+
+/// Adds the owner-only `set_failure` and the `get_failing_methods` view to a mock
+/// contract with `owner_id` and `failures: FailureInjection` fields
+#[macro_export]
+macro_rules! impl_failure_injection {
+    ($contract:ident) => {
+        #[near_sdk::near_bindgen]
+        impl $contract {
+            pub fn get_failing_methods(&self) -> Vec<String> {
+                self.failures.failing_methods()
+            }
+
+            /// Makes `method_name` panic so callers' failure paths can be exercised
+            pub fn set_failure(&mut self, method_name: String, enabled: bool) {
+                $crate::assert_owner(&self.owner_id);
+                self.failures.set(method_name, enabled);
+            }
+        }
+    };
+}
@@ -27,6 +27,13 @@ declare -A OPPORTUNITIES=(
     ["liquidity"]="Liquidity Provision Pool:Provide liquidity for trading fees:LiquidityProvision:1500:20000000000000000000000000:100000000000000000000000000:1000000000000000000000000"
 )
 
+# Protocol contract each strategy calls
+declare -A PROTOCOL_CONTRACTS=(
+    ["staking"]="staking-pool.testnet"
+    ["lending"]="lending-protocol.testnet"
+    ["liquidity"]="liquidity-pool.testnet"
+)
+
 # Check if NEAR CLI is installed
 if ! command -v near &> /dev/null; then
     echo -e "${RED}❌ NEAR CLI not found. Please install it first:${NC}"
@@ -110,7 +117,8 @@ for opportunity_key in "${!OPPORTUNITIES[@]}"; do
             "target_apy": '$target_apy',
             "max_allocation": "'$max_allocation'",
             "total_capacity": "'$total_capacity'",
-            "min_allocation": "'$min_allocation'"
+            "min_allocation": "'$min_allocation'",
+            "protocol_contract": "'${PROTOCOL_CONTRACTS[$opportunity_key]}'"
         }' \
         --accountId $OWNER_ID \
         --networkId $NETWORK
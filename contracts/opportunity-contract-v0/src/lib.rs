@@ -1,22 +1,22 @@
+// The constructor mirrors the JSON API one field per argument
+#![allow(clippy::too_many_arguments)]
+
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::{UnorderedMap, Vector};
-use near_sdk::json_types::{U128, U64};
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, log, near_bindgen, AccountId, Balance, Gas, PanicOnDefault, Promise, PromiseResult,
-    PublicKey, Timestamp,
+    env, log, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseResult,
+    Timestamp,
 };
 
 // Gas constants
-const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000);
-const GAS_FOR_STAKING_CALL: Gas = Gas(50_000_000_000_000);
-const GAS_FOR_LENDING_CALL: Gas = Gas(30_000_000_000_000);
+const GAS_FOR_STAKING_CALL: Gas = Gas::from_gas(50_000_000_000_000);
+const GAS_FOR_LENDING_CALL: Gas = Gas::from_gas(30_000_000_000_000);
+const GAS_FOR_CALLBACK: Gas = Gas::from_gas(20_000_000_000_000);
 
 // Storage keys
 const STORAGE_KEY_ALLOCATIONS: &[u8] = b"allocations";
-const STORAGE_KEY_CAPITAL_ALLOCATED_EVENTS: &[u8] = b"capital_allocated_events";
-const STORAGE_KEY_YIELD_CLAIMED_EVENTS: &[u8] = b"yield_claimed_events";
 
 /// Yield strategy types
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -62,6 +62,7 @@ pub struct OpportunityConfig {
     pub min_allocation: U128, // Minimum allocation
     pub is_active: bool,
     pub created_at: Timestamp,
+    pub protocol_contract: AccountId, // Staking pool, lending market or liquidity pool the strategy calls
 }
 
 /// User allocation information
@@ -77,7 +78,7 @@ pub struct UserAllocation {
 }
 
 /// Capital allocated event
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct CapitalAllocatedEvent {
     pub account_id: AccountId,
@@ -85,11 +86,11 @@ pub struct CapitalAllocatedEvent {
     pub amount: U128,
     pub intent_hash: String, // NEAR Intent transaction hash
     pub timestamp: Timestamp,
-    pub tx_hash: String,
+    pub tx_hash: String, // Block height; near-sdk 5 exposes no block or transaction hash
 }
 
 /// Yield claimed event
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct YieldClaimedEvent {
     pub account_id: AccountId,
@@ -97,7 +98,7 @@ pub struct YieldClaimedEvent {
     pub yield_amount: U128,
     pub intent_hash: String, // NEAR Intent transaction hash
     pub timestamp: Timestamp,
-    pub tx_hash: String,
+    pub tx_hash: String, // Block height; near-sdk 5 exposes no block or transaction hash
 }
 
 /// NEAR Intent execution result
@@ -123,9 +124,9 @@ pub struct OpportunityContract {
     /// User allocations
     pub allocations: UnorderedMap<AccountId, UserAllocation>,
     /// Capital allocated events
-    pub capital_allocated_events: Vector<CapitalAllocatedEvent>,
+    pub capital_allocated_events: Vec<CapitalAllocatedEvent>,
     /// Yield claimed events
-    pub yield_claimed_events: Vector<YieldClaimedEvent>,
+    pub yield_claimed_events: Vec<YieldClaimedEvent>,
     /// Intent execution results
     pub intent_execution_results: Vector<IntentExecutionResult>,
 }
@@ -143,6 +144,7 @@ impl OpportunityContract {
         max_allocation: U128,
         total_capacity: U128,
         min_allocation: U128,
+        protocol_contract: AccountId,
     ) -> Self {
         assert!(!env::state_exists(), "Already initialized");
         
@@ -157,15 +159,16 @@ impl OpportunityContract {
             min_allocation,
             is_active: true,
             created_at: env::block_timestamp(),
+            protocol_contract,
         };
 
         Self {
             config,
             total_allocated: U128(0),
             allocations: UnorderedMap::new(STORAGE_KEY_ALLOCATIONS),
-            capital_allocated_events: Vector::new(STORAGE_KEY_CAPITAL_ALLOCATED_EVENTS),
-            yield_claimed_events: Vector::new(STORAGE_KEY_YIELD_CLAIMED_EVENTS),
-            intent_execution_results: Vector::new(b"intent_execution_results"),
+            capital_allocated_events: Vec::new(),
+            yield_claimed_events: Vec::new(),
+            intent_execution_results: Vector::new(b"intent_execution_results".to_vec()),
         }
     }
 
@@ -191,7 +194,7 @@ impl OpportunityContract {
 
     /// Get total participants
     pub fn get_total_participants(&self) -> u32 {
-        self.allocations.len()
+        self.allocations.len() as u32
     }
 
     /// Get active participants
@@ -248,55 +251,55 @@ impl OpportunityContract {
     fn execute_intent_for_allocation(&mut self, account_id: AccountId, amount: U128) -> Promise {
         let intent_hash = self.generate_intent_hash(&account_id, &amount);
         
-        match self.config.strategy {
+        let intent = match self.config.strategy {
             YieldStrategy::Staking => {
                 // Execute staking intent
-                self.execute_staking_intent(account_id, amount, intent_hash)
+                self.execute_staking_intent(account_id.clone(), amount)
             }
             YieldStrategy::Lending => {
                 // Execute lending intent
-                self.execute_lending_intent(account_id, amount, intent_hash)
+                self.execute_lending_intent(account_id.clone(), amount)
             }
             YieldStrategy::LiquidityProvision => {
                 // Execute liquidity provision intent
-                self.execute_liquidity_intent(account_id, amount, intent_hash)
+                self.execute_liquidity_intent(account_id.clone(), amount)
             }
-        }
+        };
+
+        intent.then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_CALLBACK)
+                .on_intent_executed(account_id, amount, intent_hash),
+        )
     }
 
     /// Execute staking intent (stake wNEAR)
-    fn execute_staking_intent(&mut self, account_id: AccountId, amount: U128, intent_hash: String) -> Promise {
+    fn execute_staking_intent(&mut self, account_id: AccountId, amount: U128) -> Promise {
         // For v0, we'll simulate staking by calling a mock staking contract
         // In production, this would integrate with real NEAR staking pools
         
         log!("Executing staking intent: {} wNEAR for {}", amount.0, account_id);
         
-        // Simulate staking contract call
-        let staking_contract = "staking-pool.testnet".parse::<AccountId>().unwrap();
-        
-        Promise::new(staking_contract)
+        Promise::new(self.config.protocol_contract.clone())
             .function_call(
                 "stake".to_string(),
                 serde_json::to_vec(&serde_json::json!({
                     "account_id": account_id,
                     "amount": amount.0.to_string()
                 })).unwrap(),
-                0, // No attached deposit for now
+                NearToken::from_yoctonear(0), // No attached deposit for now
                 GAS_FOR_STAKING_CALL,
             )
     }
 
     /// Execute lending intent (lend USDC)
-    fn execute_lending_intent(&mut self, account_id: AccountId, amount: U128, intent_hash: String) -> Promise {
+    fn execute_lending_intent(&mut self, account_id: AccountId, amount: U128) -> Promise {
         // For v0, we'll simulate lending by calling a mock lending protocol
         // In production, this would integrate with real lending protocols like Burrow
         
         log!("Executing lending intent: {} USDC for {}", amount.0, account_id);
         
-        // Simulate lending contract call
-        let lending_contract = "lending-protocol.testnet".parse::<AccountId>().unwrap();
-        
-        Promise::new(lending_contract)
+        Promise::new(self.config.protocol_contract.clone())
             .function_call(
                 "supply".to_string(),
                 serde_json::to_vec(&serde_json::json!({
@@ -304,29 +307,26 @@ impl OpportunityContract {
                     "amount": amount.0.to_string(),
                     "token": "USDC"
                 })).unwrap(),
-                0, // No attached deposit for now
+                NearToken::from_yoctonear(0), // No attached deposit for now
                 GAS_FOR_LENDING_CALL,
             )
     }
 
     /// Execute liquidity provision intent
-    fn execute_liquidity_intent(&mut self, account_id: AccountId, amount: U128, intent_hash: String) -> Promise {
+    fn execute_liquidity_intent(&mut self, account_id: AccountId, amount: U128) -> Promise {
         // For v0, we'll simulate liquidity provision
         // In production, this would integrate with real DEX protocols
         
         log!("Executing liquidity intent: {} tokens for {}", amount.0, account_id);
         
-        // Simulate liquidity contract call
-        let liquidity_contract = "liquidity-pool.testnet".parse::<AccountId>().unwrap();
-        
-        Promise::new(liquidity_contract)
+        Promise::new(self.config.protocol_contract.clone())
             .function_call(
                 "add_liquidity".to_string(),
                 serde_json::to_vec(&serde_json::json!({
                     "account_id": account_id,
                     "amount": amount.0.to_string()
                 })).unwrap(),
-                0, // No attached deposit for now
+                NearToken::from_yoctonear(0), // No attached deposit for now
                 GAS_FOR_LENDING_CALL,
             )
     }
@@ -338,8 +338,8 @@ impl OpportunityContract {
         account_id: AccountId,
         amount: U128,
         intent_hash: String,
-        success: bool,
     ) {
+        let success = matches!(env::promise_result(0), PromiseResult::Successful(_));
         let execution_result = IntentExecutionResult {
             intent_hash: intent_hash.clone(),
             success,
@@ -359,10 +359,10 @@ impl OpportunityContract {
                 amount,
                 intent_hash,
                 timestamp: env::block_timestamp(),
-                tx_hash: env::block_hash().to_string(),
+                tx_hash: env::block_height().to_string(),
             };
 
-            self.capital_allocated_events.push(&capital_event);
+            self.capital_allocated_events.push(capital_event.clone());
             
             // Limit events to last 1000
             if self.capital_allocated_events.len() > 1000 {
@@ -406,7 +406,11 @@ impl OpportunityContract {
 
         // Execute yield claim intent
         let intent_hash = self.generate_yield_intent_hash(&sender_id, &yield_amount);
-        self.execute_yield_claim_intent(sender_id, yield_amount, intent_hash)
+        self.execute_yield_claim_intent(sender_id.clone()).then(
+            Self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_CALLBACK)
+                .on_yield_claimed(sender_id, yield_amount, intent_hash),
+        )
     }
 
     /// Calculate yield for an allocation
@@ -422,50 +426,47 @@ impl OpportunityContract {
 
         // Calculate yield based on APY
         let daily_rate = (self.config.target_apy as u128) * 100 / 36500; // Convert basis points to daily rate
-        let yield_amount = (allocation.allocated_amount.0 * daily_rate * days_elapsed) / 10000;
+        let yield_amount = (allocation.allocated_amount.0 * daily_rate * days_elapsed as u128) / 10000;
         
         U128(yield_amount)
     }
 
     /// Execute yield claim intent
-    fn execute_yield_claim_intent(&mut self, account_id: AccountId, yield_amount: U128, intent_hash: String) -> Promise {
+    fn execute_yield_claim_intent(&mut self, account_id: AccountId) -> Promise {
         match self.config.strategy {
             YieldStrategy::Staking => {
                 // Claim staking rewards
-                let staking_contract = "staking-pool.testnet".parse::<AccountId>().unwrap();
-                Promise::new(staking_contract)
+                Promise::new(self.config.protocol_contract.clone())
                     .function_call(
                         "claim_rewards".to_string(),
                         serde_json::to_vec(&serde_json::json!({
                             "account_id": account_id
                         })).unwrap(),
-                        0,
+                        NearToken::from_yoctonear(0),
                         GAS_FOR_STAKING_CALL,
                     )
             }
             YieldStrategy::Lending => {
                 // Claim lending rewards
-                let lending_contract = "lending-protocol.testnet".parse::<AccountId>().unwrap();
-                Promise::new(lending_contract)
+                Promise::new(self.config.protocol_contract.clone())
                     .function_call(
                         "claim_rewards".to_string(),
                         serde_json::to_vec(&serde_json::json!({
                             "account_id": account_id
                         })).unwrap(),
-                        0,
+                        NearToken::from_yoctonear(0),
                         GAS_FOR_LENDING_CALL,
                     )
             }
             YieldStrategy::LiquidityProvision => {
                 // Claim liquidity rewards
-                let liquidity_contract = "liquidity-pool.testnet".parse::<AccountId>().unwrap();
-                Promise::new(liquidity_contract)
+                Promise::new(self.config.protocol_contract.clone())
                     .function_call(
                         "claim_fees".to_string(),
                         serde_json::to_vec(&serde_json::json!({
                             "account_id": account_id
                         })).unwrap(),
-                        0,
+                        NearToken::from_yoctonear(0),
                         GAS_FOR_LENDING_CALL,
                     )
             }
@@ -479,8 +480,8 @@ impl OpportunityContract {
         account_id: AccountId,
         yield_amount: U128,
         intent_hash: String,
-        success: bool,
     ) {
+        let success = matches!(env::promise_result(0), PromiseResult::Successful(_));
         if success {
             // Update user allocation
             if let Some(mut allocation) = self.allocations.get(&account_id) {
@@ -496,10 +497,10 @@ impl OpportunityContract {
                 yield_amount,
                 intent_hash,
                 timestamp: env::block_timestamp(),
-                tx_hash: env::block_hash().to_string(),
+                tx_hash: env::block_height().to_string(),
             };
 
-            self.yield_claimed_events.push(&yield_event);
+            self.yield_claimed_events.push(yield_event.clone());
             
             // Limit events to last 1000
             if self.yield_claimed_events.len() > 1000 {
@@ -524,47 +525,31 @@ impl OpportunityContract {
 
     /// Get capital allocated events
     pub fn get_capital_allocated_events(&self, limit: Option<u32>) -> Vec<CapitalAllocatedEvent> {
-        let limit = limit.unwrap_or(50);
-        let mut events = Vec::new();
-        
+        let limit = limit.unwrap_or(50) as usize;
         let start = if self.capital_allocated_events.len() > limit {
             self.capital_allocated_events.len() - limit
         } else {
             0
         };
         
-        for i in start..self.capital_allocated_events.len() {
-            if let Some(event) = self.capital_allocated_events.get(i) {
-                events.push(event);
-            }
-        }
-        
-        events
+        self.capital_allocated_events[start..].to_vec()
     }
 
     /// Get yield claimed events
     pub fn get_yield_claimed_events(&self, limit: Option<u32>) -> Vec<YieldClaimedEvent> {
-        let limit = limit.unwrap_or(50);
-        let mut events = Vec::new();
-        
+        let limit = limit.unwrap_or(50) as usize;
         let start = if self.yield_claimed_events.len() > limit {
             self.yield_claimed_events.len() - limit
         } else {
             0
         };
         
-        for i in start..self.yield_claimed_events.len() {
-            if let Some(event) = self.yield_claimed_events.get(i) {
-                events.push(event);
-            }
-        }
-        
-        events
+        self.yield_claimed_events[start..].to_vec()
     }
 
     /// Get intent execution results
     pub fn get_intent_execution_results(&self, limit: Option<u32>) -> Vec<IntentExecutionResult> {
-        let limit = limit.unwrap_or(50);
+        let limit = limit.unwrap_or(50) as u64;
         let mut results = Vec::new();
         
         let start = if self.intent_execution_results.len() > limit {
@@ -599,14 +584,14 @@ impl OpportunityContract {
     /// Generate intent hash for tracking
     fn generate_intent_hash(&self, account_id: &AccountId, amount: &U128) -> String {
         let timestamp = env::block_timestamp();
-        let nonce = env::random_seed();
+        let nonce = u64::from_le_bytes(env::random_seed()[..8].try_into().unwrap());
         format!("{}-{}-{}-{}", account_id, amount.0, timestamp, nonce)
     }
 
     /// Generate yield intent hash
     fn generate_yield_intent_hash(&self, account_id: &AccountId, yield_amount: &U128) -> String {
         let timestamp = env::block_timestamp();
-        let nonce = env::random_seed();
+        let nonce = u64::from_le_bytes(env::random_seed()[..8].try_into().unwrap());
         format!("yield-{}-{}-{}-{}", account_id, yield_amount.0, timestamp, nonce)
     }
 
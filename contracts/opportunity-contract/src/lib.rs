@@ -1,11 +1,20 @@
+// Constructor and config update mirror the JSON API one field per argument
+#![allow(clippy::too_many_arguments)]
+
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{UnorderedMap, UnorderedSet};
-use near_sdk::json_types::{U128, U64};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
     env, near_bindgen, AccountId, PanicOnDefault, require, log, Timestamp
 };
 
+mod migration;
+
+use bond_math::mul_div;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Allocation {
@@ -28,6 +37,22 @@ pub struct OpportunityConfig {
     pub category: String,
 }
 
+// What happens to withdrawals before the minimum holding period has passed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EarlyExitPolicy {
+    Reject,
+    ExitFee,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HoldingConfig {
+    pub min_holding_period_sec: u64, // 0 disables the holding period
+    pub early_exit_policy: EarlyExitPolicy,
+    pub early_exit_fee_bps: u16, // Charged on the withdrawn amount under ExitFee
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct AllocationEvent {
@@ -42,6 +67,7 @@ pub struct WithdrawalEvent {
     pub account_id: AccountId,
     pub amount: U128,
     pub yield_earned: U128,
+    pub exit_fee: U128,
     pub timestamp: Timestamp,
 }
 
@@ -54,6 +80,10 @@ pub struct OpportunityContract {
     pub allocation_events: Vec<AllocationEvent>,
     pub withdrawal_events: Vec<WithdrawalEvent>,
     pub total_participants: u64,
+    pub holding_config: HoldingConfig,
+    pub collected_exit_fees: U128,
+    /// Amount-weighted deposit time of each allocation, which the holding period runs from
+    pub deposited_at: UnorderedMap<AccountId, Timestamp>,
 }
 
 #[near_bindgen]
@@ -69,7 +99,7 @@ impl OpportunityContract {
         total_capacity: U128,
         category: String,
     ) -> Self {
-        require!(env::state_exists() == false, "Already initialized");
+        require!(!env::state_exists(), "Already initialized");
         
         let config = OpportunityConfig {
             owner_id: owner_id.clone(),
@@ -90,6 +120,13 @@ impl OpportunityContract {
             allocation_events: Vec::new(),
             withdrawal_events: Vec::new(),
             total_participants: 0,
+            holding_config: HoldingConfig {
+                min_holding_period_sec: 0,
+                early_exit_policy: EarlyExitPolicy::ExitFee,
+                early_exit_fee_bps: 50,
+            },
+            collected_exit_fees: U128(0),
+            deposited_at: UnorderedMap::new(b"deposited_at".to_vec()),
         }
     }
 
@@ -114,6 +151,21 @@ impl OpportunityContract {
         self.total_participants
     }
 
+    pub fn get_holding_config(&self) -> HoldingConfig {
+        self.holding_config.clone()
+    }
+
+    pub fn get_collected_exit_fees(&self) -> U128 {
+        self.collected_exit_fees
+    }
+
+    // Earliest time the account can withdraw without hitting the early exit policy
+    pub fn get_earliest_exit_time(&self, account_id: AccountId) -> Option<Timestamp> {
+        self.allocations
+            .get(&account_id)
+            .map(|allocation| self.deposit_time(&allocation) + self.holding_config.min_holding_period_sec * NANOS_PER_SEC)
+    }
+
     pub fn get_allocation_events(&self, limit: Option<u64>) -> Vec<AllocationEvent> {
         let limit = limit.unwrap_or(100);
         self.allocation_events
@@ -148,7 +200,7 @@ impl OpportunityContract {
         
         // Check if user already has an allocation
         let existing_allocation = self.allocations.get(&account_id);
-        let new_total_amount = if let Some(existing) = existing_allocation {
+        let new_total_amount = if let Some(existing) = &existing_allocation {
             U128(existing.amount.0 + amount.0)
         } else {
             amount
@@ -167,6 +219,7 @@ impl OpportunityContract {
         if is_new_participant {
             self.total_participants += 1;
         }
+        self.record_deposit_time(&account_id, existing_allocation.as_ref(), amount.0);
 
         self.allocations.insert(&account_id, &allocation);
         self.total_allocated = U128(self.total_allocated.0 + amount.0);
@@ -197,7 +250,7 @@ impl OpportunityContract {
     }
 
     // Withdrawal function
-    pub fn withdraw(&mut self, amount: U128) {
+    pub fn withdraw(&mut self, amount: U128, min_amount_out: Option<U128>) {
         require!(self.config.is_active, "Opportunity is not active");
         require!(amount.0 > 0, "Amount must be greater than zero");
 
@@ -210,13 +263,34 @@ impl OpportunityContract {
         // Calculate yield (simplified: APY * time_held / 365 days)
         let time_held = env::block_timestamp() - allocation.timestamp;
         let days_held = time_held / (24 * 60 * 60 * 1_000_000_000); // Convert to days
-        let yield_rate = (self.config.apy as u128 * days_held) / 365;
+        let yield_rate = (self.config.apy as u128 * days_held as u128) / 365;
         let yield_earned = U128((amount.0 * yield_rate) / 10000); // Convert basis points
 
+        // Enforce the minimum holding period, measured from the amount-weighted deposit time
+        let holding_time = env::block_timestamp().saturating_sub(self.deposit_time(&allocation));
+        let exit_fee = if holding_time < self.holding_config.min_holding_period_sec * NANOS_PER_SEC {
+            require!(
+                self.holding_config.early_exit_policy != EarlyExitPolicy::Reject,
+                "Minimum holding period not reached"
+            );
+            U128((amount.0 + yield_earned.0) * self.holding_config.early_exit_fee_bps as u128 / 10000)
+        } else {
+            U128(0)
+        };
+
+        // Protect against the yield-inclusive payout falling below the caller's bound
+        if let Some(min_amount_out) = min_amount_out {
+            require!(
+                amount.0 + yield_earned.0 - exit_fee.0 >= min_amount_out.0,
+                "Slippage: withdrawal amount below min_amount_out"
+            );
+        }
+
         // Update allocation
         let new_allocation_amount = U128(allocation.amount.0 - amount.0);
         if new_allocation_amount.0 == 0 {
             self.allocations.remove(&account_id);
+            self.deposited_at.remove(&account_id);
             self.total_participants -= 1;
         } else {
             let updated_allocation = Allocation {
@@ -228,35 +302,58 @@ impl OpportunityContract {
         }
 
         self.total_allocated = U128(self.total_allocated.0 - amount.0);
+        self.collected_exit_fees = U128(self.collected_exit_fees.0 + exit_fee.0);
 
         // Emit withdrawal event
         let withdrawal_event = WithdrawalEvent {
             account_id: account_id.clone(),
             amount,
             yield_earned,
+            exit_fee,
             timestamp: env::block_timestamp(),
         };
         self.withdrawal_events.push(withdrawal_event.clone());
 
         log!(
-            "Withdrawal successful: {} withdrew {} tokens from {}, earned {} yield",
+            "Withdrawal successful: {} withdrew {} tokens from {}, earned {} yield, paid {} exit fee",
             account_id,
             amount.0,
             self.config.name,
-            yield_earned.0
+            yield_earned.0,
+            exit_fee.0
         );
 
         // Log event for external systems
         env::log_str(&format!(
-            "EVENT_JSON:{{\"type\":\"withdrawal\",\"account_id\":\"{}\",\"amount\":\"{}\",\"yield_earned\":\"{}\",\"opportunity\":\"{}\",\"timestamp\":{}}}",
+            "EVENT_JSON:{{\"type\":\"withdrawal\",\"account_id\":\"{}\",\"amount\":\"{}\",\"yield_earned\":\"{}\",\"exit_fee\":\"{}\",\"opportunity\":\"{}\",\"timestamp\":{}}}",
             account_id,
             amount.0,
             yield_earned.0,
+            exit_fee.0,
             self.config.name,
             env::block_timestamp()
         ));
     }
 
+    // Allocations made before deposit times were tracked fall back to their last top-up
+    fn deposit_time(&self, allocation: &Allocation) -> Timestamp {
+        self.deposited_at.get(&allocation.account_id).unwrap_or(allocation.timestamp)
+    }
+
+    // Moves the deposit time towards now, weighted by the amount being added, so a small
+    // top-up neither restarts the holding period nor lets fresh funds skip it
+    fn record_deposit_time(&mut self, account_id: &AccountId, existing: Option<&Allocation>, added: u128) {
+        let now = env::block_timestamp();
+        let deposited_at = match existing {
+            Some(existing) if existing.amount.0 > 0 => {
+                let elapsed = now.saturating_sub(self.deposit_time(existing)) as u128;
+                now - mul_div(elapsed, existing.amount.0, existing.amount.0 + added) as u64
+            }
+            _ => now,
+        };
+        self.deposited_at.insert(account_id, &deposited_at);
+    }
+
     // Admin functions
     pub fn update_config(
         &mut self,
@@ -298,6 +395,22 @@ impl OpportunityContract {
         log!("Updated opportunity config for: {}", self.config.name);
     }
 
+    pub fn update_holding_config(&mut self, holding_config: HoldingConfig) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can update holding config"
+        );
+        require!(holding_config.early_exit_fee_bps <= 10000, "Exit fee cannot exceed 10000 basis points");
+
+        self.holding_config = holding_config;
+        log!(
+            "Updated holding config for {}: {}s minimum, {:?} policy",
+            self.config.name,
+            self.holding_config.min_holding_period_sec,
+            self.holding_config.early_exit_policy
+        );
+    }
+
     pub fn pause_opportunity(&mut self) {
         require!(
             env::predecessor_account_id() == self.config.owner_id,
@@ -316,3 +429,123 @@ impl OpportunityContract {
         log!("Unpaused opportunity: {}", self.config.name);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+
+    fn owner() -> AccountId {
+        "owner.near".parse().unwrap()
+    }
+
+    fn alice() -> AccountId {
+        "alice.near".parse().unwrap()
+    }
+
+    fn set_context(predecessor: AccountId, timestamp: Timestamp) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(predecessor)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    fn setup() -> OpportunityContract {
+        set_context(owner(), 0);
+        OpportunityContract::new(
+            owner(),
+            "Staking".to_string(),
+            "Test opportunity".to_string(),
+            1000, // 10% APY
+            U128(1),
+            U128(1_000_000),
+            U128(10_000_000),
+            "staking".to_string(),
+        )
+    }
+
+    #[test]
+    fn withdraw_pays_yield_within_min_amount_out() {
+        let mut contract = setup();
+        set_context(alice(), 0);
+        contract.allocate(U128(10_000));
+
+        set_context(alice(), 365 * DAY);
+        contract.withdraw(U128(10_000), Some(U128(11_000)));
+
+        let event = contract.get_withdrawal_events(Some(1)).pop().unwrap();
+        assert_eq!(event.yield_earned, U128(1_000));
+        assert!(contract.get_allocation(alice()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Slippage: withdrawal amount below min_amount_out")]
+    fn withdraw_reverts_below_min_amount_out() {
+        let mut contract = setup();
+        set_context(alice(), 0);
+        contract.allocate(U128(10_000));
+
+        set_context(alice(), 365 * DAY);
+        contract.withdraw(U128(10_000), Some(U128(11_001)));
+    }
+
+    fn set_holding_period(contract: &mut OpportunityContract, days: u64, policy: EarlyExitPolicy) {
+        set_context(owner(), env::block_timestamp());
+        contract.update_holding_config(HoldingConfig {
+            min_holding_period_sec: days * DAY / NANOS_PER_SEC,
+            early_exit_policy: policy,
+            early_exit_fee_bps: 100,
+        });
+    }
+
+    #[test]
+    fn small_top_up_keeps_holding_clock() {
+        let mut contract = setup();
+        set_holding_period(&mut contract, 30, EarlyExitPolicy::Reject);
+        set_context(alice(), 0);
+        contract.allocate(U128(900_000));
+
+        set_context(alice(), 40 * DAY);
+        contract.allocate(U128(1));
+
+        // All but about four seconds of the 40 days held carries over
+        let earliest_exit = contract.get_earliest_exit_time(alice()).unwrap();
+        assert!(earliest_exit > 30 * DAY && earliest_exit < 30 * DAY + 4 * NANOS_PER_SEC);
+        contract.withdraw(U128(900_001), None);
+        assert!(contract.get_allocation(alice()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Minimum holding period not reached")]
+    fn large_top_up_moves_holding_clock_forward() {
+        let mut contract = setup();
+        set_holding_period(&mut contract, 30, EarlyExitPolicy::Reject);
+        set_context(alice(), 0);
+        contract.allocate(U128(100_000));
+
+        set_context(alice(), 40 * DAY);
+        contract.allocate(U128(300_000));
+
+        // A quarter of the 40 days carries over, so the deposit time lands on day 30
+        assert_eq!(contract.get_earliest_exit_time(alice()), Some(60 * DAY));
+        set_context(alice(), 59 * DAY);
+        contract.withdraw(U128(1), None);
+    }
+
+    #[test]
+    fn early_exit_fee_is_collected() {
+        let mut contract = setup();
+        set_holding_period(&mut contract, 30, EarlyExitPolicy::ExitFee);
+        set_context(alice(), 0);
+        contract.allocate(U128(10_000));
+
+        set_context(alice(), DAY);
+        contract.withdraw(U128(10_000), None);
+
+        assert_eq!(contract.get_collected_exit_fees(), U128(100));
+        assert_eq!(contract.get_withdrawal_events(Some(1))[0].exit_fee, U128(100));
+    }
+}
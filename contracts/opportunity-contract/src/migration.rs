@@ -0,0 +1,116 @@
+use crate::*;
+
+/// State layout before the minimum holding period (no exit fees, no deposit times)
+mod v0 {
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::collections::UnorderedMap;
+    use near_sdk::json_types::U128;
+    use near_sdk::{AccountId, Timestamp};
+
+    use crate::{Allocation, AllocationEvent, OpportunityConfig};
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct WithdrawalEvent {
+        pub account_id: AccountId,
+        pub amount: U128,
+        pub yield_earned: U128,
+        pub timestamp: Timestamp,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct OpportunityContract {
+        pub config: OpportunityConfig,
+        pub total_allocated: U128,
+        pub allocations: UnorderedMap<AccountId, Allocation>,
+        pub allocation_events: Vec<AllocationEvent>,
+        pub withdrawal_events: Vec<WithdrawalEvent>,
+        pub total_participants: u64,
+    }
+}
+
+#[near_bindgen]
+impl OpportunityContract {
+    /// Upgrades a deployment that predates the holding period. The period starts
+    /// disabled; existing allocations run it from their last top-up.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let legacy: v0::OpportunityContract = env::state_read().expect("No opportunity state to migrate");
+
+        let withdrawal_events = legacy
+            .withdrawal_events
+            .into_iter()
+            .map(|event| WithdrawalEvent {
+                account_id: event.account_id,
+                amount: event.amount,
+                yield_earned: event.yield_earned,
+                exit_fee: U128(0),
+                timestamp: event.timestamp,
+            })
+            .collect();
+
+        log!("Migrated opportunity {} with {} allocations", legacy.config.name, legacy.allocations.len());
+
+        Self {
+            config: legacy.config,
+            total_allocated: legacy.total_allocated,
+            allocations: legacy.allocations,
+            allocation_events: legacy.allocation_events,
+            withdrawal_events,
+            total_participants: legacy.total_participants,
+            holding_config: HoldingConfig {
+                min_holding_period_sec: 0,
+                early_exit_policy: EarlyExitPolicy::ExitFee,
+                early_exit_fee_bps: 50,
+            },
+            collected_exit_fees: U128(0),
+            deposited_at: UnorderedMap::new(b"deposited_at".to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn migrate_keeps_allocations_and_backfills_exit_fees() {
+        testing_env!(VMContextBuilder::new().build());
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let mut allocations = UnorderedMap::new(b"allocations".to_vec());
+        allocations.insert(&alice, &Allocation { account_id: alice.clone(), amount: U128(5_000), timestamp: 7 });
+        env::state_write(&v0::OpportunityContract {
+            config: OpportunityConfig {
+                owner_id: "owner.near".parse().unwrap(),
+                name: "Staking".to_string(),
+                description: String::new(),
+                apy: 1000,
+                min_allocation: U128(1),
+                max_allocation: U128(1_000_000),
+                total_capacity: U128(10_000_000),
+                is_active: true,
+                category: "staking".to_string(),
+            },
+            total_allocated: U128(5_000),
+            allocations,
+            allocation_events: Vec::new(),
+            withdrawal_events: vec![v0::WithdrawalEvent {
+                account_id: "bob.near".parse().unwrap(),
+                amount: U128(1_000),
+                yield_earned: U128(10),
+                timestamp: 0,
+            }],
+            total_participants: 1,
+        });
+
+        let contract = OpportunityContract::migrate();
+
+        assert_eq!(contract.get_allocation(alice.clone()).unwrap().amount, U128(5_000));
+        assert_eq!(contract.get_total_participants(), 1);
+        assert_eq!(contract.get_holding_config().min_holding_period_sec, 0);
+        assert_eq!(contract.get_withdrawal_events(None)[0].exit_fee, U128(0));
+        assert_eq!(contract.get_earliest_exit_time(alice), Some(7));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::*;
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::CurveType;
+
+/// Score computed off-chain and signed by a registered signer.
+///
+/// Signers sign `sha256(borsh((registry_account_id, payload)))`, binding each
+/// attestation to one registry deployment.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScorePayload {
+    pub opportunity_id: u64,
+    pub performance: u16,
+    pub reliability: u16,
+    pub safety: u16,
+    pub methodology_version: u32,
+    pub timestamp: Timestamp,
+    pub nonce: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreSigner {
+    pub public_key: PublicKey,
+    pub reporter_id: AccountId, // Identity the signer submits oracle rounds under
+    pub last_nonce: u64,
+    pub added_at: Timestamp,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AttestationConfig {
+    pub max_age_sec: u64,
+    pub max_clock_skew_sec: u64, // Tolerance for payloads timestamped ahead of the block
+    pub min_methodology_version: u32,
+}
+
+impl Default for AttestationConfig {
+    fn default() -> Self {
+        Self {
+            max_age_sec: 60 * 60,
+            max_clock_skew_sec: 5 * 60,
+            min_methodology_version: 1,
+        }
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_score_signers(&self) -> Vec<ScoreSigner> {
+        self.score_signers.values().collect()
+    }
+
+    pub fn get_score_signer(&self, public_key: PublicKey) -> Option<ScoreSigner> {
+        self.score_signers.get(&public_key)
+    }
+
+    pub fn get_attestation_config(&self) -> AttestationConfig {
+        self.attestation_config.clone()
+    }
+
+    /// Verifies a signed score and submits it to the oracle round for the signer.
+    /// Callable by anyone, so scores can be relayed without privileged keys.
+    pub fn submit_signed_score(&mut self, payload: ScorePayload, public_key: PublicKey, signature: Base64VecU8) -> ScoreRound {
+        let mut signer = self.score_signers.get(&public_key).expect("Unknown score signer");
+        // Removing a reporter revokes its signers' attestations too
+        require!(self.reporters.contains(&signer.reporter_id), "Only reporters can submit scores");
+        let config = &self.attestation_config;
+
+        require!(payload.nonce > signer.last_nonce, "Nonce already used");
+        require!(
+            payload.methodology_version >= config.min_methodology_version,
+            "Methodology version no longer accepted"
+        );
+        let now = env::block_timestamp();
+        require!(
+            payload.timestamp <= now + config.max_clock_skew_sec * 1_000_000_000,
+            "Attestation timestamp is in the future"
+        );
+        require!(
+            now.saturating_sub(payload.timestamp) <= config.max_age_sec * 1_000_000_000,
+            "Attestation is stale"
+        );
+
+        let signature: [u8; 64] = signature.0.try_into().unwrap_or_else(|_| env::panic_str("Invalid signature length"));
+        let key: [u8; 32] = public_key.as_bytes()[1..].try_into().unwrap_or_else(|_| env::panic_str("Invalid public key"));
+        let message = env::sha256(&borsh::to_vec(&(env::current_account_id(), &payload)).unwrap());
+        require!(env::ed25519_verify(&signature, &message, &key), "Invalid score signature");
+
+        signer.last_nonce = payload.nonce;
+        self.score_signers.insert(&public_key, &signer);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"signed_score_submitted\",\"data\":[{{\"opportunity_id\":{},\"reporter_id\":\"{}\",\"methodology_version\":{},\"nonce\":{},\"relayer_id\":\"{}\",\"timestamp\":{}}}]}}",
+            payload.opportunity_id,
+            signer.reporter_id,
+            payload.methodology_version,
+            payload.nonce,
+            env::predecessor_account_id(),
+            payload.timestamp
+        ));
+
+        self.record_submission(
+            signer.reporter_id,
+            payload.opportunity_id,
+            payload.performance,
+            payload.reliability,
+            payload.safety,
+        )
+    }
+
+    // Admin functions
+    pub fn add_score_signer(&mut self, public_key: PublicKey, reporter_id: AccountId) {
+        self.assert_owner();
+        require!(public_key.curve_type() == CurveType::ED25519, "Signer key must be ed25519");
+        require!(self.score_signers.get(&public_key).is_none(), "Signer already registered");
+        require!(self.reporters.contains(&reporter_id), "Score signers must belong to a reporter");
+
+        let signer = ScoreSigner {
+            public_key: public_key.clone(),
+            reporter_id: reporter_id.clone(),
+            last_nonce: 0,
+            added_at: env::block_timestamp(),
+        };
+        self.score_signers.insert(&public_key, &signer);
+        log!("Added score signer for {}", reporter_id);
+    }
+
+    pub fn remove_score_signer(&mut self, public_key: PublicKey) {
+        self.assert_owner();
+        if let Some(signer) = self.score_signers.remove(&public_key) {
+            log!("Removed score signer for {}", signer.reporter_id);
+        }
+    }
+
+    pub fn update_attestation_config(&mut self, config: AttestationConfig) {
+        self.assert_owner();
+        require!(config.max_age_sec > 0, "Max age must be positive");
+
+        self.attestation_config = config;
+        log!("Updated attestation config");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const SECOND: Timestamp = 1_000_000_000;
+    const NOW: Timestamp = 10_000 * SECOND;
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn public_key(key: &SigningKey) -> PublicKey {
+        PublicKey::from_parts(CurveType::ED25519, key.verifying_key().to_bytes().to_vec()).unwrap()
+    }
+
+    fn payload(opportunity_id: u64, nonce: u64, timestamp: Timestamp) -> ScorePayload {
+        ScorePayload {
+            opportunity_id,
+            performance: 32,
+            reliability: 30,
+            safety: 12,
+            methodology_version: 1,
+            timestamp,
+            nonce,
+        }
+    }
+
+    /// Signs the payload the way off-chain signers do, bound to `registry_id`
+    fn sign(key: &SigningKey, registry_id: &str, payload: &ScorePayload) -> Base64VecU8 {
+        let registry_id: AccountId = registry_id.parse().unwrap();
+        let message = env::sha256(&borsh::to_vec(&(registry_id, payload)).unwrap());
+        Base64VecU8(key.sign(&message).to_bytes().to_vec())
+    }
+
+    /// Registry with one opportunity and signer key 1 registered for reporter r1
+    fn setup_signer() -> (RegistryContract, u64) {
+        let mut contract = setup();
+        let opportunity_id = add_opportunity(&mut contract, "pool", "staking");
+        contract.add_reporter(account("r1"));
+        contract.add_score_signer(public_key(&signing_key(1)), account("r1"));
+        set_context(account("relayer"), NOW);
+        (contract, opportunity_id)
+    }
+
+    #[test]
+    fn valid_attestation_is_submitted_for_the_signer() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let payload = payload(id, 1, NOW - 60 * SECOND);
+
+        let round = contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "registry.near", &payload));
+
+        assert_eq!(round.submissions[0].reporter_id, account("r1"));
+        assert_eq!(round.submissions[0].performance, 32);
+        assert_eq!(contract.get_score_signer(public_key(&key)).unwrap().last_nonce, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Nonce already used")]
+    fn replayed_nonce_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let first = payload(id, 5, NOW);
+        contract.submit_signed_score(first.clone(), public_key(&key), sign(&key, "registry.near", &first));
+
+        let replay = payload(id, 5, NOW);
+        contract.submit_signed_score(replay.clone(), public_key(&key), sign(&key, "registry.near", &replay));
+    }
+
+    #[test]
+    #[should_panic(expected = "Nonce already used")]
+    fn lower_nonce_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let first = payload(id, 5, NOW);
+        contract.submit_signed_score(first.clone(), public_key(&key), sign(&key, "registry.near", &first));
+
+        let older = payload(id, 4, NOW);
+        contract.submit_signed_score(older.clone(), public_key(&key), sign(&key, "registry.near", &older));
+    }
+
+    #[test]
+    #[should_panic(expected = "Attestation is stale")]
+    fn stale_timestamp_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let payload = payload(id, 1, NOW - 60 * 60 * SECOND - 1);
+
+        contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "registry.near", &payload));
+    }
+
+    #[test]
+    fn clock_skew_within_tolerance_is_accepted() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let payload = payload(id, 1, NOW + 5 * 60 * SECOND);
+
+        let round = contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "registry.near", &payload));
+        assert_eq!(round.submissions.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Attestation timestamp is in the future")]
+    fn clock_skew_beyond_tolerance_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let payload = payload(id, 1, NOW + 5 * 60 * SECOND + 1);
+
+        contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "registry.near", &payload));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid score signature")]
+    fn wrong_signer_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let payload = payload(id, 1, NOW);
+
+        // Signed by an unregistered key but presented under the registered one
+        let signature = sign(&signing_key(2), "registry.near", &payload);
+        contract.submit_signed_score(payload, public_key(&signing_key(1)), signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown score signer")]
+    fn unregistered_key_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(2);
+        let payload = payload(id, 1, NOW);
+
+        contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "registry.near", &payload));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid score signature")]
+    fn payload_signed_for_another_registry_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        let key = signing_key(1);
+        let payload = payload(id, 1, NOW);
+
+        contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "other-registry.near", &payload));
+    }
+
+    #[test]
+    #[should_panic(expected = "Score signers must belong to a reporter")]
+    fn signer_requires_a_registered_reporter() {
+        let mut contract = setup();
+        contract.add_score_signer(public_key(&signing_key(1)), account("r2"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only reporters can submit scores")]
+    fn removed_reporter_signer_is_rejected() {
+        let (mut contract, id) = setup_signer();
+        set_context(owner(), NOW);
+        contract.remove_reporter(account("r1"));
+
+        set_context(account("relayer"), NOW);
+        let key = signing_key(1);
+        let payload = payload(id, 1, NOW);
+        contract.submit_signed_score(payload.clone(), public_key(&key), sign(&key, "registry.near", &payload));
+    }
+}
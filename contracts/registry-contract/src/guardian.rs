@@ -0,0 +1,218 @@
+use crate::*;
+
+/// Immediate response to a guardian report, by severity
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IncidentPolicy {
+    pub low_penalty: u16, // Safety points withheld until the report is dismissed or resolved
+    pub medium_penalty: u16,
+    pub high_penalty: u16,
+    pub critical_penalty: u16,
+    pub pause_from: Option<IncidentSeverity>, // Active opportunities are paused at this severity and above
+}
+
+impl Default for IncidentPolicy {
+    fn default() -> Self {
+        Self {
+            low_penalty: 0,
+            medium_penalty: 5,
+            high_penalty: 10,
+            critical_penalty: 20,
+            pause_from: Some(IncidentSeverity::High),
+        }
+    }
+}
+
+impl IncidentPolicy {
+    pub(crate) fn penalty_for(&self, severity: &IncidentSeverity) -> u16 {
+        match severity {
+            IncidentSeverity::Low => self.low_penalty,
+            IncidentSeverity::Medium => self.medium_penalty,
+            IncidentSeverity::High => self.high_penalty,
+            IncidentSeverity::Critical => self.critical_penalty,
+        }
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_guardians(&self) -> Vec<AccountId> {
+        self.guardians.to_vec()
+    }
+
+    pub fn get_incident_policy(&self) -> IncidentPolicy {
+        self.incident_policy.clone()
+    }
+
+    /// Reported and confirmed incidents that are neither resolved nor dismissed
+    pub fn get_open_incidents(&self, opportunity_id: u64) -> Vec<IncidentRecord> {
+        self.get_incidents(opportunity_id, Some(true))
+    }
+
+    pub fn has_open_incidents(&self, opportunity_id: u64) -> bool {
+        self.incidents
+            .get(&opportunity_id)
+            .is_some_and(|incidents| incidents.iter().any(|incident| incident.status.is_open()))
+    }
+
+    // Guardian functions
+    /// Files an incident and applies the policy's safety penalty and pause at once.
+    /// Works while the registry is paused and regardless of `oracle_only`.
+    pub fn report_incident(
+        &mut self,
+        opportunity_id: u64,
+        severity: IncidentSeverity,
+        description: String,
+        loss_amount: Option<U128>,
+    ) -> u64 {
+        let guardian_id = env::predecessor_account_id();
+        require!(self.guardians.contains(&guardian_id), "Only guardians can report incidents");
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+
+        let mut incidents = self.get_incidents(opportunity_id, None);
+        // Penalties stack, so an open report already covers anything up to its severity
+        require!(
+            !incidents
+                .iter()
+                .any(|incident| incident.reported && incident.status.is_open() && incident.severity >= severity),
+            "An open report already covers this severity"
+        );
+
+        let should_pause = self.incident_policy.pause_from.as_ref().is_some_and(|from| &severity >= from)
+            && opportunity.status == OpportunityStatus::Active;
+        let status_before = opportunity.status.clone();
+        let safety_penalty = self.incident_policy.penalty_for(&severity);
+
+        // Pause first so the penalized score can't move the opportunity to review instead
+        if should_pause {
+            self.transition(opportunity, OpportunityStatus::Paused, "incident_reported");
+        }
+        self.add_safety_penalty(opportunity_id, safety_penalty);
+        let status_after = self.opportunities.get(&opportunity_id).expect("Opportunity not found").status;
+
+        let incident = IncidentRecord {
+            id: incidents.len() as u64,
+            occurred_at: env::block_timestamp(),
+            severity: severity.clone(),
+            loss_amount: loss_amount.unwrap_or(U128(0)),
+            description,
+            status: IncidentStatus::Reported,
+            resolution: None,
+            resolved_at: None,
+            reported: true,
+            safety_penalty,
+            status_before: if status_after != status_before { Some(status_before) } else { None },
+            recorded_at: env::block_timestamp(),
+            recorded_by: guardian_id.clone(),
+        };
+        incidents.push(incident.clone());
+        self.incidents.insert(&opportunity_id, &incidents);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"incident_reported\",\"data\":[{{\"opportunity_id\":{},\"incident_id\":{},\"severity\":\"{:?}\",\"safety_penalty\":{},\"paused\":{},\"guardian_id\":\"{}\",\"timestamp\":{}}}]}}",
+            opportunity_id,
+            incident.id,
+            severity,
+            incident.safety_penalty,
+            should_pause,
+            guardian_id,
+            env::block_timestamp()
+        ));
+        incident.id
+    }
+
+    // Admin functions
+    /// Accepts a guardian report. The penalty and pause stay until the incident is
+    /// resolved, and the incident now counts towards the scoring methodology.
+    pub fn confirm_incident(&mut self, opportunity_id: u64, incident_id: u64) {
+        self.assert_owner();
+        let mut incidents = self.get_incidents(opportunity_id, None);
+        let incident = incidents.get_mut(incident_id as usize).expect("Incident not found");
+        require!(incident.status == IncidentStatus::Reported, "Incident is not awaiting review");
+
+        incident.status = IncidentStatus::Open;
+        self.incidents.insert(&opportunity_id, &incidents);
+
+        self.log_incident_review(opportunity_id, incident_id, "incident_confirmed");
+        self.rescore_safety(opportunity_id);
+    }
+
+    /// Rejects a guardian report, lifting its penalty and undoing any status change it caused
+    pub fn dismiss_incident(&mut self, opportunity_id: u64, incident_id: u64, reason: String) {
+        self.assert_owner();
+        let mut incidents = self.get_incidents(opportunity_id, None);
+        let incident = incidents.get_mut(incident_id as usize).expect("Incident not found");
+        require!(incident.status == IncidentStatus::Reported, "Incident is not awaiting review");
+
+        incident.status = IncidentStatus::Dismissed;
+        incident.resolution = Some(reason);
+        incident.resolved_at = Some(env::block_timestamp());
+        let penalty = incident.safety_penalty;
+        let status_before = incident.status_before.clone();
+        self.incidents.insert(&opportunity_id, &incidents);
+
+        self.log_incident_review(opportunity_id, incident_id, "incident_dismissed");
+
+        // Restore the status from before the report unless the opportunity has been deprecated since.
+        // The penalty is lifted afterwards so a score that is low on its own is flagged again.
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        if let Some(status) = status_before {
+            if opportunity.status != status && opportunity.status != OpportunityStatus::Deprecated {
+                self.transition(opportunity, status, "incident_dismissed");
+            }
+        }
+        self.lift_safety_penalty(opportunity_id, penalty);
+    }
+
+    pub fn add_guardian(&mut self, guardian_id: AccountId) {
+        self.assert_owner();
+        self.guardians.insert(&guardian_id);
+        log!("Added guardian {}", guardian_id);
+    }
+
+    pub fn remove_guardian(&mut self, guardian_id: AccountId) {
+        self.assert_owner();
+        self.guardians.remove(&guardian_id);
+        log!("Removed guardian {}", guardian_id);
+    }
+
+    pub fn update_incident_policy(&mut self, policy: IncidentPolicy) {
+        self.assert_owner();
+        self.incident_policy = policy;
+        log!("Updated incident policy");
+    }
+
+    // Helper functions
+    pub(crate) fn add_safety_penalty(&mut self, opportunity_id: u64, penalty: u16) {
+        if penalty == 0 {
+            return;
+        }
+        let mut opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        opportunity.safety_penalty = opportunity.safety_penalty.saturating_add(penalty);
+        self.store_opportunity(&opportunity);
+        // Rescore with unchanged components so the total, history and risk rules pick it up
+        self.refresh_score(opportunity_id, opportunity.performance, opportunity.reliability, opportunity.safety);
+    }
+
+    pub(crate) fn lift_safety_penalty(&mut self, opportunity_id: u64, penalty: u16) {
+        if penalty == 0 {
+            return;
+        }
+        let mut opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        opportunity.safety_penalty = opportunity.safety_penalty.saturating_sub(penalty);
+        self.store_opportunity(&opportunity);
+        self.refresh_score(opportunity_id, opportunity.performance, opportunity.reliability, opportunity.safety);
+    }
+
+    fn log_incident_review(&self, opportunity_id: u64, incident_id: u64, event: &str) {
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"{}\",\"data\":[{{\"opportunity_id\":{},\"incident_id\":{},\"reviewed_by\":\"{}\",\"timestamp\":{}}}]}}",
+            event,
+            opportunity_id,
+            incident_id,
+            env::predecessor_account_id(),
+            env::block_timestamp()
+        ));
+    }
+}
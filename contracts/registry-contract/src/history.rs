@@ -0,0 +1,199 @@
+use crate::*;
+
+const HOUR_SEC: u64 = 60 * 60;
+const DAY_SEC: u64 = 24 * HOUR_SEC;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+const MAX_RAW_POINTS: usize = 168; // One per hour over the default 7 day raw tier
+
+/// One score observation, or the average of several once downsampled
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScorePoint {
+    pub timestamp: Timestamp, // Bucket start for downsampled points
+    pub performance: u16,
+    pub reliability: u16,
+    pub safety: u16,
+    pub total_score: u16,
+    pub updated_by: AccountId, // Last updater within the bucket
+    pub samples: u32,
+}
+
+/// Points older than `min_age_sec` are merged into one point per `bucket_sec`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RetentionTier {
+    pub min_age_sec: u64,
+    pub bucket_sec: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreRetention {
+    pub tiers: Vec<RetentionTier>, // Ordered by min_age_sec
+}
+
+impl Default for ScoreRetention {
+    fn default() -> Self {
+        Self {
+            tiers: vec![
+                RetentionTier { min_age_sec: 7 * DAY_SEC, bucket_sec: HOUR_SEC },
+                RetentionTier { min_age_sec: 30 * DAY_SEC, bucket_sec: DAY_SEC },
+                RetentionTier { min_age_sec: 365 * DAY_SEC, bucket_sec: 7 * DAY_SEC },
+            ],
+        }
+    }
+}
+
+impl ScorePoint {
+    pub(crate) fn current(opportunity: &Opportunity) -> Self {
+        Self {
+            timestamp: env::block_timestamp(),
+            performance: opportunity.performance,
+            reliability: opportunity.reliability,
+            safety: opportunity.safety,
+            total_score: opportunity.total_score,
+            updated_by: env::predecessor_account_id(),
+            samples: 1,
+        }
+    }
+}
+
+impl ScoreRetention {
+    /// Bucket width in nanoseconds for a point of the given age, None while kept raw
+    fn bucket_for_age(&self, age: u64) -> Option<u64> {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| age >= tier.min_age_sec.saturating_mul(NANOS_PER_SEC))
+            .map(|tier| tier.bucket_sec.saturating_mul(NANOS_PER_SEC))
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    /// Score points in `[from_ts, to_ts]`, oldest first
+    pub fn get_score_history(
+        &self,
+        opportunity_id: u64,
+        from_ts: Option<Timestamp>,
+        to_ts: Option<Timestamp>,
+        limit: Option<u64>,
+    ) -> Vec<ScorePoint> {
+        let from_ts = from_ts.unwrap_or(0);
+        let to_ts = to_ts.unwrap_or(Timestamp::MAX);
+        let limit = limit.unwrap_or(100);
+
+        let points = self.score_history.get(&opportunity_id).unwrap_or_default();
+        let start = points.partition_point(|point| point.timestamp < from_ts);
+
+        points[start..]
+            .iter()
+            .take_while(|point| point.timestamp <= to_ts)
+            .take(limit as usize)
+            .cloned()
+            .collect()
+    }
+
+    /// Latest score point at or before `ts`
+    pub fn get_score_at(&self, opportunity_id: u64, ts: Timestamp) -> Option<ScorePoint> {
+        let points = self.score_history.get(&opportunity_id)?;
+        let end = points.partition_point(|point| point.timestamp <= ts);
+
+        end.checked_sub(1).map(|index| points[index].clone())
+    }
+
+    pub fn get_score_retention(&self) -> ScoreRetention {
+        self.score_retention.clone()
+    }
+
+    pub fn update_score_retention(&mut self, tiers: Vec<RetentionTier>) {
+        self.assert_owner();
+        for (index, tier) in tiers.iter().enumerate() {
+            require!(tier.bucket_sec > 0, "Bucket must be at least one second");
+            if index > 0 {
+                let previous = &tiers[index - 1];
+                require!(tier.min_age_sec > previous.min_age_sec, "Tiers must be ordered by age");
+                require!(
+                    tier.bucket_sec % previous.bucket_sec == 0,
+                    "Bucket must be a multiple of the previous tier's bucket"
+                );
+            }
+        }
+
+        self.score_retention = ScoreRetention { tiers };
+        log!("Updated score retention");
+    }
+
+    /// Records a score point for the opportunity and downsamples its older points
+    pub(crate) fn record_score_point(&mut self, opportunity_id: u64, point: ScorePoint) {
+        let mut points = self.score_history.get(&opportunity_id).unwrap_or_default();
+        // Legacy migrations can backfill older points, so keep the series sorted
+        let index = points.partition_point(|existing| existing.timestamp <= point.timestamp);
+        points.insert(index, point);
+
+        let mut points = self.downsample(points);
+        self.cap_raw_points(&mut points);
+        self.score_history.insert(&opportunity_id, &points);
+    }
+
+    /// Keeps at most MAX_RAW_POINTS points in the raw tier by merging the two closest
+    /// neighbours, so frequent updates can't grow the series without bound
+    fn cap_raw_points(&self, points: &mut Vec<ScorePoint>) {
+        let now = env::block_timestamp();
+        // Raw points are the newest ones, at the end of the series
+        let first_raw = points.partition_point(|point| {
+            self.score_retention.bucket_for_age(now.saturating_sub(point.timestamp)).is_some()
+        });
+
+        while points.len() - first_raw > MAX_RAW_POINTS {
+            let index = (first_raw..points.len() - 1)
+                .min_by_key(|&index| points[index + 1].timestamp - points[index].timestamp)
+                .expect("Raw tier has at least two points");
+            let point = points.remove(index + 1);
+            merge_point(&mut points[index], &point);
+        }
+    }
+
+    /// Merges points that share a retention bucket into their sample-weighted average
+    fn downsample(&self, points: Vec<ScorePoint>) -> Vec<ScorePoint> {
+        let now = env::block_timestamp();
+        let mut compacted: Vec<ScorePoint> = Vec::with_capacity(points.len());
+        let mut last_bucket: Option<(u64, Timestamp)> = None;
+
+        for mut point in points {
+            let bucket = self
+                .score_retention
+                .bucket_for_age(now.saturating_sub(point.timestamp))
+                .map(|width| (width, point.timestamp - point.timestamp % width));
+
+            match (bucket, last_bucket, compacted.last_mut()) {
+                (Some(bucket), Some(last), Some(previous)) if bucket == last => {
+                    merge_point(previous, &point);
+                }
+                _ => {
+                    if let Some((_, bucket_start)) = bucket {
+                        point.timestamp = bucket_start;
+                    }
+                    compacted.push(point);
+                }
+            }
+            last_bucket = bucket;
+        }
+
+        compacted
+    }
+}
+
+fn merge_point(into: &mut ScorePoint, point: &ScorePoint) {
+    let (weight, other_weight) = (into.samples as u64, point.samples as u64);
+    let total = weight + other_weight;
+    let average = |a: u16, b: u16| ((a as u64 * weight + b as u64 * other_weight + total / 2) / total) as u16;
+
+    into.performance = average(into.performance, point.performance);
+    into.reliability = average(into.reliability, point.reliability);
+    into.safety = average(into.safety, point.safety);
+    // Averaged separately: guardian penalties keep the total below the component sum
+    into.total_score = average(into.total_score, point.total_score);
+    into.updated_by = point.updated_by.clone();
+    into.samples = total as u32;
+}
@@ -0,0 +1,214 @@
+use crate::*;
+use near_sdk::collections::TreeMap;
+use std::ops::Bound;
+
+/// Position of an opportunity within an ordered index: (score or apy, id), the key
+/// is 0 in id-ordered indexes
+pub(crate) type IndexPosition = (u16, u64);
+
+/// Secondary indexes kept in step with `opportunities` by `store_opportunity`
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct OpportunityIndex {
+    pub by_contract: LookupMap<AccountId, u64>,
+    pub by_category: TreeMap<(String, u64), ()>, // (category, id)
+    pub by_status: LookupMap<OpportunityStatus, UnorderedSet<u64>>,
+    pub by_score: TreeMap<(u16, u64), ()>, // (total_score, id)
+    pub by_apy: TreeMap<(u16, u64), ()>,   // (apy, id)
+    pub by_status_id: TreeMap<(OpportunityStatus, u64), ()>,               // (status, id)
+    pub by_status_score: TreeMap<(OpportunityStatus, u16, u64), ()>,       // (status, total_score, id)
+    pub by_status_category: TreeMap<(OpportunityStatus, String, u64), ()>, // (status, category, id)
+}
+
+impl OpportunityIndex {
+    pub(crate) fn new() -> Self {
+        Self {
+            by_contract: LookupMap::new(STORAGE_KEY_INDEX_CONTRACT),
+            by_category: TreeMap::new(STORAGE_KEY_INDEX_CATEGORY),
+            by_status: LookupMap::new(STORAGE_KEY_INDEX_STATUS),
+            by_score: TreeMap::new(STORAGE_KEY_INDEX_SCORE),
+            by_apy: TreeMap::new(STORAGE_KEY_INDEX_APY),
+            by_status_id: TreeMap::new(STORAGE_KEY_INDEX_STATUS_ID),
+            by_status_score: TreeMap::new(STORAGE_KEY_INDEX_STATUS_SCORE),
+            by_status_category: TreeMap::new(STORAGE_KEY_INDEX_STATUS_CATEGORY),
+        }
+    }
+
+    /// Ids with `min_score <= total_score <= max_score`, highest score first
+    pub(crate) fn score_range(&self, min_score: u16, max_score: u16) -> impl Iterator<Item = u64> + '_ {
+        // Exclusive start, and no id reaches u64::MAX
+        self.score_from((max_score, u64::MAX), min_score).map(|(_, id)| id)
+    }
+
+    /// Positions below `from` down to `min_score`, highest first
+    pub(crate) fn score_from(&self, from: IndexPosition, min_score: u16) -> impl Iterator<Item = IndexPosition> + '_ {
+        walk_down(&self.by_score, from, min_score)
+    }
+
+    /// Positions below the `(apy, id)` position `from` down to `min_apy`, highest first
+    pub(crate) fn apy_from(&self, from: IndexPosition, min_apy: u16) -> impl Iterator<Item = IndexPosition> + '_ {
+        walk_down(&self.by_apy, from, min_apy)
+    }
+
+    /// Ids with `status`, highest score first
+    pub(crate) fn status_by_score(&self, status: OpportunityStatus) -> impl Iterator<Item = u64> + '_ {
+        self.status_score_from(status, (u16::MAX, u64::MAX), 0).map(|(_, id)| id)
+    }
+
+    /// Positions with `status` below `from` down to `min_score`, highest first
+    pub(crate) fn status_score_from(
+        &self,
+        status: OpportunityStatus,
+        from: IndexPosition,
+        min_score: u16,
+    ) -> impl Iterator<Item = IndexPosition> + '_ {
+        self.by_status_score
+            .iter_rev_from((status.clone(), from.0, from.1))
+            .take_while(move |((key, score, _), _)| *key == status && *score >= min_score)
+            .map(|((_, score, id), _)| (score, id))
+    }
+
+    /// Ids with `status` in `category`, oldest first
+    pub(crate) fn status_in_category(&self, status: OpportunityStatus, category: String) -> impl Iterator<Item = u64> + '_ {
+        self.status_category_ids_from(status, category, 0)
+    }
+
+    /// Ids from `start` with `status` in `category`, oldest first
+    pub(crate) fn status_category_ids_from(
+        &self,
+        status: OpportunityStatus,
+        category: String,
+        start: u64,
+    ) -> impl Iterator<Item = u64> + '_ {
+        let bounds = (
+            Bound::Included((status.clone(), category.clone(), start)),
+            Bound::Included((status, category, u64::MAX)),
+        );
+        self.by_status_category.range(bounds).map(|((_, _, id), _)| id)
+    }
+
+    /// Ids from `start` in `category`, oldest first
+    pub(crate) fn category_ids_from(&self, category: String, start: u64) -> impl Iterator<Item = u64> + '_ {
+        let bounds = (Bound::Included((category.clone(), start)), Bound::Included((category, u64::MAX)));
+        self.by_category.range(bounds).map(|((_, id), _)| id)
+    }
+
+    /// Ids from `start` with `status`, oldest first
+    pub(crate) fn status_ids_from(&self, status: OpportunityStatus, start: u64) -> impl Iterator<Item = u64> + '_ {
+        let bounds = (Bound::Included((status.clone(), start)), Bound::Included((status, u64::MAX)));
+        self.by_status_id.range(bounds).map(|((_, id), _)| id)
+    }
+
+    fn insert(&mut self, opportunity: &Opportunity) {
+        let id = opportunity.id;
+        self.by_contract.insert(&opportunity.contract_id, &id);
+        self.by_category.insert(&(opportunity.category.clone(), id), &());
+        add_to_set(&mut self.by_status, &opportunity.status, id, STORAGE_KEY_STATUS_IDS);
+        self.by_score.insert(&(opportunity.total_score, id), &());
+        self.by_apy.insert(&(opportunity.apy, id), &());
+        self.by_status_id.insert(&(opportunity.status.clone(), id), &());
+        self.by_status_score.insert(&status_score_key(opportunity), &());
+        self.by_status_category.insert(&status_category_key(opportunity), &());
+    }
+
+    /// Moves the opportunity between index entries for whichever fields changed
+    fn update(&mut self, previous: &Opportunity, opportunity: &Opportunity) {
+        let id = opportunity.id;
+        if previous.category != opportunity.category {
+            self.by_category.remove(&(previous.category.clone(), id));
+            self.by_category.insert(&(opportunity.category.clone(), id), &());
+        }
+        if previous.status != opportunity.status {
+            remove_from_set(&mut self.by_status, &previous.status, id);
+            add_to_set(&mut self.by_status, &opportunity.status, id, STORAGE_KEY_STATUS_IDS);
+            self.by_status_id.remove(&(previous.status.clone(), id));
+            self.by_status_id.insert(&(opportunity.status.clone(), id), &());
+        }
+        if previous.total_score != opportunity.total_score {
+            self.by_score.remove(&(previous.total_score, id));
+            self.by_score.insert(&(opportunity.total_score, id), &());
+        }
+        if previous.apy != opportunity.apy {
+            self.by_apy.remove(&(previous.apy, id));
+            self.by_apy.insert(&(opportunity.apy, id), &());
+        }
+        if previous.status != opportunity.status || previous.total_score != opportunity.total_score {
+            self.by_status_score.remove(&status_score_key(previous));
+            self.by_status_score.insert(&status_score_key(opportunity), &());
+        }
+        if previous.status != opportunity.status || previous.category != opportunity.category {
+            self.by_status_category.remove(&status_category_key(previous));
+            self.by_status_category.insert(&status_category_key(opportunity), &());
+        }
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_opportunity_by_contract(&self, contract_id: AccountId) -> Option<Opportunity> {
+        let opportunity_id = self.index.by_contract.get(&contract_id)?;
+        self.opportunities.get(&opportunity_id)
+    }
+
+    // Helper functions
+    /// Writes the opportunity and keeps the secondary indexes in step
+    pub(crate) fn store_opportunity(&mut self, opportunity: &Opportunity) {
+        match self.opportunities.insert(&opportunity.id, opportunity) {
+            Some(previous) => self.index.update(&previous, opportunity),
+            None => self.index.insert(opportunity),
+        }
+    }
+
+    pub(crate) fn assert_unique_contract(&self, contract_id: &AccountId) {
+        require!(
+            self.index.by_contract.get(contract_id).is_none(),
+            "Contract is already listed"
+        );
+    }
+
+    /// Loads the opportunities for a list of indexed ids
+    pub(crate) fn load_opportunities(&self, ids: impl Iterator<Item = u64>, limit: u64) -> Vec<Opportunity> {
+        ids.filter_map(|id| self.opportunities.get(&id))
+            .take(limit as usize)
+            .collect()
+    }
+}
+
+/// Walks a `(key, id)` index down from an exclusive position while the key is at least `min`
+fn walk_down(index: &TreeMap<(u16, u64), ()>, from: IndexPosition, min: u16) -> impl Iterator<Item = IndexPosition> + '_ {
+    index
+        .iter_rev_from(from)
+        .take_while(move |((key, _), _)| *key >= min)
+        .map(|(position, _)| position)
+}
+
+fn status_score_key(opportunity: &Opportunity) -> (OpportunityStatus, u16, u64) {
+    (opportunity.status.clone(), opportunity.total_score, opportunity.id)
+}
+
+fn status_category_key(opportunity: &Opportunity) -> (OpportunityStatus, String, u64) {
+    (opportunity.status.clone(), opportunity.category.clone(), opportunity.id)
+}
+
+fn add_to_set<K: BorshSerialize + BorshDeserialize>(
+    index: &mut LookupMap<K, UnorderedSet<u64>>,
+    key: &K,
+    id: u64,
+    prefix: &[u8],
+) {
+    let mut ids = index.get(key).unwrap_or_else(|| {
+        // Each key gets its own set under the set prefix + hash of the key
+        let mut set_prefix = prefix.to_vec();
+        set_prefix.extend(env::sha256(&borsh::to_vec(key).unwrap()));
+        UnorderedSet::new(set_prefix)
+    });
+    ids.insert(&id);
+    index.insert(key, &ids);
+}
+
+fn remove_from_set<K: BorshSerialize + BorshDeserialize>(index: &mut LookupMap<K, UnorderedSet<u64>>, key: &K, id: u64) {
+    if let Some(mut ids) = index.get(key) {
+        ids.remove(&id);
+        index.insert(key, &ids);
+    }
+}
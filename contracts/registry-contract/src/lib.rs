@@ -1,77 +1,236 @@
+// Admin methods take their fields as named JSON arguments, and `#[near_bindgen]`
+// mirrors each one on the generated `RegistryContractExt`
+#![allow(clippy::too_many_arguments)]
+
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{UnorderedMap, UnorderedSet};
-use near_sdk::json_types::{U128, U64};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet, Vector};
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, near_bindgen, AccountId, PanicOnDefault, require, log, Timestamp
+    env, near_bindgen, AccountId, PanicOnDefault, PublicKey, require, log, Timestamp
 };
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+mod attestation;
+mod guardian;
+mod history;
+mod index;
+mod migration;
+mod oracle;
+mod query;
+mod records;
+mod risk;
+mod risk_level;
+mod scoring;
+#[cfg(test)]
+mod test_utils;
+
+pub use crate::attestation::{AttestationConfig, ScorePayload, ScoreSigner};
+pub use crate::guardian::IncidentPolicy;
+pub use crate::history::{RetentionTier, ScorePoint, ScoreRetention};
+pub use crate::index::OpportunityIndex;
+pub use crate::migration::LegacyEventLog;
+pub use crate::oracle::{OracleConfig, OutlierPolicy, RoundStatus, ScoreRound, ScoreSubmission};
+pub use crate::query::{OpportunityFilter, OpportunityPage, OpportunitySort};
+pub use crate::records::{AuditRecord, IncidentRecord, IncidentSeverity, IncidentStatus};
+pub use crate::risk::{LowScoreAction, RiskRules};
+pub use crate::risk_level::{RiskBand, RiskBands};
+pub use crate::scoring::{ComputedScore, RiskMetrics, SafetyBreakdown, ScoreTier, ScoringParams};
+
+// Storage keys
+const STORAGE_KEY_OPPORTUNITIES: &[u8] = b"opportunities";
+const STORAGE_KEY_CATEGORIES: &[u8] = b"categories";
+const STORAGE_KEY_OPPORTUNITY_LOG: &[u8] = b"opportunity_log";
+const STORAGE_KEY_SCORE_LOG: &[u8] = b"score_log";
+const STORAGE_KEY_SCORE_HISTORY: &[u8] = b"score_history";
+const STORAGE_KEY_REPORTERS: &[u8] = b"reporters";
+const STORAGE_KEY_CURRENT_ROUNDS: &[u8] = b"current_rounds";
+const STORAGE_KEY_SCORE_ROUNDS: &[u8] = b"score_rounds";
+const STORAGE_KEY_SCORE_SIGNERS: &[u8] = b"score_signers";
+const STORAGE_KEY_LOW_SCORE_STREAKS: &[u8] = b"low_score_streaks";
+const STORAGE_KEY_RISK_METRICS: &[u8] = b"risk_metrics";
+const STORAGE_KEY_INDEX_CONTRACT: &[u8] = b"by_contract";
+const STORAGE_KEY_INDEX_CATEGORY: &[u8] = b"by_category";
+const STORAGE_KEY_INDEX_STATUS: &[u8] = b"by_status";
+const STORAGE_KEY_STATUS_IDS: &[u8] = b"status_ids";
+const STORAGE_KEY_INDEX_SCORE: &[u8] = b"by_score";
+const STORAGE_KEY_INDEX_APY: &[u8] = b"by_apy";
+const STORAGE_KEY_INDEX_STATUS_ID: &[u8] = b"by_status_id";
+const STORAGE_KEY_INDEX_STATUS_SCORE: &[u8] = b"by_status_score";
+const STORAGE_KEY_INDEX_STATUS_CATEGORY: &[u8] = b"by_status_category";
+const STORAGE_KEY_AUDITS: &[u8] = b"audits";
+const STORAGE_KEY_INCIDENTS: &[u8] = b"incidents";
+const STORAGE_KEY_GUARDIANS: &[u8] = b"guardians";
+
+// Constants
+const MAX_PERFORMANCE: u16 = 40;
+const MAX_RELIABILITY: u16 = 40;
+const MAX_SAFETY: u16 = 20;
+const MAX_LOGGED_EVENTS: u64 = 1000;
+const DEFAULT_MAX_OPPORTUNITIES: u32 = 100;
+const DEFAULT_MIN_SCORE_THRESHOLD: u16 = 50;
+const DEFAULT_CATEGORIES: [&str; 6] = ["staking", "lending", "liquidity", "farming", "bridge", "index"];
+
+/// Opportunity status
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OpportunityStatus {
+    Active,
+    Inactive,
+    Paused,
+    Deprecated,
+    UnderReview,
+}
+
+/// Risk level derived from the total score through `RiskBands`.
+/// New levels must be appended to keep the stored layout.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RiskLevel {
+    Caution,
+    Moderate,
+    Preferred,
+}
+
+/// Listed yield opportunity
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Opportunity {
     pub id: u64,
     pub name: String,
     pub description: String,
+    pub category: String,
     pub contract_id: AccountId,
+    pub token_id: Option<AccountId>,
     pub apy: u16, // Basis points (e.g., 1250 = 12.5%)
-    pub trust_score: u16, // 0-100
     pub performance: u16, // 0-40
     pub reliability: u16, // 0-40
     pub safety: u16, // 0-20
+    pub safety_penalty: u16, // Held back from total_score while guardian reports are open
     pub total_score: u16, // 0-100
-    pub risk_level: String,
-    pub category: String,
+    pub risk_level: RiskLevel,
     pub min_deposit: U128,
     pub max_deposit: U128,
+    pub total_capacity: U128,
     pub tvl: U128,
-    pub is_active: bool,
+    pub status: OpportunityStatus,
+    pub tags: Vec<String>, // Lowercase, matched by `query_opportunities`
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
+    pub created_by: AccountId,
+}
+
+/// Opportunity event types
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OpportunityEventType {
+    Added,
+    Updated,
+    Removed,
+    StatusChanged,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+/// Opportunity management event
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OpportunityEvent {
+    pub event_type: OpportunityEventType,
+    pub opportunity_id: u64,
+    pub opportunity_name: String,
+    pub old_data: Option<Opportunity>,
+    pub new_data: Option<Opportunity>,
+    pub timestamp: Timestamp,
+    pub tx_hash: String, // Block height; contracts can't read their transaction hash, migrated events keep theirs
+    pub triggered_by: AccountId,
+}
+
+/// Score update event
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreUpdateEvent {
+    pub opportunity_id: u64,
+    pub opportunity_name: String,
+    pub old_score: u16,
+    pub new_score: u16,
+    pub score_change: i16,
+    pub performance: u16,
+    pub reliability: u16,
+    pub safety: u16,
+    pub timestamp: Timestamp,
+    pub tx_hash: String, // See `OpportunityEvent::tx_hash`
+    pub updated_by: AccountId,
+}
+
+/// Registry configuration
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
 #[serde(crate = "near_sdk::serde")]
 pub struct RegistryConfig {
     pub owner_id: AccountId,
     pub fee_percentage: u16, // Basis points
+    pub max_opportunities: u32,
+    pub min_score_threshold: u16,
+    pub is_paused: bool,
 }
 
+/// Opportunity registry combining the status lifecycle of registry-contract-v0 with
+/// the score breakdown of registry-contract. Both deployed layouts can be upgraded
+/// in place, see `migration.rs`.
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct RegistryContract {
     pub config: RegistryConfig,
-    pub opportunities: UnorderedMap<u64, Opportunity>,
-    pub opportunity_ids: UnorderedSet<u64>,
     pub next_opportunity_id: u64,
+    pub opportunities: UnorderedMap<u64, Opportunity>,
+    /// Lookups by contract, category, status and score; see `index.rs`
+    pub index: OpportunityIndex,
     pub categories: UnorderedSet<String>,
+    /// Ring buffers of the last MAX_LOGGED_EVENTS events
+    pub opportunity_events: Vector<OpportunityEvent>,
+    pub opportunity_event_count: u64,
+    pub score_events: Vector<ScoreUpdateEvent>,
+    pub score_event_count: u64,
+    /// Per-opportunity score series, downsampled per `score_retention`
+    pub score_history: LookupMap<u64, Vec<ScorePoint>>,
+    pub score_retention: ScoreRetention,
+    /// Score oracle: reporters submit per round, the median lands at quorum
+    pub reporters: UnorderedSet<AccountId>,
+    pub oracle_config: OracleConfig,
+    pub current_rounds: LookupMap<u64, u64>,
+    pub score_rounds: LookupMap<(u64, u64), ScoreRound>,
+    /// ed25519 keys whose signed scores anyone may relay into the oracle
+    pub score_signers: UnorderedMap<PublicKey, ScoreSigner>,
+    pub attestation_config: AttestationConfig,
+    /// Status changes triggered by scores below `min_score_threshold`
+    pub risk_rules: RiskRules,
+    pub low_score_streaks: LookupMap<u64, u32>,
+    /// Raw inputs behind each computed score, see `scoring.rs`
+    pub risk_metrics: LookupMap<u64, RiskMetrics>,
+    pub scoring_params: ScoringParams,
+    pub risk_bands: RiskBands,
+    /// Audit and incident records referenced by the safety component
+    pub audits: LookupMap<u64, Vec<AuditRecord>>,
+    pub incidents: LookupMap<u64, Vec<IncidentRecord>>,
+    /// Accounts that can report incidents with an immediate penalty and pause
+    pub guardians: UnorderedSet<AccountId>,
+    pub incident_policy: IncidentPolicy,
+    /// Event logs of a migrated registry-contract-v0 awaiting `migrate_legacy_events`
+    pub legacy_events: Option<LegacyEventLog>,
 }
 
 #[near_bindgen]
 impl RegistryContract {
     #[init]
-    pub fn new(owner_id: AccountId, fee_percentage: u16) -> Self {
-        require!(env::state_exists() == false, "Already initialized");
-        
-        let config = RegistryConfig {
-            owner_id: owner_id.clone(),
-            fee_percentage,
-        };
+    pub fn new(owner_id: AccountId, fee_percentage: Option<u16>) -> Self {
+        require!(!env::state_exists(), "Already initialized");
 
-        let mut contract = Self {
-            config,
-            opportunities: UnorderedMap::new(b"opportunities".to_vec()),
-            opportunity_ids: UnorderedSet::new(b"opportunity_ids".to_vec()),
-            next_opportunity_id: 1,
-            categories: UnorderedSet::new(b"categories".to_vec()),
+        let config = RegistryConfig {
+            owner_id,
+            fee_percentage: fee_percentage.unwrap_or(0),
+            max_opportunities: DEFAULT_MAX_OPPORTUNITIES,
+            min_score_threshold: DEFAULT_MIN_SCORE_THRESHOLD,
+            is_paused: false,
         };
 
-        // Initialize with default categories
-        contract.categories.insert(&"staking".to_string());
-        contract.categories.insert(&"liquidity".to_string());
-        contract.categories.insert(&"bridge".to_string());
-        contract.categories.insert(&"index".to_string());
-
-        contract
+        Self::with_state(config, 1, UnorderedSet::new(STORAGE_KEY_CATEGORIES))
     }
 
     // View functions
@@ -79,97 +238,131 @@ impl RegistryContract {
         self.config.clone()
     }
 
+    pub fn get_total_opportunities(&self) -> u64 {
+        self.opportunities.len()
+    }
+
+    pub fn get_active_opportunities_count(&self) -> u64 {
+        self.index
+            .by_status
+            .get(&OpportunityStatus::Active)
+            .map_or(0, |ids| ids.len())
+    }
+
     pub fn get_opportunities(&self, limit: Option<u64>, offset: Option<u64>) -> Vec<Opportunity> {
         let limit = limit.unwrap_or(50);
         let offset = offset.unwrap_or(0);
-        
-        self.opportunity_ids
-            .iter()
+
+        self.opportunities
+            .values()
             .skip(offset as usize)
             .take(limit as usize)
-            .filter_map(|id| self.opportunities.get(&id))
             .collect()
     }
 
+    pub fn get_active_opportunities(&self, limit: Option<u64>, offset: Option<u64>) -> Vec<Opportunity> {
+        let limit = limit.unwrap_or(50);
+        let offset = offset.unwrap_or(0);
+
+        match self.index.by_status.get(&OpportunityStatus::Active) {
+            Some(ids) => self.load_opportunities(ids.iter().skip(offset as usize), limit),
+            None => Vec::new(),
+        }
+    }
+
     pub fn get_opportunity(&self, opportunity_id: u64) -> Option<Opportunity> {
         self.opportunities.get(&opportunity_id)
     }
 
+    /// Active opportunities in `category`
     pub fn get_opportunities_by_category(&self, category: String, limit: Option<u64>) -> Vec<Opportunity> {
         let limit = limit.unwrap_or(50);
-        
-        self.opportunity_ids
-            .iter()
-            .filter_map(|id| self.opportunities.get(&id))
-            .filter(|opp| opp.category == category && opp.is_active)
-            .take(limit as usize)
-            .collect()
+        self.load_opportunities(self.index.status_in_category(OpportunityStatus::Active, category), limit)
+    }
+
+    /// Opportunities scored within `[min_score, max_score]`, highest first
+    pub fn get_opportunities_by_score_range(&self, min_score: u16, max_score: u16, limit: Option<u64>) -> Vec<Opportunity> {
+        let limit = limit.unwrap_or(50);
+        self.load_opportunities(self.index.score_range(min_score, max_score), limit)
+    }
+
+    /// Active opportunities, highest score first
+    pub fn get_top_opportunities(&self, limit: Option<u64>) -> Vec<Opportunity> {
+        let limit = limit.unwrap_or(10);
+        self.load_opportunities(self.index.status_by_score(OpportunityStatus::Active), limit)
     }
 
     pub fn get_categories(&self) -> Vec<String> {
         self.categories.iter().collect()
     }
 
-    pub fn get_total_opportunities(&self) -> u64 {
-        self.opportunity_ids.len()
+    pub fn get_opportunity_events(&self, limit: Option<u64>) -> Vec<OpportunityEvent> {
+        latest_events(&self.opportunity_events, self.opportunity_event_count, limit.unwrap_or(50))
     }
 
-    pub fn get_active_opportunities_count(&self) -> u64 {
-        self.opportunity_ids
-            .iter()
-            .filter_map(|id| self.opportunities.get(&id))
-            .filter(|opp| opp.is_active)
-            .count() as u64
+    pub fn get_score_events(&self, limit: Option<u64>) -> Vec<ScoreUpdateEvent> {
+        latest_events(&self.score_events, self.score_event_count, limit.unwrap_or(50))
     }
 
     // Admin functions
+    /// Lists an opportunity, computing its score components from `metrics`
     pub fn add_opportunity(
         &mut self,
         name: String,
         description: String,
+        category: String,
         contract_id: AccountId,
+        token_id: Option<AccountId>,
         apy: u16,
-        trust_score: u16,
-        performance: u16,
-        reliability: u16,
-        safety: u16,
-        risk_level: String,
-        category: String,
+        metrics: RiskMetrics,
         min_deposit: U128,
         max_deposit: U128,
-        tvl: U128,
-    ) {
+        total_capacity: U128,
+        tags: Option<Vec<String>>,
+    ) -> u64 {
+        self.assert_owner();
+        self.assert_not_paused();
         require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can add opportunities"
+            self.opportunities.len() < self.config.max_opportunities as u64,
+            "Maximum number of opportunities reached"
         );
+        self.assert_unique_contract(&contract_id);
 
-        let total_score = performance + reliability + safety;
         let opportunity_id = self.next_opportunity_id;
-
-        let opportunity = Opportunity {
+        let mut opportunity = Opportunity {
             id: opportunity_id,
             name: name.clone(),
             description,
+            category: category.clone(),
             contract_id,
+            token_id,
             apy,
-            trust_score,
-            performance,
-            reliability,
-            safety,
-            total_score,
-            risk_level,
-            category: category.clone(),
+            performance: 0,
+            reliability: 0,
+            safety: 0,
+            safety_penalty: 0,
+            total_score: 0,
+            risk_level: RiskLevel::Caution,
             min_deposit,
             max_deposit,
-            tvl,
-            is_active: true,
+            total_capacity,
+            tvl: U128(0),
+            status: OpportunityStatus::Active,
+            tags: query::normalize_tags(tags.unwrap_or_default()),
             created_at: env::block_timestamp(),
             updated_at: env::block_timestamp(),
+            created_by: env::predecessor_account_id(),
         };
 
-        self.opportunities.insert(&opportunity_id, &opportunity);
-        self.opportunity_ids.insert(&opportunity_id);
+        let score = self.score_from_metrics(&opportunity, metrics);
+        opportunity.performance = score.performance;
+        opportunity.reliability = score.reliability;
+        opportunity.safety = score.safety;
+        opportunity.total_score = score.total_score;
+        opportunity.risk_level = self.risk_bands.level_for(score.total_score);
+
+        self.store_opportunity(&opportunity);
+        self.record_score_point(opportunity_id, ScorePoint::current(&opportunity));
         self.next_opportunity_id += 1;
 
         // Add category if it doesn't exist
@@ -177,7 +370,12 @@ impl RegistryContract {
             self.categories.insert(&category);
         }
 
+        self.log_opportunity_event(OpportunityEventType::Added, None, Some(opportunity));
+        // The score from `metrics` is the first scoring round
+        self.evaluate_risk(opportunity_id);
+
         log!("Added opportunity: {} with ID: {}", name, opportunity_id);
+        opportunity_id
     }
 
     pub fn update_opportunity(
@@ -185,22 +383,19 @@ impl RegistryContract {
         opportunity_id: u64,
         name: Option<String>,
         description: Option<String>,
+        category: Option<String>,
         apy: Option<u16>,
-        trust_score: Option<u16>,
-        performance: Option<u16>,
-        reliability: Option<u16>,
-        safety: Option<u16>,
-        risk_level: Option<String>,
-        tvl: Option<U128>,
-        is_active: Option<bool>,
+        min_deposit: Option<U128>,
+        max_deposit: Option<U128>,
+        total_capacity: Option<U128>,
+        tags: Option<Vec<String>>,
     ) {
-        require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can update opportunities"
-        );
+        self.assert_owner();
+        self.assert_not_paused();
 
         let mut opportunity = self.opportunities.get(&opportunity_id)
             .expect("Opportunity not found");
+        let old_opportunity = opportunity.clone();
 
         if let Some(name) = name {
             opportunity.name = name;
@@ -208,61 +403,336 @@ impl RegistryContract {
         if let Some(description) = description {
             opportunity.description = description;
         }
+        if let Some(category) = category {
+            if !self.categories.contains(&category) {
+                self.categories.insert(&category);
+            }
+            opportunity.category = category;
+        }
         if let Some(apy) = apy {
             opportunity.apy = apy;
         }
-        if let Some(trust_score) = trust_score {
-            opportunity.trust_score = trust_score;
+        if let Some(min_deposit) = min_deposit {
+            opportunity.min_deposit = min_deposit;
         }
-        if let Some(performance) = performance {
-            opportunity.performance = performance;
+        if let Some(max_deposit) = max_deposit {
+            opportunity.max_deposit = max_deposit;
         }
-        if let Some(reliability) = reliability {
-            opportunity.reliability = reliability;
+        if let Some(total_capacity) = total_capacity {
+            opportunity.total_capacity = total_capacity;
         }
-        if let Some(safety) = safety {
-            opportunity.safety = safety;
+        if let Some(tags) = tags {
+            opportunity.tags = query::normalize_tags(tags);
         }
-        if let Some(risk_level) = risk_level {
-            opportunity.risk_level = risk_level;
-        }
-        if let Some(tvl) = tvl {
-            opportunity.tvl = tvl;
-        }
-        if let Some(is_active) = is_active {
-            opportunity.is_active = is_active;
-        }
-
-        // Recalculate total score
-        opportunity.total_score = opportunity.performance + opportunity.reliability + opportunity.safety;
         opportunity.updated_at = env::block_timestamp();
 
-        self.opportunities.insert(&opportunity_id, &opportunity);
+        self.store_opportunity(&opportunity);
+        self.log_opportunity_event(OpportunityEventType::Updated, Some(old_opportunity), Some(opportunity));
+
         log!("Updated opportunity with ID: {}", opportunity_id);
     }
 
+    /// Deprecates the opportunity, keeping it listed for history
     pub fn remove_opportunity(&mut self, opportunity_id: u64) {
-        require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can remove opportunities"
-        );
+        self.assert_owner();
+        self.assert_not_paused();
 
-        if self.opportunities.remove(&opportunity_id).is_some() {
-            self.opportunity_ids.remove(&opportunity_id);
-            log!("Removed opportunity with ID: {}", opportunity_id);
-        }
+        self.set_status(opportunity_id, OpportunityStatus::Deprecated);
+        log!("Deprecated opportunity with ID: {}", opportunity_id);
+    }
+
+    pub fn update_opportunity_status(&mut self, opportunity_id: u64, status: OpportunityStatus) {
+        self.assert_owner();
+        self.assert_not_paused();
+
+        self.set_status(opportunity_id, status.clone());
+        log!("Opportunity {} status updated to {:?}", opportunity_id, status);
     }
 
-    pub fn update_config(&mut self, fee_percentage: Option<u16>) {
+    /// Update opportunity TVL (called by the opportunity contract itself)
+    pub fn update_opportunity_tvl(&mut self, opportunity_id: u64, new_tvl: U128) {
+        let mut opportunity = self.opportunities.get(&opportunity_id)
+            .expect("Opportunity not found");
         require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can update config"
+            env::predecessor_account_id() == opportunity.contract_id,
+            "Only the opportunity contract can update its TVL"
         );
 
+        opportunity.tvl = new_tvl;
+        opportunity.updated_at = env::block_timestamp();
+        self.store_opportunity(&opportunity);
+
+        log!("TVL updated for {}: {}", opportunity.name, new_tvl.0);
+    }
+
+    pub fn add_category(&mut self, category: String) {
+        self.assert_owner();
+        self.categories.insert(&category);
+        log!("Added category: {}", category);
+    }
+
+    pub fn update_config(
+        &mut self,
+        fee_percentage: Option<u16>,
+        max_opportunities: Option<u32>,
+        min_score_threshold: Option<u16>,
+    ) {
+        self.assert_owner();
+
         if let Some(fee_percentage) = fee_percentage {
+            require!(fee_percentage <= 10_000, "Fee cannot exceed 10000 basis points");
             self.config.fee_percentage = fee_percentage;
         }
+        if let Some(max_opportunities) = max_opportunities {
+            self.config.max_opportunities = max_opportunities;
+        }
+        if let Some(min_score_threshold) = min_score_threshold {
+            require!(min_score_threshold <= 100, "Score threshold must be between 0 and 100");
+            self.config.min_score_threshold = min_score_threshold;
+        }
 
         log!("Updated registry config");
     }
+
+    pub fn set_paused(&mut self, is_paused: bool) {
+        self.assert_owner();
+        self.config.is_paused = is_paused;
+        log!("Registry {} by owner", if is_paused { "paused" } else { "unpaused" });
+    }
+
+    // Helper functions
+    fn with_state(config: RegistryConfig, next_opportunity_id: u64, mut categories: UnorderedSet<String>) -> Self {
+        for category in DEFAULT_CATEGORIES {
+            categories.insert(&category.to_string());
+        }
+
+        Self {
+            config,
+            next_opportunity_id,
+            opportunities: UnorderedMap::new(STORAGE_KEY_OPPORTUNITIES),
+            index: OpportunityIndex::new(),
+            categories,
+            opportunity_events: Vector::new(STORAGE_KEY_OPPORTUNITY_LOG),
+            opportunity_event_count: 0,
+            score_events: Vector::new(STORAGE_KEY_SCORE_LOG),
+            score_event_count: 0,
+            score_history: LookupMap::new(STORAGE_KEY_SCORE_HISTORY),
+            score_retention: ScoreRetention::default(),
+            reporters: UnorderedSet::new(STORAGE_KEY_REPORTERS),
+            oracle_config: OracleConfig::default(),
+            current_rounds: LookupMap::new(STORAGE_KEY_CURRENT_ROUNDS),
+            score_rounds: LookupMap::new(STORAGE_KEY_SCORE_ROUNDS),
+            score_signers: UnorderedMap::new(STORAGE_KEY_SCORE_SIGNERS),
+            attestation_config: AttestationConfig::default(),
+            risk_rules: RiskRules::default(),
+            low_score_streaks: LookupMap::new(STORAGE_KEY_LOW_SCORE_STREAKS),
+            risk_metrics: LookupMap::new(STORAGE_KEY_RISK_METRICS),
+            scoring_params: ScoringParams::default(),
+            risk_bands: RiskBands::default(),
+            audits: LookupMap::new(STORAGE_KEY_AUDITS),
+            incidents: LookupMap::new(STORAGE_KEY_INCIDENTS),
+            guardians: UnorderedSet::new(STORAGE_KEY_GUARDIANS),
+            incident_policy: IncidentPolicy::default(),
+            legacy_events: None,
+        }
+    }
+
+    fn set_status(&mut self, opportunity_id: u64, status: OpportunityStatus) {
+        let mut opportunity = self.opportunities.get(&opportunity_id)
+            .expect("Opportunity not found");
+        let old_opportunity = opportunity.clone();
+
+        opportunity.status = status;
+        opportunity.updated_at = env::block_timestamp();
+        self.store_opportunity(&opportunity);
+
+        self.log_opportunity_event(OpportunityEventType::StatusChanged, Some(old_opportunity), Some(opportunity));
+    }
+
+    /// Stores a scoring round and applies the risk rules to it
+    fn apply_score(&mut self, opportunity_id: u64, performance: u16, reliability: u16, safety: u16) {
+        self.write_score(opportunity_id, performance, reliability, safety);
+        self.evaluate_risk(opportunity_id);
+    }
+
+    /// Stores recomputed components without counting a scoring round towards the low score streak
+    pub(crate) fn refresh_score(&mut self, opportunity_id: u64, performance: u16, reliability: u16, safety: u16) {
+        self.write_score(opportunity_id, performance, reliability, safety);
+        self.check_low_score(opportunity_id);
+    }
+
+    fn write_score(&mut self, opportunity_id: u64, performance: u16, reliability: u16, safety: u16) {
+        let mut opportunity = self.opportunities.get(&opportunity_id)
+            .expect("Opportunity not found");
+
+        let old_score = opportunity.total_score;
+        let new_score = performance + reliability + safety.saturating_sub(opportunity.safety_penalty);
+        opportunity.performance = performance;
+        opportunity.reliability = reliability;
+        opportunity.safety = safety;
+        opportunity.total_score = new_score;
+        opportunity.risk_level = self.risk_bands.level_for(new_score);
+        opportunity.updated_at = env::block_timestamp();
+        self.store_opportunity(&opportunity);
+        self.record_score_point(opportunity_id, ScorePoint::current(&opportunity));
+
+        let event = ScoreUpdateEvent {
+            opportunity_id,
+            opportunity_name: opportunity.name.clone(),
+            old_score,
+            new_score,
+            score_change: new_score as i16 - old_score as i16,
+            performance,
+            reliability,
+            safety,
+            timestamp: env::block_timestamp(),
+            tx_hash: env::block_height().to_string(),
+            updated_by: env::predecessor_account_id(),
+        };
+        self.record_score_event(&event);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"score_updated\",\"data\":[{{\"opportunity_id\":{},\"opportunity_name\":\"{}\",\"old_score\":{},\"new_score\":{},\"score_change\":{},\"performance\":{},\"reliability\":{},\"safety\":{},\"risk_level\":\"{:?}\",\"timestamp\":{}}}]}}",
+            opportunity_id,
+            opportunity.name,
+            old_score,
+            new_score,
+            event.score_change,
+            performance,
+            reliability,
+            safety,
+            opportunity.risk_level,
+            event.timestamp
+        ));
+    }
+
+    fn log_opportunity_event(
+        &mut self,
+        event_type: OpportunityEventType,
+        old_data: Option<Opportunity>,
+        new_data: Option<Opportunity>,
+    ) {
+        let opportunity = new_data.as_ref().or(old_data.as_ref()).expect("Event without opportunity");
+        let opportunity_id = opportunity.id;
+        let opportunity_name = opportunity.name.clone();
+
+        let event_type_str = match event_type {
+            OpportunityEventType::Added => "added",
+            OpportunityEventType::Updated => "updated",
+            OpportunityEventType::Removed => "removed",
+            OpportunityEventType::StatusChanged => "status_changed",
+        };
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"opportunity_{}\",\"data\":[{{\"opportunity_id\":{},\"opportunity_name\":\"{}\",\"timestamp\":{}}}]}}",
+            event_type_str,
+            opportunity_id,
+            opportunity_name,
+            env::block_timestamp()
+        ));
+
+        let event = OpportunityEvent {
+            event_type,
+            opportunity_id,
+            opportunity_name,
+            old_data,
+            new_data,
+            timestamp: env::block_timestamp(),
+            tx_hash: env::block_height().to_string(),
+            triggered_by: env::predecessor_account_id(),
+        };
+        self.record_opportunity_event(&event);
+    }
+
+    pub(crate) fn record_opportunity_event(&mut self, event: &OpportunityEvent) {
+        push_event(&mut self.opportunity_events, &mut self.opportunity_event_count, event);
+    }
+
+    pub(crate) fn record_score_event(&mut self, event: &ScoreUpdateEvent) {
+        push_event(&mut self.score_events, &mut self.score_event_count, event);
+    }
+
+    fn assert_owner(&self) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can call this function"
+        );
+    }
+
+    fn assert_direct_scoring(&self) {
+        require!(!self.oracle_config.oracle_only, "Scores are set through the reporter oracle");
+    }
+
+    fn assert_not_paused(&self) {
+        require!(!self.config.is_paused, "Registry is paused");
+    }
+}
+
+fn assert_score_components(performance: u16, reliability: u16, safety: u16) {
+    require!(performance <= MAX_PERFORMANCE, "Performance must be between 0 and 40");
+    require!(reliability <= MAX_RELIABILITY, "Reliability must be between 0 and 40");
+    require!(safety <= MAX_SAFETY, "Safety must be between 0 and 20");
+}
+
+/// Appends to a ring buffer holding the last MAX_LOGGED_EVENTS entries
+fn push_event<T: BorshSerialize + BorshDeserialize>(log: &mut Vector<T>, count: &mut u64, event: &T) {
+    if log.len() < MAX_LOGGED_EVENTS {
+        log.push(event);
+    } else {
+        log.replace(*count % MAX_LOGGED_EVENTS, event);
+    }
+    *count += 1;
+}
+
+/// Returns up to `limit` most recent ring buffer entries, oldest first
+fn latest_events<T: BorshSerialize + BorshDeserialize>(log: &Vector<T>, count: u64, limit: u64) -> Vec<T> {
+    let start = count.saturating_sub(limit.min(log.len()));
+    (start..count)
+        .filter_map(|index| log.get(index % MAX_LOGGED_EVENTS))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn category_and_top_views_list_active_opportunities_only() {
+        let mut contract = setup();
+        let active = add_opportunity(&mut contract, "active", "staking");
+        let paused = add_opportunity(&mut contract, "paused", "staking");
+        let removed = add_opportunity(&mut contract, "removed", "staking");
+        contract.update_oracle_config(OracleConfig { oracle_only: false, ..OracleConfig::default() });
+        // 50 against the others' 55, so the views have to skip the higher inactive scores
+        let metrics = RiskMetrics { apy_30d: Some(1000), is_audited: true, ..RiskMetrics::default() };
+        contract.update_opportunity_metrics(active, metrics);
+        contract.update_opportunity_status(paused, OpportunityStatus::Paused);
+        contract.remove_opportunity(removed);
+
+        let by_category = contract.get_opportunities_by_category("staking".to_string(), None);
+        assert_eq!(by_category.iter().map(|o| o.id).collect::<Vec<_>>(), vec![active]);
+        let top = contract.get_top_opportunities(None);
+        assert_eq!(top.iter().map(|o| o.id).collect::<Vec<_>>(), vec![active]);
+    }
+
+    #[test]
+    fn category_and_top_views_follow_rescoring_and_moves() {
+        let mut contract = setup();
+        let first = add_opportunity(&mut contract, "first", "staking");
+        let second = add_opportunity(&mut contract, "second", "staking");
+        let third = add_opportunity(&mut contract, "third", "lending");
+        contract.update_oracle_config(OracleConfig { oracle_only: false, ..OracleConfig::default() });
+        let metrics = RiskMetrics { apy_30d: Some(2000), is_audited: true, ..RiskMetrics::default() };
+        contract.update_opportunity_metrics(second, metrics);
+        contract.update_opportunity(third, None, None, Some("staking".to_string()), None, None, None, None, None);
+        contract.update_opportunity_status(first, OpportunityStatus::Paused);
+        contract.update_opportunity_status(first, OpportunityStatus::Active);
+
+        let by_category = contract.get_opportunities_by_category("staking".to_string(), Some(2));
+        assert_eq!(by_category.iter().map(|o| o.id).collect::<Vec<_>>(), vec![first, second]);
+        assert!(contract.get_opportunities_by_category("lending".to_string(), None).is_empty());
+        // Ties on score list the newer id first
+        let top = contract.get_top_opportunities(None);
+        assert_eq!(top.iter().map(|o| o.id).collect::<Vec<_>>(), vec![second, third, first]);
+    }
 }
@@ -0,0 +1,713 @@
+use crate::*;
+
+/// State layout of the deployed registry-contract-v0 (u32 ids, enum categories, single score)
+mod v0 {
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::collections::{UnorderedMap, Vector};
+    use near_sdk::json_types::U128;
+    use near_sdk::{AccountId, Timestamp};
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub enum OpportunityCategory {
+        Staking,
+        Lending,
+        Liquidity,
+        Farming,
+        Other,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub enum OpportunityStatus {
+        Active,
+        Inactive,
+        Paused,
+        Deprecated,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct Opportunity {
+        pub id: u32,
+        pub name: String,
+        pub description: String,
+        pub category: OpportunityCategory,
+        pub apy: u16,
+        pub current_score: u16,
+        pub contract_address: AccountId,
+        pub token_address: Option<AccountId>,
+        pub min_deposit: U128,
+        pub max_deposit: U128,
+        pub total_capacity: U128,
+        pub current_tvl: U128,
+        pub status: OpportunityStatus,
+        pub created_at: Timestamp,
+        pub updated_at: Timestamp,
+        pub created_by: AccountId,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub enum OpportunityEventType {
+        Added,
+        Updated,
+        Removed,
+        StatusChanged,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct OpportunityEvent {
+        pub event_type: OpportunityEventType,
+        pub opportunity_id: u32,
+        pub opportunity_name: String,
+        pub old_data: Option<Opportunity>,
+        pub new_data: Option<Opportunity>,
+        pub timestamp: Timestamp,
+        pub tx_hash: String,
+        pub triggered_by: AccountId,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct ScoreUpdateEvent {
+        pub opportunity_id: u32,
+        pub opportunity_name: String,
+        pub old_score: u16,
+        pub new_score: u16,
+        pub score_change: i16,
+        pub timestamp: Timestamp,
+        pub tx_hash: String,
+        pub updated_by: AccountId,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct RegistryConfig {
+        pub owner_id: AccountId,
+        pub max_opportunities: u32,
+        pub min_score_threshold: u16,
+        pub is_paused: bool,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct RegistryContract {
+        pub config: RegistryConfig,
+        pub next_opportunity_id: u32,
+        pub opportunities: UnorderedMap<u32, Opportunity>,
+        pub opportunity_events: Vector<OpportunityEvent>,
+        pub score_events: Vector<ScoreUpdateEvent>,
+    }
+}
+
+/// State layout of the deployed registry-contract (score breakdown, string categories)
+mod v1 {
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::collections::{UnorderedMap, UnorderedSet};
+    use near_sdk::json_types::U128;
+    use near_sdk::{AccountId, Timestamp};
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct Opportunity {
+        pub id: u64,
+        pub name: String,
+        pub description: String,
+        pub contract_id: AccountId,
+        pub apy: u16,
+        pub trust_score: u16,
+        pub performance: u16,
+        pub reliability: u16,
+        pub safety: u16,
+        pub total_score: u16,
+        pub risk_level: String,
+        pub category: String,
+        pub min_deposit: U128,
+        pub max_deposit: U128,
+        pub tvl: U128,
+        pub is_active: bool,
+        pub created_at: Timestamp,
+        pub updated_at: Timestamp,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct RegistryConfig {
+        pub owner_id: AccountId,
+        pub fee_percentage: u16,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct RegistryContract {
+        pub config: RegistryConfig,
+        pub opportunities: UnorderedMap<u64, Opportunity>,
+        pub opportunity_ids: UnorderedSet<u64>,
+        pub next_opportunity_id: u64,
+        pub categories: UnorderedSet<String>,
+    }
+}
+
+/// registry-contract-v0 event logs still stored under their old prefixes
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LegacyEventLog {
+    opportunity_events: Vector<v0::OpportunityEvent>,
+    score_events: Vector<v0::ScoreUpdateEvent>,
+    next_index: u64,
+}
+
+impl LegacyEventLog {
+    fn remaining(&self) -> u64 {
+        let total = self.opportunity_events.len() + self.score_events.len();
+        // Copied entries still need their storage removed
+        total + total.saturating_sub(self.next_index)
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    /// Upgrades a registry-contract-v0 deployment. Opportunities are converted here;
+    /// the event logs follow in batches through `migrate_legacy_events`.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate_from_v0() -> Self {
+        let mut legacy: v0::RegistryContract = env::state_read().expect("No registry state to migrate");
+        let opportunities: Vec<v0::Opportunity> = legacy.opportunities.values().collect();
+        legacy.opportunities.clear();
+
+        let config = RegistryConfig {
+            owner_id: legacy.config.owner_id,
+            fee_percentage: 0,
+            max_opportunities: legacy.config.max_opportunities,
+            min_score_threshold: legacy.config.min_score_threshold,
+            is_paused: legacy.config.is_paused,
+        };
+        let mut contract = Self::with_state(
+            config,
+            legacy.next_opportunity_id as u64,
+            UnorderedSet::new(STORAGE_KEY_CATEGORIES),
+        );
+
+        for opportunity in opportunities {
+            let opportunity = Opportunity::from(opportunity);
+            // Same rule as `add_opportunity`; remove duplicates before upgrading
+            contract.assert_unique_contract(&opportunity.contract_id);
+            contract.categories.insert(&opportunity.category);
+            contract.store_opportunity(&opportunity);
+            contract.record_score_point(opportunity.id, seed_point(&opportunity));
+        }
+
+        if !legacy.opportunity_events.is_empty() || !legacy.score_events.is_empty() {
+            contract.legacy_events = Some(LegacyEventLog {
+                opportunity_events: legacy.opportunity_events,
+                score_events: legacy.score_events,
+                next_index: 0,
+            });
+        }
+
+        log!("Migrated {} opportunities from registry-contract-v0", contract.opportunities.len());
+        contract
+    }
+
+    /// Upgrades a registry-contract deployment that predates the status lifecycle
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate_from_v1() -> Self {
+        let mut legacy: v1::RegistryContract = env::state_read().expect("No registry state to migrate");
+        let opportunities: Vec<v1::Opportunity> = legacy
+            .opportunity_ids
+            .iter()
+            .filter_map(|id| legacy.opportunities.get(&id))
+            .collect();
+        legacy.opportunities.clear();
+        legacy.opportunity_ids.clear();
+
+        let config = RegistryConfig {
+            owner_id: legacy.config.owner_id.clone(),
+            fee_percentage: legacy.config.fee_percentage,
+            max_opportunities: DEFAULT_MAX_OPPORTUNITIES.max(opportunities.len() as u32),
+            min_score_threshold: DEFAULT_MIN_SCORE_THRESHOLD,
+            is_paused: false,
+        };
+        let owner_id = config.owner_id.clone();
+        let mut contract = Self::with_state(config, legacy.next_opportunity_id, legacy.categories);
+
+        for opportunity in opportunities {
+            let opportunity = from_v1(opportunity, &owner_id, &contract.risk_bands);
+            contract.assert_unique_contract(&opportunity.contract_id);
+            contract.store_opportunity(&opportunity);
+            contract.record_score_point(opportunity.id, seed_point(&opportunity));
+        }
+
+        log!("Migrated {} opportunities from registry-contract", contract.opportunities.len());
+        contract
+    }
+
+    pub fn get_legacy_events_remaining(&self) -> u64 {
+        self.legacy_events.as_ref().map_or(0, |log| log.remaining())
+    }
+
+    /// Copies up to `limit` legacy v0 events into the current logs, then frees the
+    /// old entries. Returns how many steps remain; call until it reaches zero.
+    pub fn migrate_legacy_events(&mut self, limit: u64) -> u64 {
+        self.assert_owner();
+        let mut legacy = self.legacy_events.take().expect("No legacy events to migrate");
+
+        let opportunity_count = legacy.opportunity_events.len();
+        let total = opportunity_count + legacy.score_events.len();
+        let mut steps = 0;
+
+        while steps < limit && legacy.next_index < total {
+            if legacy.next_index < opportunity_count {
+                let event = legacy.opportunity_events.get(legacy.next_index).expect("Missing legacy event");
+                self.record_opportunity_event(&event.into());
+            } else {
+                let event: ScoreUpdateEvent = legacy
+                    .score_events
+                    .get(legacy.next_index - opportunity_count)
+                    .expect("Missing legacy event")
+                    .into();
+                self.record_score_point(
+                    event.opportunity_id,
+                    ScorePoint {
+                        timestamp: event.timestamp,
+                        performance: event.performance,
+                        reliability: event.reliability,
+                        safety: event.safety,
+                        total_score: event.new_score,
+                        updated_by: event.updated_by.clone(),
+                        samples: 1,
+                    },
+                );
+                self.record_score_event(&event);
+            }
+            legacy.next_index += 1;
+            steps += 1;
+        }
+
+        while steps < limit && legacy.next_index >= total && !legacy.opportunity_events.is_empty() {
+            legacy.opportunity_events.pop();
+            steps += 1;
+        }
+        while steps < limit && legacy.next_index >= total && !legacy.score_events.is_empty() {
+            legacy.score_events.pop();
+            steps += 1;
+        }
+
+        let remaining = legacy.remaining();
+        if remaining > 0 {
+            self.legacy_events = Some(legacy);
+        } else {
+            log!("Legacy event migration complete");
+        }
+        remaining
+    }
+}
+
+impl From<v0::Opportunity> for Opportunity {
+    fn from(legacy: v0::Opportunity) -> Self {
+        // v0 kept a single score, so spread it over the components at full weight
+        let (performance, reliability, safety) = split_score(legacy.current_score);
+        let category = match legacy.category {
+            v0::OpportunityCategory::Staking => "staking",
+            v0::OpportunityCategory::Lending => "lending",
+            v0::OpportunityCategory::Liquidity => "liquidity",
+            v0::OpportunityCategory::Farming => "farming",
+            v0::OpportunityCategory::Other => "other",
+        };
+
+        Self {
+            id: legacy.id as u64,
+            name: legacy.name,
+            description: legacy.description,
+            category: category.to_string(),
+            contract_id: legacy.contract_address,
+            token_id: legacy.token_address,
+            apy: legacy.apy,
+            performance,
+            reliability,
+            safety,
+            safety_penalty: 0,
+            total_score: legacy.current_score,
+            risk_level: RiskBands::default().level_for(legacy.current_score),
+            min_deposit: legacy.min_deposit,
+            max_deposit: legacy.max_deposit,
+            total_capacity: legacy.total_capacity,
+            tvl: legacy.current_tvl,
+            status: match legacy.status {
+                v0::OpportunityStatus::Active => OpportunityStatus::Active,
+                v0::OpportunityStatus::Inactive => OpportunityStatus::Inactive,
+                v0::OpportunityStatus::Paused => OpportunityStatus::Paused,
+                v0::OpportunityStatus::Deprecated => OpportunityStatus::Deprecated,
+            },
+            tags: Vec::new(),
+            created_at: legacy.created_at,
+            updated_at: legacy.updated_at,
+            created_by: legacy.created_by,
+        }
+    }
+}
+
+impl From<v0::OpportunityEvent> for OpportunityEvent {
+    fn from(legacy: v0::OpportunityEvent) -> Self {
+        Self {
+            event_type: match legacy.event_type {
+                v0::OpportunityEventType::Added => OpportunityEventType::Added,
+                v0::OpportunityEventType::Updated => OpportunityEventType::Updated,
+                v0::OpportunityEventType::Removed => OpportunityEventType::Removed,
+                v0::OpportunityEventType::StatusChanged => OpportunityEventType::StatusChanged,
+            },
+            opportunity_id: legacy.opportunity_id as u64,
+            opportunity_name: legacy.opportunity_name,
+            old_data: legacy.old_data.map(Opportunity::from),
+            new_data: legacy.new_data.map(Opportunity::from),
+            timestamp: legacy.timestamp,
+            tx_hash: legacy.tx_hash,
+            triggered_by: legacy.triggered_by,
+        }
+    }
+}
+
+impl From<v0::ScoreUpdateEvent> for ScoreUpdateEvent {
+    fn from(legacy: v0::ScoreUpdateEvent) -> Self {
+        let (performance, reliability, safety) = split_score(legacy.new_score);
+
+        Self {
+            opportunity_id: legacy.opportunity_id as u64,
+            opportunity_name: legacy.opportunity_name,
+            old_score: legacy.old_score,
+            new_score: legacy.new_score,
+            score_change: legacy.score_change,
+            performance,
+            reliability,
+            safety,
+            timestamp: legacy.timestamp,
+            tx_hash: legacy.tx_hash,
+            updated_by: legacy.updated_by,
+        }
+    }
+}
+
+/// Starting point of a migrated opportunity's score history
+fn seed_point(opportunity: &Opportunity) -> ScorePoint {
+    ScorePoint {
+        timestamp: opportunity.updated_at,
+        performance: opportunity.performance,
+        reliability: opportunity.reliability,
+        safety: opportunity.safety,
+        total_score: opportunity.total_score,
+        updated_by: opportunity.created_by.clone(),
+        samples: 1,
+    }
+}
+
+/// Splits a v0 single 0-100 score into performance/reliability/safety at the 40/40/20 weights
+fn split_score(score: u16) -> (u16, u16, u16) {
+    let weighted = (score * MAX_PERFORMANCE + 50) / 100;
+    (weighted, weighted, score - 2 * weighted)
+}
+
+/// Clamps a score component the v1 registry stored without range checks
+fn clamp_component(opportunity_id: u64, component: &str, value: u16, max: u16) -> u16 {
+    if value > max {
+        log!("Opportunity {} {} {} clamped to {}", opportunity_id, component, value, max);
+    }
+    value.min(max)
+}
+
+fn from_v1(legacy: v1::Opportunity, owner_id: &AccountId, bands: &RiskBands) -> Opportunity {
+    let performance = clamp_component(legacy.id, "performance", legacy.performance, MAX_PERFORMANCE);
+    let reliability = clamp_component(legacy.id, "reliability", legacy.reliability, MAX_RELIABILITY);
+    let safety = clamp_component(legacy.id, "safety", legacy.safety, MAX_SAFETY);
+    let total_score = performance.saturating_add(reliability).saturating_add(safety).min(100);
+    // Levels follow the score now; hand-typed labels that disagree are dropped
+    let risk_level = bands.level_for(total_score);
+    if RiskLevel::from_label(&legacy.risk_level).as_ref() != Some(&risk_level) {
+        log!(
+            "Opportunity {} risk level \"{}\" replaced by {:?} from its score {}",
+            legacy.id, legacy.risk_level, risk_level, total_score
+        );
+    }
+
+    Opportunity {
+        id: legacy.id,
+        name: legacy.name,
+        description: legacy.description,
+        category: legacy.category,
+        contract_id: legacy.contract_id,
+        token_id: None,
+        apy: legacy.apy,
+        performance,
+        reliability,
+        safety,
+        safety_penalty: 0,
+        total_score,
+        risk_level,
+        min_deposit: legacy.min_deposit,
+        max_deposit: legacy.max_deposit,
+        total_capacity: U128(0), // Not tracked before the merge
+        tvl: legacy.tvl,
+        status: if legacy.is_active { OpportunityStatus::Active } else { OpportunityStatus::Inactive },
+        tags: Vec::new(),
+        created_at: legacy.created_at,
+        updated_at: legacy.updated_at,
+        created_by: owner_id.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn v1_opportunity(performance: u16, reliability: u16, safety: u16) -> v1::Opportunity {
+        v1::Opportunity {
+            id: 7,
+            name: "legacy".to_string(),
+            description: String::new(),
+            contract_id: account("legacy"),
+            apy: 1200,
+            trust_score: 0,
+            performance,
+            reliability,
+            safety,
+            total_score: 0,
+            risk_level: "Low".to_string(),
+            category: "staking".to_string(),
+            min_deposit: U128(0),
+            max_deposit: U128(0),
+            tvl: U128(0),
+            is_active: true,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn from_v1_clamps_out_of_range_components() {
+        set_context(owner(), 0);
+        let opportunity = from_v1(v1_opportunity(90, 41, u16::MAX), &owner(), &RiskBands::default());
+
+        assert_eq!(
+            (opportunity.performance, opportunity.reliability, opportunity.safety),
+            (MAX_PERFORMANCE, MAX_RELIABILITY, MAX_SAFETY)
+        );
+        assert_eq!(opportunity.total_score, 100);
+    }
+
+    #[test]
+    fn from_v1_keeps_in_range_components() {
+        set_context(owner(), 0);
+        let opportunity = from_v1(v1_opportunity(30, 25, 12), &owner(), &RiskBands::default());
+
+        assert_eq!((opportunity.performance, opportunity.reliability, opportunity.safety), (30, 25, 12));
+        assert_eq!(opportunity.total_score, 67);
+    }
+
+    fn v0_opportunity(id: u32, name: &str, score: u16, status: v0::OpportunityStatus) -> v0::Opportunity {
+        v0::Opportunity {
+            id,
+            name: name.to_string(),
+            description: String::new(),
+            category: v0::OpportunityCategory::Lending,
+            apy: 800,
+            current_score: score,
+            contract_address: account(name),
+            token_address: None,
+            min_deposit: U128(0),
+            max_deposit: U128(0),
+            total_capacity: U128(5_000),
+            current_tvl: U128(1_000),
+            status,
+            created_at: 10,
+            updated_at: 20,
+            created_by: account("creator"),
+        }
+    }
+
+    fn v0_opportunity_event(opportunity_id: u32, timestamp: Timestamp) -> v0::OpportunityEvent {
+        v0::OpportunityEvent {
+            event_type: v0::OpportunityEventType::Added,
+            opportunity_id,
+            opportunity_name: format!("opportunity {}", opportunity_id),
+            old_data: None,
+            new_data: None,
+            timestamp,
+            tx_hash: timestamp.to_string(),
+            triggered_by: owner(),
+        }
+    }
+
+    fn v0_score_event(opportunity_id: u32, new_score: u16, timestamp: Timestamp) -> v0::ScoreUpdateEvent {
+        v0::ScoreUpdateEvent {
+            opportunity_id,
+            opportunity_name: format!("opportunity {}", opportunity_id),
+            old_score: 0,
+            new_score,
+            score_change: new_score as i16,
+            timestamp,
+            tx_hash: timestamp.to_string(),
+            updated_by: owner(),
+        }
+    }
+
+    /// Writes a v0 registry with the given opportunities, three opportunity events and
+    /// two score events for opportunity 1
+    fn write_v0_state(opportunities: Vec<v0::Opportunity>) {
+        set_context(owner(), 0);
+        let mut stored = UnorderedMap::new(b"opportunities".to_vec());
+        for opportunity in &opportunities {
+            stored.insert(&opportunity.id, opportunity);
+        }
+        let mut opportunity_events = Vector::new(b"opportunity_events".to_vec());
+        for timestamp in [30, 40, 50] {
+            opportunity_events.push(&v0_opportunity_event(1, timestamp));
+        }
+        let mut score_events = Vector::new(b"score_events".to_vec());
+        score_events.push(&v0_score_event(1, 60, 60));
+        score_events.push(&v0_score_event(1, 90, 70));
+
+        env::state_write(&v0::RegistryContract {
+            config: v0::RegistryConfig {
+                owner_id: owner(),
+                max_opportunities: 25,
+                min_score_threshold: 40,
+                is_paused: false,
+            },
+            next_opportunity_id: opportunities.len() as u32 + 1,
+            opportunities: stored,
+            opportunity_events,
+            score_events,
+        });
+    }
+
+    fn legacy_entry_exists(prefix: &[u8], index: u64) -> bool {
+        env::storage_has_key(&[prefix, &index.to_le_bytes()].concat())
+    }
+
+    #[test]
+    fn migrate_from_v0_converts_opportunities_and_config() {
+        write_v0_state(vec![
+            v0_opportunity(1, "lend", 80, v0::OpportunityStatus::Active),
+            v0_opportunity(2, "farm", 45, v0::OpportunityStatus::Paused),
+        ]);
+
+        let contract = RegistryContract::migrate_from_v0();
+
+        let config = contract.get_config();
+        assert_eq!((config.max_opportunities, config.min_score_threshold), (25, 40));
+        assert_eq!(contract.get_total_opportunities(), 2);
+        let lend = contract.get_opportunity_by_contract(account("lend")).unwrap();
+        assert_eq!((lend.id, lend.category.as_str(), lend.status), (1, "lending", OpportunityStatus::Active));
+        assert_eq!((lend.performance, lend.reliability, lend.safety, lend.total_score), (32, 32, 16, 80));
+        assert_eq!((lend.total_capacity, lend.tvl), (U128(5_000), U128(1_000)));
+        assert_eq!(contract.get_opportunity(2).unwrap().status, OpportunityStatus::Paused);
+        assert!(contract.get_categories().contains(&"lending".to_string()));
+        assert_eq!(contract.get_score_history(1, None, None, None).len(), 1);
+        // Five events to copy, then five old entries to free
+        assert_eq!(contract.get_legacy_events_remaining(), 10);
+    }
+
+    #[test]
+    fn legacy_events_move_over_in_batches() {
+        write_v0_state(vec![v0_opportunity(1, "lend", 80, v0::OpportunityStatus::Active)]);
+        let mut contract = RegistryContract::migrate_from_v0();
+
+        assert_eq!(contract.migrate_legacy_events(3), 7);
+        assert_eq!(contract.get_opportunity_events(None).len(), 3);
+        assert!(contract.get_score_events(None).is_empty());
+
+        // Copies the two score events, then frees the last opportunity event
+        assert_eq!(contract.migrate_legacy_events(3), 4);
+        assert_eq!(contract.get_score_events(None).len(), 2);
+        assert!(!legacy_entry_exists(b"opportunity_events", 2));
+        assert!(legacy_entry_exists(b"opportunity_events", 1));
+
+        assert_eq!(contract.migrate_legacy_events(3), 1);
+        assert!(!legacy_entry_exists(b"opportunity_events", 0));
+        assert!(legacy_entry_exists(b"score_events", 0));
+
+        assert_eq!(contract.migrate_legacy_events(3), 0);
+        assert!(!legacy_entry_exists(b"score_events", 0));
+        assert_eq!(contract.get_legacy_events_remaining(), 0);
+
+        let events = contract.get_opportunity_events(None);
+        assert_eq!(events.iter().map(|e| e.timestamp).collect::<Vec<_>>(), vec![30, 40, 50]);
+        let scores = contract.get_score_events(None);
+        assert_eq!((scores[1].new_score, scores[1].performance, scores[1].safety), (90, 36, 18));
+        let history = contract.get_score_history(1, None, None, None);
+        assert_eq!(history.iter().map(|p| p.total_score).collect::<Vec<_>>(), vec![80, 60, 90]);
+    }
+
+    #[test]
+    #[should_panic(expected = "No legacy events to migrate")]
+    fn migrate_legacy_events_stops_once_done() {
+        write_v0_state(vec![v0_opportunity(1, "lend", 80, v0::OpportunityStatus::Active)]);
+        let mut contract = RegistryContract::migrate_from_v0();
+        assert_eq!(contract.migrate_legacy_events(100), 0);
+
+        contract.migrate_legacy_events(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is already listed")]
+    fn migrate_from_v0_rejects_duplicate_contracts() {
+        let mut duplicate = v0_opportunity(2, "other", 60, v0::OpportunityStatus::Active);
+        duplicate.contract_address = account("lend");
+        write_v0_state(vec![v0_opportunity(1, "lend", 80, v0::OpportunityStatus::Active), duplicate]);
+
+        RegistryContract::migrate_from_v0();
+    }
+
+    #[test]
+    fn migrate_from_v1_keeps_ids_categories_and_activity() {
+        set_context(owner(), 0);
+        let mut opportunities = UnorderedMap::new(b"opportunities".to_vec());
+        let mut opportunity_ids = UnorderedSet::new(b"opportunity_ids".to_vec());
+        let mut categories = UnorderedSet::new(b"categories".to_vec());
+        let mut inactive = v1_opportunity(30, 25, 12);
+        inactive.id = 9;
+        inactive.contract_id = account("inactive");
+        inactive.category = "lending".to_string();
+        inactive.is_active = false;
+        for opportunity in [v1_opportunity(30, 25, 12), inactive] {
+            opportunity_ids.insert(&opportunity.id);
+            categories.insert(&opportunity.category);
+            opportunities.insert(&opportunity.id, &opportunity);
+        }
+        env::state_write(&v1::RegistryContract {
+            config: v1::RegistryConfig { owner_id: owner(), fee_percentage: 150 },
+            opportunities,
+            opportunity_ids,
+            next_opportunity_id: 10,
+            categories,
+        });
+
+        let mut contract = RegistryContract::migrate_from_v1();
+
+        assert_eq!(contract.get_config().fee_percentage, 150);
+        assert_eq!(contract.get_total_opportunities(), 2);
+        assert_eq!(contract.get_opportunity(7).unwrap().status, OpportunityStatus::Active);
+        assert_eq!(contract.get_opportunity(9).unwrap().status, OpportunityStatus::Inactive);
+        assert_eq!(contract.get_opportunity_by_contract(account("inactive")).unwrap().id, 9);
+        let categories = contract.get_categories();
+        assert!(categories.contains(&"lending".to_string()) && categories.contains(&"staking".to_string()));
+        assert_eq!(contract.get_legacy_events_remaining(), 0);
+        // New listings continue after the highest v1 id
+        assert_eq!(add_opportunity(&mut contract, "fresh", "staking"), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "Contract is already listed")]
+    fn migrate_from_v1_rejects_duplicate_contracts() {
+        set_context(owner(), 0);
+        let mut opportunities = UnorderedMap::new(b"opportunities".to_vec());
+        let mut opportunity_ids = UnorderedSet::new(b"opportunity_ids".to_vec());
+        let mut duplicate = v1_opportunity(30, 25, 12);
+        duplicate.id = 8;
+        for opportunity in [v1_opportunity(30, 25, 12), duplicate] {
+            opportunity_ids.insert(&opportunity.id);
+            opportunities.insert(&opportunity.id, &opportunity);
+        }
+        env::state_write(&v1::RegistryContract {
+            config: v1::RegistryConfig { owner_id: owner(), fee_percentage: 0 },
+            opportunities,
+            opportunity_ids,
+            next_opportunity_id: 9,
+            categories: UnorderedSet::new(b"categories".to_vec()),
+        });
+
+        RegistryContract::migrate_from_v1();
+    }
+}
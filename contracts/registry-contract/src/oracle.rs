@@ -0,0 +1,381 @@
+use crate::*;
+
+/// What happens to a submission that deviates too far from the round median
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OutlierPolicy {
+    Reject, // Excluded from the median
+    Flag,   // Kept in the median, marked for review
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OracleConfig {
+    pub quorum: u32,
+    pub max_deviation: u16, // Max distance from the median per component
+    pub outlier_policy: OutlierPolicy,
+    pub round_timeout_sec: u64,
+    pub oracle_only: bool, // Disables direct owner score updates, on by default
+}
+
+impl Default for OracleConfig {
+    fn default() -> Self {
+        Self {
+            quorum: 3,
+            max_deviation: 8,
+            outlier_policy: OutlierPolicy::Reject,
+            round_timeout_sec: 24 * 60 * 60,
+            oracle_only: true,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RoundStatus {
+    Open,
+    Finalized,
+    Expired,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreSubmission {
+    pub reporter_id: AccountId,
+    pub performance: u16,
+    pub reliability: u16,
+    pub safety: u16,
+    pub submitted_at: Timestamp,
+    pub is_outlier: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreRound {
+    pub opportunity_id: u64,
+    pub round_id: u64,
+    pub status: RoundStatus,
+    pub submissions: Vec<ScoreSubmission>,
+    pub opened_at: Timestamp,
+    pub finalized_at: Option<Timestamp>,
+    pub performance: Option<u16>, // Medians once finalized
+    pub reliability: Option<u16>,
+    pub safety: Option<u16>,
+}
+
+impl ScoreRound {
+    fn is_expired(&self, timeout_sec: u64) -> bool {
+        self.status == RoundStatus::Open
+            && env::block_timestamp() >= self.opened_at + timeout_sec * 1_000_000_000
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_oracle_config(&self) -> OracleConfig {
+        self.oracle_config.clone()
+    }
+
+    pub fn get_reporters(&self) -> Vec<AccountId> {
+        self.reporters.to_vec()
+    }
+
+    pub fn get_current_round(&self, opportunity_id: u64) -> Option<ScoreRound> {
+        let round_id = self.current_rounds.get(&opportunity_id)?;
+        self.score_rounds.get(&(opportunity_id, round_id))
+    }
+
+    pub fn get_score_round(&self, opportunity_id: u64, round_id: u64) -> Option<ScoreRound> {
+        self.score_rounds.get(&(opportunity_id, round_id))
+    }
+
+    pub fn get_round_submissions(&self, opportunity_id: u64, round_id: u64) -> Vec<ScoreSubmission> {
+        self.score_rounds
+            .get(&(opportunity_id, round_id))
+            .map_or_else(Vec::new, |round| round.submissions)
+    }
+
+    // Reporter functions
+    /// Submits a score for the opportunity's open round; the round finalizes at quorum
+    pub fn submit_score(&mut self, opportunity_id: u64, performance: u16, reliability: u16, safety: u16) -> ScoreRound {
+        let reporter_id = env::predecessor_account_id();
+        require!(self.reporters.contains(&reporter_id), "Only reporters can submit scores");
+        self.record_submission(reporter_id, opportunity_id, performance, reliability, safety)
+    }
+
+    // Admin functions
+    pub fn add_reporter(&mut self, reporter_id: AccountId) {
+        self.assert_owner();
+        self.reporters.insert(&reporter_id);
+        log!("Added score reporter {}", reporter_id);
+    }
+
+    pub fn remove_reporter(&mut self, reporter_id: AccountId) {
+        self.assert_owner();
+        self.reporters.remove(&reporter_id);
+        log!("Removed score reporter {}", reporter_id);
+    }
+
+    pub fn update_oracle_config(&mut self, config: OracleConfig) {
+        self.assert_owner();
+        require!(config.quorum > 0, "Quorum must be at least one");
+        require!(config.round_timeout_sec > 0, "Round timeout must be positive");
+
+        self.oracle_config = config;
+        log!("Updated oracle config");
+    }
+
+    // Helper functions
+    /// Adds a submission to the opportunity's open round, finalizing it at quorum
+    pub(crate) fn record_submission(
+        &mut self,
+        reporter_id: AccountId,
+        opportunity_id: u64,
+        performance: u16,
+        reliability: u16,
+        safety: u16,
+    ) -> ScoreRound {
+        self.assert_not_paused();
+        assert_score_components(performance, reliability, safety);
+        require!(self.opportunities.get(&opportunity_id).is_some(), "Opportunity not found");
+
+        let mut round = self.open_round(opportunity_id);
+        require!(
+            round.submissions.iter().all(|s| s.reporter_id != reporter_id),
+            "Reporter already submitted for this round"
+        );
+
+        round.submissions.push(ScoreSubmission {
+            reporter_id: reporter_id.clone(),
+            performance,
+            reliability,
+            safety,
+            submitted_at: env::block_timestamp(),
+            is_outlier: false,
+        });
+        log!(
+            "Reporter {} submitted {}/{}/{} for opportunity {} round {}",
+            reporter_id, performance, reliability, safety, opportunity_id, round.round_id
+        );
+
+        if round.submissions.len() as u32 >= self.oracle_config.quorum {
+            self.try_finalize(&mut round);
+        }
+
+        self.score_rounds.insert(&(opportunity_id, round.round_id), &round);
+        round
+    }
+
+    /// Returns the open round for the opportunity, starting a new one when needed
+    fn open_round(&mut self, opportunity_id: u64) -> ScoreRound {
+        let current = self
+            .current_rounds
+            .get(&opportunity_id)
+            .and_then(|round_id| self.score_rounds.get(&(opportunity_id, round_id)));
+
+        let next_round_id = match current {
+            Some(mut round) if round.is_expired(self.oracle_config.round_timeout_sec) => {
+                round.status = RoundStatus::Expired;
+                self.score_rounds.insert(&(opportunity_id, round.round_id), &round);
+                round.round_id + 1
+            }
+            Some(round) if round.status == RoundStatus::Open => return round,
+            Some(round) => round.round_id + 1,
+            None => 1,
+        };
+
+        self.current_rounds.insert(&opportunity_id, &next_round_id);
+        ScoreRound {
+            opportunity_id,
+            round_id: next_round_id,
+            status: RoundStatus::Open,
+            submissions: Vec::new(),
+            opened_at: env::block_timestamp(),
+            finalized_at: None,
+            performance: None,
+            reliability: None,
+            safety: None,
+        }
+    }
+
+    /// Marks outliers against the round median and finalizes once enough inliers remain
+    fn try_finalize(&mut self, round: &mut ScoreRound) {
+        let (performance, reliability, safety) = medians(round.submissions.iter());
+        let max_deviation = self.oracle_config.max_deviation;
+
+        for submission in round.submissions.iter_mut() {
+            let is_outlier = submission.performance.abs_diff(performance) > max_deviation
+                || submission.reliability.abs_diff(reliability) > max_deviation
+                || submission.safety.abs_diff(safety) > max_deviation;
+            if is_outlier && !submission.is_outlier {
+                env::log_str(&format!(
+                    "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"score_outlier\",\"data\":[{{\"opportunity_id\":{},\"round_id\":{},\"reporter_id\":\"{}\",\"policy\":\"{:?}\"}}]}}",
+                    round.opportunity_id, round.round_id, submission.reporter_id, self.oracle_config.outlier_policy
+                ));
+            }
+            submission.is_outlier = is_outlier;
+        }
+
+        let accepted: Vec<&ScoreSubmission> = round
+            .submissions
+            .iter()
+            .filter(|s| self.oracle_config.outlier_policy == OutlierPolicy::Flag || !s.is_outlier)
+            .collect();
+        if (accepted.len() as u32) < self.oracle_config.quorum {
+            log!("Round {} waiting for more agreeing reporters", round.round_id);
+            return;
+        }
+
+        let (performance, reliability, safety) = medians(accepted.into_iter());
+        round.status = RoundStatus::Finalized;
+        round.finalized_at = Some(env::block_timestamp());
+        round.performance = Some(performance);
+        round.reliability = Some(reliability);
+        round.safety = Some(safety);
+
+        self.apply_score(round.opportunity_id, performance, reliability, safety);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"score_round_finalized\",\"data\":[{{\"opportunity_id\":{},\"round_id\":{},\"submissions\":{},\"outliers\":{},\"performance\":{},\"reliability\":{},\"safety\":{},\"timestamp\":{}}}]}}",
+            round.opportunity_id,
+            round.round_id,
+            round.submissions.len(),
+            round.submissions.iter().filter(|s| s.is_outlier).count(),
+            performance,
+            reliability,
+            safety,
+            env::block_timestamp()
+        ));
+    }
+}
+
+/// Per-component medians; even counts average the two middle values
+fn medians<'a>(submissions: impl Iterator<Item = &'a ScoreSubmission>) -> (u16, u16, u16) {
+    let (mut performance, mut reliability, mut safety) = (Vec::new(), Vec::new(), Vec::new());
+    for submission in submissions {
+        performance.push(submission.performance);
+        reliability.push(submission.reliability);
+        safety.push(submission.safety);
+    }
+    (median(performance), median(reliability), median(safety))
+}
+
+fn median(mut values: Vec<u16>) -> u16 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[middle - 1] + values[middle]) / 2
+    } else {
+        values[middle]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    const DAY: Timestamp = 24 * 60 * 60 * 1_000_000_000;
+
+    /// Registry with one opportunity and reporters r1..r5
+    fn setup_oracle(quorum: u32, outlier_policy: OutlierPolicy) -> (RegistryContract, u64) {
+        let mut contract = setup();
+        let opportunity_id = add_opportunity(&mut contract, "pool", "staking");
+        for i in 1..=5 {
+            contract.add_reporter(account(&format!("r{}", i)));
+        }
+        contract.update_oracle_config(OracleConfig { quorum, outlier_policy, ..OracleConfig::default() });
+        (contract, opportunity_id)
+    }
+
+    fn submit(contract: &mut RegistryContract, reporter: u32, opportunity_id: u64, performance: u16, timestamp: Timestamp) -> ScoreRound {
+        set_context(account(&format!("r{}", reporter)), timestamp);
+        contract.submit_score(opportunity_id, performance, 30, 10)
+    }
+
+    #[test]
+    #[should_panic(expected = "Scores are set through the reporter oracle")]
+    fn owner_cannot_rescore_by_default() {
+        let mut contract = setup();
+        let id = add_opportunity(&mut contract, "pool", "staking");
+        contract.update_opportunity_metrics(id, RiskMetrics::default());
+    }
+
+    #[test]
+    fn round_finalizes_at_quorum_with_odd_median() {
+        let (mut contract, id) = setup_oracle(3, OutlierPolicy::Reject);
+
+        assert_eq!(submit(&mut contract, 1, id, 36, 0).status, RoundStatus::Open);
+        assert_eq!(submit(&mut contract, 2, id, 30, 0).status, RoundStatus::Open);
+        let round = submit(&mut contract, 3, id, 33, 0);
+
+        assert_eq!(round.status, RoundStatus::Finalized);
+        assert_eq!((round.performance, round.reliability, round.safety), (Some(33), Some(30), Some(10)));
+        let opportunity = contract.get_opportunity(id).unwrap();
+        assert_eq!((opportunity.performance, opportunity.total_score), (33, 73));
+    }
+
+    #[test]
+    fn even_median_averages_the_middle_values() {
+        let (mut contract, id) = setup_oracle(4, OutlierPolicy::Reject);
+
+        for (reporter, performance) in [(1, 30), (2, 36), (3, 32), (4, 35)] {
+            submit(&mut contract, reporter, id, performance, 0);
+        }
+
+        let round = contract.get_current_round(id).unwrap();
+        assert_eq!(round.status, RoundStatus::Finalized);
+        assert_eq!(round.performance, Some(33));
+    }
+
+    #[test]
+    #[should_panic(expected = "Reporter already submitted for this round")]
+    fn reporter_submits_once_per_round() {
+        let (mut contract, id) = setup_oracle(3, OutlierPolicy::Reject);
+        submit(&mut contract, 1, id, 30, 0);
+        submit(&mut contract, 1, id, 31, 0);
+    }
+
+    #[test]
+    fn rejected_outliers_wait_for_more_reporters() {
+        let (mut contract, id) = setup_oracle(3, OutlierPolicy::Reject);
+
+        submit(&mut contract, 1, id, 30, 0);
+        submit(&mut contract, 2, id, 31, 0);
+        let round = submit(&mut contract, 3, id, 40, 0);
+        assert_eq!(round.status, RoundStatus::Open);
+        assert!(round.submissions[2].is_outlier);
+
+        let round = submit(&mut contract, 4, id, 32, 0);
+        assert_eq!(round.status, RoundStatus::Finalized);
+        assert_eq!(round.performance, Some(31));
+        assert!(round.submissions[2].is_outlier);
+    }
+
+    #[test]
+    fn flagged_outliers_count_towards_the_median() {
+        let (mut contract, id) = setup_oracle(3, OutlierPolicy::Flag);
+
+        submit(&mut contract, 1, id, 30, 0);
+        submit(&mut contract, 2, id, 31, 0);
+        let round = submit(&mut contract, 3, id, 40, 0);
+
+        assert_eq!(round.status, RoundStatus::Finalized);
+        assert_eq!(round.performance, Some(31));
+        assert!(round.submissions[2].is_outlier);
+    }
+
+    #[test]
+    fn expired_round_is_replaced_by_a_new_one() {
+        let (mut contract, id) = setup_oracle(3, OutlierPolicy::Reject);
+
+        submit(&mut contract, 1, id, 30, 0);
+        submit(&mut contract, 2, id, 31, 0);
+        let round = submit(&mut contract, 3, id, 32, DAY);
+
+        assert_eq!(contract.get_score_round(id, 1).unwrap().status, RoundStatus::Expired);
+        assert_eq!((round.round_id, round.status, round.submissions.len()), (2, RoundStatus::Open, 1));
+        assert_eq!(contract.get_opportunity(id).unwrap().performance, 40);
+    }
+}
@@ -0,0 +1,295 @@
+use crate::*;
+use crate::index::IndexPosition;
+use near_sdk::json_types::Base64VecU8;
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 100;
+const MAX_TAGS: usize = 10;
+const MAX_FILTER_VALUES: usize = 10; // Per list filter, each value opens an index range
+const MAX_SCANNED: usize = 200; // Index entries read per call before handing out a cursor
+
+/// Conditions an opportunity must meet; unset fields match everything.
+/// List fields match any of their values, except `tags` which must all be present.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OpportunityFilter {
+    pub categories: Option<Vec<String>>,
+    pub statuses: Option<Vec<OpportunityStatus>>,
+    pub min_score: Option<u16>,
+    pub max_score: Option<u16>,
+    pub min_apy: Option<u16>,
+    pub max_apy: Option<u16>,
+    pub risk_levels: Option<Vec<RiskLevel>>,
+    pub min_available_capacity: Option<U128>, // total_capacity - tvl
+    pub tags: Option<Vec<String>>,
+}
+
+/// Listing order; ties are broken by id in the same direction
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OpportunitySort {
+    Id,    // Oldest first
+    Score, // Highest total_score first
+    Apy,   // Highest apy first
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OpportunityPage {
+    pub opportunities: Vec<Opportunity>,
+    pub next_cursor: Option<Base64VecU8>, // None on the last page
+}
+
+/// Position after the last scanned index entry, handed out base64 encoded
+#[derive(BorshDeserialize, BorshSerialize)]
+struct QueryCursor {
+    sort: OpportunitySort,
+    key: u16, // Score or apy of the last opportunity, unused for `Id`
+    id: u64,
+}
+
+impl OpportunityFilter {
+    fn matches(&self, opportunity: &Opportunity) -> bool {
+        let available = opportunity.total_capacity.0.saturating_sub(opportunity.tvl.0);
+
+        self.categories.as_ref().is_none_or(|categories| categories.contains(&opportunity.category))
+            && self.statuses.as_ref().is_none_or(|statuses| statuses.contains(&opportunity.status))
+            && self.risk_levels.as_ref().is_none_or(|levels| levels.contains(&opportunity.risk_level))
+            && self.min_score.is_none_or(|min| opportunity.total_score >= min)
+            && self.max_score.is_none_or(|max| opportunity.total_score <= max)
+            && self.min_apy.is_none_or(|min| opportunity.apy >= min)
+            && self.max_apy.is_none_or(|max| opportunity.apy <= max)
+            && self.min_available_capacity.is_none_or(|min| available >= min.0)
+            && self.tags.as_ref().is_none_or(|tags| {
+                tags.iter().all(|tag| opportunity.tags.contains(&tag.trim().to_lowercase()))
+            })
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    /// Filtered, sorted listing with cursor pagination. Pass `next_cursor` back with the
+    /// same sort to continue; pages stay stable as opportunities are added.
+    ///
+    /// Each call reads at most MAX_SCANNED index entries, so a page can hold fewer than
+    /// `limit` opportunities, or none, while `next_cursor` is still set.
+    pub fn query_opportunities(
+        &self,
+        filter: Option<OpportunityFilter>,
+        sort: Option<OpportunitySort>,
+        cursor: Option<Base64VecU8>,
+        limit: Option<u64>,
+    ) -> OpportunityPage {
+        let filter = filter.unwrap_or_default();
+        let sort = sort.unwrap_or(OpportunitySort::Id);
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize;
+        let categories = filter.categories.clone().map(filter_values);
+        let statuses = filter.statuses.clone().map(filter_values);
+
+        let after = cursor.map(|cursor| {
+            let cursor: QueryCursor =
+                borsh::from_slice(&cursor.0).unwrap_or_else(|_| env::panic_str("Invalid cursor"));
+            require!(cursor.sort == sort, "Cursor was issued for a different sort");
+            cursor
+        });
+
+        let positions: Box<dyn Iterator<Item = IndexPosition> + '_> = match sort {
+            OpportunitySort::Id => {
+                let start = after.map_or(0, |cursor| cursor.id + 1);
+                let ids: Box<dyn Iterator<Item = u64> + '_> = match (categories, statuses) {
+                    (Some(categories), Some(statuses)) => {
+                        let mut ranges: Vec<Box<dyn Iterator<Item = u64> + '_>> = Vec::new();
+                        for status in &statuses {
+                            for category in &categories {
+                                ranges.push(Box::new(self.index.status_category_ids_from(status.clone(), category.clone(), start)));
+                            }
+                        }
+                        Box::new(merge(ranges, false))
+                    }
+                    (Some(categories), None) => Box::new(merge(
+                        categories.into_iter().map(|category| boxed(self.index.category_ids_from(category, start))).collect(),
+                        false,
+                    )),
+                    (None, Some(statuses)) => Box::new(merge(
+                        statuses.into_iter().map(|status| boxed(self.index.status_ids_from(status, start))).collect(),
+                        false,
+                    )),
+                    (None, None) => Box::new(start..self.next_opportunity_id),
+                };
+                Box::new(ids.map(|id| (0, id)))
+            }
+            OpportunitySort::Score => {
+                let from = after.map_or((filter.max_score.unwrap_or(u16::MAX), u64::MAX), |cursor| (cursor.key, cursor.id));
+                let min_score = filter.min_score.unwrap_or(0);
+                match statuses {
+                    Some(statuses) => Box::new(merge(
+                        statuses
+                            .into_iter()
+                            .map(|status| boxed(self.index.status_score_from(status, from, min_score)))
+                            .collect(),
+                        true,
+                    )),
+                    None => Box::new(self.index.score_from(from, min_score)),
+                }
+            }
+            OpportunitySort::Apy => {
+                let from = after.map_or((filter.max_apy.unwrap_or(u16::MAX), u64::MAX), |cursor| (cursor.key, cursor.id));
+                Box::new(self.index.apy_from(from, filter.min_apy.unwrap_or(0)))
+            }
+        };
+
+        let mut opportunities = Vec::new();
+        let mut last = None;
+        let mut exhausted = true;
+        for (scanned, position) in positions.enumerate() {
+            if opportunities.len() == limit || scanned == MAX_SCANNED {
+                exhausted = false;
+                break;
+            }
+            last = Some(position);
+            if let Some(opportunity) = self.opportunities.get(&position.1) {
+                if filter.matches(&opportunity) {
+                    opportunities.push(opportunity);
+                }
+            }
+        }
+
+        let next_cursor = match (exhausted, last) {
+            (false, Some((key, id))) => {
+                let cursor = QueryCursor { sort, key, id };
+                Some(Base64VecU8(borsh::to_vec(&cursor).unwrap()))
+            }
+            _ => None,
+        };
+
+        OpportunityPage { opportunities, next_cursor }
+    }
+}
+
+/// Sorts and dedups a list filter so every index range is walked once
+fn filter_values<T: Ord>(mut values: Vec<T>) -> Vec<T> {
+    values.sort();
+    values.dedup();
+    require!(values.len() <= MAX_FILTER_VALUES, "Too many filter values");
+    values
+}
+
+fn boxed<'a, T>(iter: impl Iterator<Item = T> + 'a) -> Box<dyn Iterator<Item = T> + 'a> {
+    Box::new(iter)
+}
+
+/// Merges iterators that are each sorted in the same direction into one sorted iterator
+fn merge<'a, T: Ord + Copy + 'a>(
+    sources: Vec<Box<dyn Iterator<Item = T> + 'a>>,
+    descending: bool,
+) -> impl Iterator<Item = T> + 'a {
+    let mut heads: Vec<_> = sources.into_iter().map(Iterator::peekable).collect();
+    std::iter::from_fn(move || {
+        let (next, _) = heads
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, head)| head.peek().map(|value| (i, *value)))
+            .reduce(|best, head| if (head.1 > best.1) == descending { head } else { best })?;
+        heads[next].next()
+    })
+}
+
+/// Lowercases, trims and dedups tags
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    require!(tags.len() <= MAX_TAGS, "Too many tags");
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn ids(page: &OpportunityPage) -> Vec<u64> {
+        page.opportunities.iter().map(|o| o.id).collect()
+    }
+
+    /// Opportunities 1-6 alternating staking/lending; 2 and 5 paused, 3 rescored to 70
+    fn setup_listings() -> RegistryContract {
+        let mut contract = setup();
+        for n in 1..=6 {
+            add_opportunity(&mut contract, &format!("pool{}", n), if n % 2 == 1 { "staking" } else { "lending" });
+        }
+        contract.update_oracle_config(OracleConfig { oracle_only: false, ..OracleConfig::default() });
+        contract.update_opportunity_metrics(3, RiskMetrics { apy_30d: Some(2000), is_audited: true, ..RiskMetrics::default() });
+        contract.update_opportunity_status(2, OpportunityStatus::Paused);
+        contract.update_opportunity_status(5, OpportunityStatus::Paused);
+        contract
+    }
+
+    #[test]
+    fn id_sort_pages_through_category_and_status_ranges() {
+        let contract = setup_listings();
+        let filter = OpportunityFilter {
+            categories: Some(vec!["staking".to_string(), "lending".to_string()]),
+            statuses: Some(vec![OpportunityStatus::Active, OpportunityStatus::Paused, OpportunityStatus::Active]),
+            ..OpportunityFilter::default()
+        };
+
+        let first = contract.query_opportunities(Some(filter.clone()), None, None, Some(4));
+        assert_eq!(ids(&first), vec![1, 2, 3, 4]);
+        let second = contract.query_opportunities(Some(filter), None, first.next_cursor, Some(4));
+        assert_eq!(ids(&second), vec![5, 6]);
+        assert!(second.next_cursor.is_none());
+
+        let paused = OpportunityFilter { statuses: Some(vec![OpportunityStatus::Paused]), ..OpportunityFilter::default() };
+        assert_eq!(ids(&contract.query_opportunities(Some(paused), None, None, None)), vec![2, 5]);
+        let lending = OpportunityFilter { categories: Some(vec!["lending".to_string()]), ..OpportunityFilter::default() };
+        assert_eq!(ids(&contract.query_opportunities(Some(lending), None, None, None)), vec![2, 4, 6]);
+    }
+
+    #[test]
+    fn score_sort_merges_status_ranges() {
+        let contract = setup_listings();
+        let filter = OpportunityFilter {
+            statuses: Some(vec![OpportunityStatus::Paused, OpportunityStatus::Active]),
+            min_score: Some(55),
+            ..OpportunityFilter::default()
+        };
+
+        let first = contract.query_opportunities(Some(filter.clone()), Some(OpportunitySort::Score), None, Some(3));
+        assert_eq!(ids(&first), vec![3, 6, 5]);
+        let second = contract.query_opportunities(Some(filter), Some(OpportunitySort::Score), first.next_cursor, Some(3));
+        assert_eq!(ids(&second), vec![4, 2, 1]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    fn scans_stop_at_the_cap_with_a_cursor() {
+        let mut contract = setup();
+        contract.update_config(None, Some(250), None);
+        for n in 0..MAX_SCANNED + 10 {
+            add_opportunity(&mut contract, &format!("pool{}", n), "staking");
+        }
+        let tagged = contract.next_opportunity_id - 1;
+        contract.update_opportunity(tagged, None, None, None, None, None, None, None, Some(vec!["rare".to_string()]));
+        let filter = OpportunityFilter { tags: Some(vec!["rare".to_string()]), ..OpportunityFilter::default() };
+
+        let first = contract.query_opportunities(Some(filter.clone()), None, None, None);
+        assert!(first.opportunities.is_empty());
+        let second = contract.query_opportunities(Some(filter), None, first.next_cursor, None);
+        assert_eq!(ids(&second), vec![tagged]);
+        assert!(second.next_cursor.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Too many filter values")]
+    fn long_filter_lists_are_rejected() {
+        let contract = setup();
+        let categories = (0..=MAX_FILTER_VALUES).map(|n| n.to_string()).collect();
+        let filter = OpportunityFilter { categories: Some(categories), ..OpportunityFilter::default() };
+        contract.query_opportunities(Some(filter), None, None, None);
+    }
+}
@@ -0,0 +1,329 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AuditRecord {
+    pub id: u64, // Position in the opportunity's audit list
+    pub auditor: String,
+    pub audited_at: Timestamp,
+    pub scope: String,
+    pub report_url: String,
+    pub report_hash: String, // Hex sha256 of the published report
+    pub recorded_at: Timestamp,
+    pub recorded_by: AccountId,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(crate = "near_sdk::serde")]
+pub enum IncidentSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum IncidentStatus {
+    Open, // Recorded by the owner or confirmed after a guardian report
+    Resolved,
+    Reported, // Filed by a guardian, awaiting owner confirmation
+    Dismissed,
+}
+
+impl IncidentStatus {
+    /// Whether the incident is still unresolved, confirmed or not
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Open | Self::Reported)
+    }
+
+    /// Whether the incident counts towards the scoring methodology
+    fn is_counted(&self) -> bool {
+        matches!(self, Self::Open | Self::Resolved)
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IncidentRecord {
+    pub id: u64, // Position in the opportunity's incident list
+    pub occurred_at: Timestamp,
+    pub severity: IncidentSeverity,
+    pub loss_amount: U128,
+    pub description: String,
+    pub status: IncidentStatus,
+    pub resolution: Option<String>,
+    pub resolved_at: Option<Timestamp>,
+    pub reported: bool, // Filed by a guardian, so the policy's penalty applies while open
+    pub safety_penalty: u16, // Temporary penalty from a guardian report, lifted on dismiss or resolve
+    pub status_before: Option<OpportunityStatus>, // Status before the report paused or flagged it, restored on dismiss
+    pub recorded_at: Timestamp,
+    pub recorded_by: AccountId,
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_audits(&self, opportunity_id: u64) -> Vec<AuditRecord> {
+        self.audits.get(&opportunity_id).unwrap_or_default()
+    }
+
+    pub fn get_incidents(&self, opportunity_id: u64, open_only: Option<bool>) -> Vec<IncidentRecord> {
+        let open_only = open_only.unwrap_or(false);
+
+        self.incidents
+            .get(&opportunity_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|incident| !open_only || incident.status.is_open())
+            .collect()
+    }
+
+    /// Safety points the methodology gives the opportunity's metrics and records, and
+    /// the latest audit and incident behind them
+    pub fn get_safety_breakdown(&self, opportunity_id: u64) -> SafetyBreakdown {
+        require!(self.opportunities.get(&opportunity_id).is_some(), "Opportunity not found");
+        let metrics = self.risk_metrics.get(&opportunity_id).unwrap_or_default();
+        let inputs = self.with_safety_records(opportunity_id, metrics);
+
+        let mut breakdown = self.scoring_params.safety(&inputs, env::block_timestamp());
+        breakdown.latest_audit_id = latest(&self.get_audits(opportunity_id), |audit| audit.audited_at).map(|audit| audit.id);
+        breakdown.latest_incident_id =
+            latest(&self.counted_incidents(opportunity_id), |incident| incident.occurred_at).map(|incident| incident.id);
+        breakdown
+    }
+
+    // Admin functions
+    pub fn add_audit(
+        &mut self,
+        opportunity_id: u64,
+        auditor: String,
+        audited_at: Timestamp,
+        scope: String,
+        report_url: String,
+        report_hash: String,
+    ) -> u64 {
+        self.assert_owner();
+        require!(self.opportunities.get(&opportunity_id).is_some(), "Opportunity not found");
+        require!(audited_at <= env::block_timestamp(), "Audit date is in the future");
+        require!(!report_url.is_empty(), "Report URL is required");
+        assert_report_hash(&report_hash);
+
+        let mut audits = self.get_audits(opportunity_id);
+        let audit = AuditRecord {
+            id: audits.len() as u64,
+            auditor,
+            audited_at,
+            scope,
+            report_url,
+            report_hash,
+            recorded_at: env::block_timestamp(),
+            recorded_by: env::predecessor_account_id(),
+        };
+        audits.push(audit.clone());
+        self.audits.insert(&opportunity_id, &audits);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"audit_added\",\"data\":[{{\"opportunity_id\":{},\"audit_id\":{},\"auditor\":\"{}\",\"audited_at\":{},\"report_hash\":\"{}\"}}]}}",
+            opportunity_id, audit.id, audit.auditor, audit.audited_at, audit.report_hash
+        ));
+
+        self.rescore_safety(opportunity_id);
+        audit.id
+    }
+
+    pub fn update_audit(
+        &mut self,
+        opportunity_id: u64,
+        audit_id: u64,
+        auditor: Option<String>,
+        audited_at: Option<Timestamp>,
+        scope: Option<String>,
+        report_url: Option<String>,
+        report_hash: Option<String>,
+    ) {
+        self.assert_owner();
+        let mut audits = self.get_audits(opportunity_id);
+        let audit = audits.get_mut(audit_id as usize).expect("Audit not found");
+
+        if let Some(auditor) = auditor {
+            audit.auditor = auditor;
+        }
+        if let Some(audited_at) = audited_at {
+            require!(audited_at <= env::block_timestamp(), "Audit date is in the future");
+            audit.audited_at = audited_at;
+        }
+        if let Some(scope) = scope {
+            audit.scope = scope;
+        }
+        if let Some(report_url) = report_url {
+            require!(!report_url.is_empty(), "Report URL is required");
+            audit.report_url = report_url;
+        }
+        if let Some(report_hash) = report_hash {
+            assert_report_hash(&report_hash);
+            audit.report_hash = report_hash;
+        }
+        self.audits.insert(&opportunity_id, &audits);
+
+        log!("Updated audit {} of opportunity {}", audit_id, opportunity_id);
+        self.rescore_safety(opportunity_id);
+    }
+
+    pub fn add_incident(
+        &mut self,
+        opportunity_id: u64,
+        occurred_at: Timestamp,
+        severity: IncidentSeverity,
+        loss_amount: U128,
+        description: String,
+    ) -> u64 {
+        self.assert_owner();
+        require!(self.opportunities.get(&opportunity_id).is_some(), "Opportunity not found");
+        require!(occurred_at <= env::block_timestamp(), "Incident date is in the future");
+
+        let mut incidents = self.get_incidents(opportunity_id, None);
+        let incident = IncidentRecord {
+            id: incidents.len() as u64,
+            occurred_at,
+            severity,
+            loss_amount,
+            description,
+            status: IncidentStatus::Open,
+            resolution: None,
+            resolved_at: None,
+            reported: false,
+            safety_penalty: 0,
+            status_before: None,
+            recorded_at: env::block_timestamp(),
+            recorded_by: env::predecessor_account_id(),
+        };
+        incidents.push(incident.clone());
+        self.incidents.insert(&opportunity_id, &incidents);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"incident_added\",\"data\":[{{\"opportunity_id\":{},\"incident_id\":{},\"severity\":\"{:?}\",\"loss_amount\":\"{}\",\"occurred_at\":{}}}]}}",
+            opportunity_id, incident.id, incident.severity, incident.loss_amount.0, incident.occurred_at
+        ));
+
+        self.rescore_safety(opportunity_id);
+        incident.id
+    }
+
+    pub fn update_incident(
+        &mut self,
+        opportunity_id: u64,
+        incident_id: u64,
+        occurred_at: Option<Timestamp>,
+        severity: Option<IncidentSeverity>,
+        loss_amount: Option<U128>,
+        description: Option<String>,
+    ) {
+        self.assert_owner();
+        let mut incidents = self.get_incidents(opportunity_id, None);
+        let incident = incidents.get_mut(incident_id as usize).expect("Incident not found");
+
+        if let Some(occurred_at) = occurred_at {
+            require!(occurred_at <= env::block_timestamp(), "Incident date is in the future");
+            incident.occurred_at = occurred_at;
+        }
+        // An open guardian report carries the policy's penalty for its severity
+        let (mut old_penalty, mut new_penalty) = (0, 0);
+        if let Some(severity) = severity {
+            if incident.reported && incident.status.is_open() {
+                old_penalty = incident.safety_penalty;
+                new_penalty = self.incident_policy.penalty_for(&severity);
+                incident.safety_penalty = new_penalty;
+            }
+            incident.severity = severity;
+        }
+        if let Some(loss_amount) = loss_amount {
+            incident.loss_amount = loss_amount;
+        }
+        if let Some(description) = description {
+            incident.description = description;
+        }
+        self.incidents.insert(&opportunity_id, &incidents);
+
+        log!("Updated incident {} of opportunity {}", incident_id, opportunity_id);
+        if new_penalty > old_penalty {
+            self.add_safety_penalty(opportunity_id, new_penalty - old_penalty);
+        } else {
+            self.lift_safety_penalty(opportunity_id, old_penalty - new_penalty);
+        }
+        self.rescore_safety(opportunity_id);
+    }
+
+    /// Closes a confirmed incident and lifts any guardian penalty; the incident keeps
+    /// counting towards the methodology's incident penalty by its date
+    pub fn resolve_incident(&mut self, opportunity_id: u64, incident_id: u64, resolution: String) {
+        self.assert_owner();
+        let mut incidents = self.get_incidents(opportunity_id, None);
+        let incident = incidents.get_mut(incident_id as usize).expect("Incident not found");
+        require!(incident.status == IncidentStatus::Open, "Only confirmed open incidents can be resolved");
+
+        incident.status = IncidentStatus::Resolved;
+        incident.resolution = Some(resolution);
+        incident.resolved_at = Some(env::block_timestamp());
+        let penalty = incident.safety_penalty;
+        self.incidents.insert(&opportunity_id, &incidents);
+        self.lift_safety_penalty(opportunity_id, penalty);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"incident_resolved\",\"data\":[{{\"opportunity_id\":{},\"incident_id\":{},\"timestamp\":{}}}]}}",
+            opportunity_id,
+            incident_id,
+            env::block_timestamp()
+        ));
+    }
+
+    // Helper functions
+    /// Marks the metrics audited or incident-hit when records say so, keeping the most
+    /// recent date from either source
+    pub(crate) fn with_safety_records(&self, opportunity_id: u64, mut metrics: RiskMetrics) -> RiskMetrics {
+        if let Some(audit) = latest(&self.get_audits(opportunity_id), |audit| audit.audited_at) {
+            metrics.is_audited = true;
+            metrics.last_audit_at = metrics.last_audit_at.max(Some(audit.audited_at));
+        }
+        if let Some(incident) = latest(&self.counted_incidents(opportunity_id), |incident| incident.occurred_at) {
+            metrics.has_incidents = true;
+            metrics.last_incident_at = metrics.last_incident_at.max(Some(incident.occurred_at));
+        }
+        metrics
+    }
+
+    /// Confirmed and resolved incidents; reports awaiting review and dismissals don't count
+    fn counted_incidents(&self, opportunity_id: u64) -> Vec<IncidentRecord> {
+        self.get_incidents(opportunity_id, None)
+            .into_iter()
+            .filter(|incident| incident.status.is_counted())
+            .collect()
+    }
+
+    /// Recomputes the safety component of a metrics-scored opportunity after its records
+    /// change. Applies under `oracle_only` too, keeping the oracle's other components;
+    /// the next finalized round replaces it again.
+    pub(crate) fn rescore_safety(&mut self, opportunity_id: u64) {
+        let Some(metrics) = self.risk_metrics.get(&opportunity_id) else {
+            return;
+        };
+
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        let score = self.score_from_metrics(&opportunity, metrics);
+        if score.safety != opportunity.safety {
+            self.refresh_score(opportunity_id, opportunity.performance, opportunity.reliability, score.safety);
+        }
+    }
+}
+
+/// Report hashes are hex sha256 digests
+fn assert_report_hash(report_hash: &str) {
+    require!(
+        report_hash.len() == 64 && report_hash.bytes().all(|byte| byte.is_ascii_hexdigit()),
+        "Report hash must be 64 hex characters"
+    );
+}
+
+fn latest<T: Clone>(records: &[T], date: impl Fn(&T) -> Timestamp) -> Option<T> {
+    records.iter().max_by_key(|record| date(record)).cloned()
+}
@@ -0,0 +1,351 @@
+use crate::*;
+
+/// Status applied when a score drops below `min_score_threshold`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LowScoreAction {
+    Pause,
+    Review,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RiskRules {
+    pub is_enabled: bool,
+    pub low_score_action: LowScoreAction,
+    pub deprecate_after: u32, // Consecutive low scores before deprecation, 0 disables
+}
+
+impl Default for RiskRules {
+    fn default() -> Self {
+        Self {
+            is_enabled: true,
+            low_score_action: LowScoreAction::Review,
+            deprecate_after: 3,
+        }
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_risk_rules(&self) -> RiskRules {
+        self.risk_rules.clone()
+    }
+
+    pub fn get_low_score_streak(&self, opportunity_id: u64) -> u32 {
+        self.low_score_streaks.get(&opportunity_id).unwrap_or(0)
+    }
+
+    // Admin functions
+    pub fn update_risk_rules(&mut self, rules: RiskRules) {
+        self.assert_owner();
+        self.risk_rules = rules;
+        log!("Updated risk rules");
+    }
+
+    /// Reactivates an opportunity moved out of Active by the risk rules
+    pub fn recover_opportunity(&mut self, opportunity_id: u64) {
+        self.assert_owner();
+
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        require!(opportunity.status != OpportunityStatus::Active, "Opportunity is already active");
+        require!(
+            opportunity.total_score >= self.config.min_score_threshold,
+            "Score is still below the threshold"
+        );
+
+        self.low_score_streaks.remove(&opportunity_id);
+        self.transition(opportunity, OpportunityStatus::Active, "owner_recovery");
+    }
+
+    // Helper functions
+    /// Applies the risk rules after a score change
+    pub(crate) fn evaluate_risk(&mut self, opportunity_id: u64) {
+        if !self.risk_rules.is_enabled {
+            return;
+        }
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+
+        if opportunity.total_score >= self.config.min_score_threshold {
+            // Recovery stays manual; only the streak resets
+            self.low_score_streaks.remove(&opportunity_id);
+            return;
+        }
+
+        let streak = self.get_low_score_streak(opportunity_id) + 1;
+        self.low_score_streaks.insert(&opportunity_id, &streak);
+
+        let deprecate_after = self.risk_rules.deprecate_after;
+        if deprecate_after > 0 && streak >= deprecate_after {
+            if opportunity.status != OpportunityStatus::Deprecated {
+                self.transition(opportunity, OpportunityStatus::Deprecated, "consecutive_low_scores");
+            }
+        } else {
+            self.flag_low_score(opportunity);
+        }
+    }
+
+    /// Applies the low score action after a rescore that isn't a scoring round,
+    /// such as a safety penalty or record change, leaving the streak alone
+    pub(crate) fn check_low_score(&mut self, opportunity_id: u64) {
+        if !self.risk_rules.is_enabled {
+            return;
+        }
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        if opportunity.total_score < self.config.min_score_threshold {
+            self.flag_low_score(opportunity);
+        }
+    }
+
+    fn flag_low_score(&mut self, opportunity: Opportunity) {
+        if opportunity.status != OpportunityStatus::Active {
+            return;
+        }
+        let status = match self.risk_rules.low_score_action {
+            LowScoreAction::Pause => OpportunityStatus::Paused,
+            LowScoreAction::Review => OpportunityStatus::UnderReview,
+        };
+        self.transition(opportunity, status, "score_below_threshold");
+    }
+
+    pub(crate) fn transition(&mut self, opportunity: Opportunity, status: OpportunityStatus, reason: &str) {
+        self.set_status(opportunity.id, status.clone());
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"risk_alert\",\"data\":[{{\"opportunity_id\":{},\"contract_id\":\"{}\",\"previous_status\":\"{:?}\",\"new_status\":\"{:?}\",\"reason\":\"{}\",\"score\":{},\"threshold\":{},\"low_score_streak\":{},\"timestamp\":{}}}]}}",
+            opportunity.id,
+            opportunity.contract_id,
+            opportunity.status,
+            status,
+            reason,
+            opportunity.total_score,
+            self.config.min_score_threshold,
+            self.get_low_score_streak(opportunity.id),
+            env::block_timestamp()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn setup_rules(low_score_action: LowScoreAction) -> (RegistryContract, u64) {
+        let mut contract = setup();
+        let opportunity_id = add_opportunity(&mut contract, "pool", "staking");
+        contract.update_risk_rules(RiskRules { is_enabled: true, low_score_action, deprecate_after: 3 });
+        contract.update_oracle_config(OracleConfig { oracle_only: false, ..OracleConfig::default() });
+        (contract, opportunity_id)
+    }
+
+    fn status_of(contract: &RegistryContract, opportunity_id: u64) -> OpportunityStatus {
+        contract.get_opportunity(opportunity_id).unwrap().status
+    }
+
+    /// Rescores from metrics with a realized APY; reliability stays at the base 15 points
+    fn rescore(contract: &mut RegistryContract, opportunity_id: u64, apy_30d: u16, is_audited: bool) {
+        let metrics = RiskMetrics { apy_30d: Some(apy_30d), is_audited, ..RiskMetrics::default() };
+        contract.update_opportunity_metrics(opportunity_id, metrics);
+    }
+
+    // Total 30 + 15 + 0 = 45, below the default threshold of 50
+    fn score_low(contract: &mut RegistryContract, opportunity_id: u64) {
+        rescore(contract, opportunity_id, 1500, false);
+    }
+
+    // Total 40 + 15 + 15 = 70
+    fn score_high(contract: &mut RegistryContract, opportunity_id: u64) {
+        rescore(contract, opportunity_id, 2000, true);
+    }
+
+    #[test]
+    fn low_score_moves_active_to_under_review() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+
+        score_low(&mut contract, id);
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::UnderReview);
+        assert_eq!(contract.get_low_score_streak(id), 1);
+    }
+
+    #[test]
+    fn low_score_moves_active_to_paused() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Pause);
+
+        score_low(&mut contract, id);
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Paused);
+    }
+
+    #[test]
+    fn score_at_threshold_stays_active() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+
+        // Total 20 + 15 + 15 = 50
+        rescore(&mut contract, id, 1000, true);
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Active);
+        assert_eq!(contract.get_low_score_streak(id), 0);
+    }
+
+    #[test]
+    fn consecutive_low_scores_deprecate_from_under_review() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+
+        score_low(&mut contract, id);
+        score_low(&mut contract, id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::UnderReview);
+        assert_eq!(contract.get_low_score_streak(id), 2);
+
+        score_low(&mut contract, id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Deprecated);
+        assert_eq!(contract.get_low_score_streak(id), 3);
+    }
+
+    #[test]
+    fn consecutive_low_scores_deprecate_from_paused() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Pause);
+
+        for _ in 0..3 {
+            score_low(&mut contract, id);
+        }
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Deprecated);
+    }
+
+    #[test]
+    fn under_review_then_paused_is_deprecated_by_the_streak() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+
+        score_low(&mut contract, id);
+        contract.update_opportunity_status(id, OpportunityStatus::Paused);
+        score_low(&mut contract, id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Paused);
+
+        score_low(&mut contract, id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Deprecated);
+    }
+
+    #[test]
+    fn recovered_score_resets_the_streak_but_not_the_status() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+
+        score_low(&mut contract, id);
+        score_low(&mut contract, id);
+        score_high(&mut contract, id);
+
+        assert_eq!(contract.get_low_score_streak(id), 0);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::UnderReview);
+
+        // The streak starts over, so two more low scores do not deprecate
+        score_low(&mut contract, id);
+        score_low(&mut contract, id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::UnderReview);
+    }
+
+    #[test]
+    fn zero_deprecate_after_never_deprecates() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+        contract.update_risk_rules(RiskRules { is_enabled: true, low_score_action: LowScoreAction::Review, deprecate_after: 0 });
+
+        for _ in 0..5 {
+            score_low(&mut contract, id);
+        }
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::UnderReview);
+        assert_eq!(contract.get_low_score_streak(id), 5);
+    }
+
+    #[test]
+    fn disabled_rules_leave_the_status_alone() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+        contract.update_risk_rules(RiskRules { is_enabled: false, ..RiskRules::default() });
+
+        score_low(&mut contract, id);
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Active);
+        assert_eq!(contract.get_low_score_streak(id), 0);
+    }
+
+    #[test]
+    fn low_initial_score_puts_new_opportunity_under_review() {
+        let mut contract = setup();
+        set_context(owner(), 0);
+        // No realized or target APY leaves performance at 0
+        let id = contract.add_opportunity(
+            "flat".to_string(),
+            String::new(),
+            "staking".to_string(),
+            account("flat"),
+            None,
+            0,
+            RiskMetrics::default(),
+            U128(0),
+            U128(0),
+            U128(0),
+            None,
+        );
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::UnderReview);
+        assert_eq!(contract.get_low_score_streak(id), 1);
+    }
+
+    #[test]
+    fn recover_reactivates_and_clears_the_streak() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Pause);
+        score_low(&mut contract, id);
+        score_high(&mut contract, id);
+
+        contract.recover_opportunity(id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Active);
+
+        // A fresh streak after recovery needs all three low scores again
+        score_low(&mut contract, id);
+        score_low(&mut contract, id);
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Paused);
+        assert_eq!(contract.get_low_score_streak(id), 2);
+    }
+
+    #[test]
+    fn recover_brings_back_deprecated_opportunities() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+        for _ in 0..3 {
+            score_low(&mut contract, id);
+        }
+        score_high(&mut contract, id);
+
+        contract.recover_opportunity(id);
+
+        assert_eq!(status_of(&contract, id), OpportunityStatus::Active);
+    }
+
+    #[test]
+    #[should_panic(expected = "Score is still below the threshold")]
+    fn recover_requires_a_score_above_the_threshold() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+        score_low(&mut contract, id);
+
+        contract.recover_opportunity(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Opportunity is already active")]
+    fn recover_rejects_active_opportunities() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+
+        contract.recover_opportunity(id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can call this function")]
+    fn only_owner_can_recover() {
+        let (mut contract, id) = setup_rules(LowScoreAction::Review);
+        score_low(&mut contract, id);
+        score_high(&mut contract, id);
+
+        set_context(account("alice"), 0);
+        contract.recover_opportunity(id);
+    }
+}
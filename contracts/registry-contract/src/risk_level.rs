@@ -0,0 +1,111 @@
+use crate::*;
+
+/// Lowest total score that maps to `level`
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RiskBand {
+    pub min_score: u16,
+    pub level: RiskLevel,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RiskBands {
+    pub bands: Vec<RiskBand>, // Ordered by min_score, starting at 0
+}
+
+impl Default for RiskBands {
+    fn default() -> Self {
+        Self {
+            bands: vec![
+                RiskBand { min_score: 0, level: RiskLevel::Caution },
+                RiskBand { min_score: 50, level: RiskLevel::Moderate },
+                RiskBand { min_score: 80, level: RiskLevel::Preferred },
+            ],
+        }
+    }
+}
+
+impl RiskBands {
+    pub(crate) fn level_for(&self, total_score: u16) -> RiskLevel {
+        self.bands
+            .iter()
+            .rev()
+            .find(|band| total_score >= band.min_score)
+            .map(|band| band.level.clone())
+            .expect("Risk bands must start at 0")
+    }
+
+    /// Inclusive score ranges that map to `level`
+    fn score_ranges(&self, level: &RiskLevel) -> Vec<(u16, u16)> {
+        self.bands
+            .iter()
+            .enumerate()
+            .filter(|(_, band)| &band.level == level)
+            .map(|(index, band)| {
+                let max_score = self.bands.get(index + 1).map_or(u16::MAX, |next| next.min_score - 1);
+                (band.min_score, max_score)
+            })
+            .collect()
+    }
+}
+
+impl RiskLevel {
+    /// Reads the free-form labels stored before risk levels were typed
+    pub(crate) fn from_label(label: &str) -> Option<Self> {
+        match label.to_lowercase().as_str() {
+            "caution" | "high" => Some(Self::Caution),
+            "moderate" | "medium" => Some(Self::Moderate),
+            "preferred" | "low" => Some(Self::Preferred),
+            _ => None,
+        }
+    }
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_risk_bands(&self) -> RiskBands {
+        self.risk_bands.clone()
+    }
+
+    /// Levels follow score bands, so this reads the score index, highest score first
+    pub fn get_opportunities_by_risk_level(&self, risk_level: RiskLevel, limit: Option<u64>) -> Vec<Opportunity> {
+        let limit = limit.unwrap_or(50);
+        let index = &self.index;
+
+        let ids = self
+            .risk_bands
+            .score_ranges(&risk_level)
+            .into_iter()
+            .rev()
+            .flat_map(move |(min_score, max_score)| index.score_range(min_score, max_score));
+        self.load_opportunities(ids, limit)
+    }
+
+    // Admin functions
+    /// Replaces the bands and relabels every opportunity
+    pub fn update_risk_bands(&mut self, bands: Vec<RiskBand>) {
+        self.assert_owner();
+        require!(bands.first().is_some_and(|band| band.min_score == 0), "First band must start at 0");
+        require!(
+            bands.windows(2).all(|pair| pair[0].min_score < pair[1].min_score),
+            "Bands must be ordered by min_score"
+        );
+        require!(bands.iter().all(|band| band.min_score <= 100), "Band scores must be between 0 and 100");
+
+        self.risk_bands = RiskBands { bands };
+
+        let mut relabeled = 0;
+        for (_, mut opportunity) in self.opportunities.to_vec() {
+            let level = self.risk_bands.level_for(opportunity.total_score);
+            if level != opportunity.risk_level {
+                opportunity.risk_level = level;
+                self.store_opportunity(&opportunity);
+                relabeled += 1;
+            }
+        }
+
+        log!("Updated risk bands, relabeled {} opportunities", relabeled);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::*;
+
+const DAY_SEC: u64 = 24 * 60 * 60;
+const MONTH_SEC: u64 = 30 * DAY_SEC;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Raw inputs the score components are computed from
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RiskMetrics {
+    pub apy_7d: Option<u16>, // Realized APY in basis points
+    pub apy_30d: Option<u16>,
+    pub success_rate: u16, // Successful intents in basis points (10000 = 100%)
+    pub avg_gas_used: u64,
+    pub avg_latency_ms: u64,
+    pub total_intents: u64,
+    pub is_audited: bool,
+    pub last_audit_at: Option<Timestamp>,
+    pub has_incidents: bool,
+    pub last_incident_at: Option<Timestamp>,
+}
+
+/// Awards `points` to values below `below`; tiers are checked in order
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoreTier {
+    pub below: u64,
+    pub points: u16,
+}
+
+/// Parameters of the Bond.Credit scoring methodology
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ScoringParams {
+    pub methodology_version: u32,
+    // Performance (0-40)
+    pub full_performance_apy: u16, // APY in basis points earning the full 40 points
+    // Reliability (0-40)
+    pub min_intents: u64, // Below this, reliability falls back to `base_reliability`
+    pub base_reliability: u16,
+    pub success_rate_points: u16,
+    pub gas_tiers: Vec<ScoreTier>,
+    pub latency_tiers: Vec<ScoreTier>, // Milliseconds
+    // Safety (0-20)
+    pub audit_points: u16,
+    pub audit_recency_tiers: Vec<ScoreTier>, // Audit age in seconds
+    pub incident_penalty: u16,
+    pub incident_recency_tiers: Vec<ScoreTier>, // Incident age in seconds, extra penalty
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        let recency = |points: [u16; 3]| {
+            vec![
+                ScoreTier { below: 6 * MONTH_SEC, points: points[0] },
+                ScoreTier { below: 12 * MONTH_SEC, points: points[1] },
+                ScoreTier { below: 24 * MONTH_SEC, points: points[2] },
+            ]
+        };
+
+        Self {
+            methodology_version: 1,
+            full_performance_apy: 2000,
+            min_intents: 10,
+            base_reliability: 15,
+            success_rate_points: 25,
+            gas_tiers: vec![
+                ScoreTier { below: 20_000_000_000_000, points: 10 },
+                ScoreTier { below: 40_000_000_000_000, points: 8 },
+                ScoreTier { below: 60_000_000_000_000, points: 6 },
+                ScoreTier { below: 80_000_000_000_000, points: 4 },
+                ScoreTier { below: 100_000_000_000_000, points: 2 },
+            ],
+            latency_tiers: vec![
+                ScoreTier { below: 1_000, points: 5 },
+                ScoreTier { below: 2_000, points: 4 },
+                ScoreTier { below: 3_000, points: 3 },
+                ScoreTier { below: 5_000, points: 2 },
+                ScoreTier { below: 10_000, points: 1 },
+            ],
+            audit_points: 15,
+            audit_recency_tiers: recency([3, 2, 1]),
+            incident_penalty: 5,
+            incident_recency_tiers: recency([3, 2, 1]),
+        }
+    }
+}
+
+/// Components computed from a set of metrics
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ComputedScore {
+    pub performance: u16,
+    pub reliability: u16,
+    pub safety: u16,
+    pub total_score: u16,
+    pub methodology_version: u32,
+}
+
+/// How the safety component was reached, with the records behind it
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SafetyBreakdown {
+    pub audit_points: u16,
+    pub audit_recency_points: u16,
+    pub incident_penalty: u16,
+    pub incident_recency_penalty: u16,
+    pub safety: u16,
+    pub latest_audit_id: Option<u64>,
+    pub latest_incident_id: Option<u64>,
+}
+
+impl ScoringParams {
+    /// Computes the 40/40/20 components; `target_apy` is used when no realized APY is reported
+    pub(crate) fn compute(&self, metrics: &RiskMetrics, target_apy: u16, now: Timestamp) -> ComputedScore {
+        let performance = self.performance(metrics, target_apy);
+        let reliability = self.reliability(metrics);
+        let safety = self.safety(metrics, now).safety;
+
+        ComputedScore {
+            performance,
+            reliability,
+            safety,
+            total_score: performance + reliability + safety,
+            methodology_version: self.methodology_version,
+        }
+    }
+
+    /// 10 points per quarter of `full_performance_apy`, linear up to 30. The top band
+    /// steps like scorer.js: 30 points until the full APY is reached, then 40.
+    fn performance(&self, metrics: &RiskMetrics, target_apy: u16) -> u16 {
+        let apy = metrics.apy_30d.or(metrics.apy_7d).unwrap_or(target_apy) as u64;
+        let full_apy = self.full_performance_apy as u64;
+        if apy >= full_apy {
+            return MAX_PERFORMANCE;
+        }
+        let points = apy * MAX_PERFORMANCE as u64 / full_apy;
+        points.min(MAX_PERFORMANCE as u64 * 3 / 4) as u16
+    }
+
+    fn reliability(&self, metrics: &RiskMetrics) -> u16 {
+        if metrics.total_intents < self.min_intents {
+            return self.base_reliability.min(MAX_RELIABILITY);
+        }
+
+        let success_rate = metrics.success_rate.min(10_000) as u64;
+        let success = (success_rate * self.success_rate_points as u64 / 10_000) as u16;
+        let gas = tier_points(&self.gas_tiers, metrics.avg_gas_used);
+        let latency = tier_points(&self.latency_tiers, metrics.avg_latency_ms);
+
+        success.saturating_add(gas).saturating_add(latency).min(MAX_RELIABILITY)
+    }
+
+    pub(crate) fn safety(&self, metrics: &RiskMetrics, now: Timestamp) -> SafetyBreakdown {
+        let age = |ts: Option<Timestamp>| ts.map(|ts| now.saturating_sub(ts) / NANOS_PER_SEC);
+        let mut breakdown = SafetyBreakdown::default();
+
+        if metrics.is_audited {
+            breakdown.audit_points = self.audit_points;
+            if let Some(audit_age) = age(metrics.last_audit_at) {
+                breakdown.audit_recency_points = tier_points(&self.audit_recency_tiers, audit_age);
+            }
+        }
+        if metrics.has_incidents {
+            breakdown.incident_penalty = self.incident_penalty;
+            if let Some(incident_age) = age(metrics.last_incident_at) {
+                breakdown.incident_recency_penalty = tier_points(&self.incident_recency_tiers, incident_age);
+            }
+        }
+
+        let score = breakdown.audit_points as i32 + breakdown.audit_recency_points as i32
+            - breakdown.incident_penalty as i32
+            - breakdown.incident_recency_penalty as i32;
+        breakdown.safety = score.clamp(0, MAX_SAFETY as i32) as u16;
+        breakdown
+    }
+}
+
+fn tier_points(tiers: &[ScoreTier], value: u64) -> u16 {
+    tiers.iter().find(|tier| value < tier.below).map_or(0, |tier| tier.points)
+}
+
+#[near_bindgen]
+impl RegistryContract {
+    // View functions
+    pub fn get_scoring_params(&self) -> ScoringParams {
+        self.scoring_params.clone()
+    }
+
+    pub fn get_opportunity_metrics(&self, opportunity_id: u64) -> Option<RiskMetrics> {
+        self.risk_metrics.get(&opportunity_id)
+    }
+
+    /// Components the current parameters would assign to the metrics
+    pub fn preview_score(&self, metrics: RiskMetrics, target_apy: u16) -> ComputedScore {
+        self.scoring_params.compute(&metrics, target_apy, env::block_timestamp())
+    }
+
+    // Admin functions
+    /// Stores new metrics and rescores the opportunity from them
+    pub fn update_opportunity_metrics(&mut self, opportunity_id: u64, metrics: RiskMetrics) -> ComputedScore {
+        self.assert_owner();
+        self.assert_not_paused();
+        self.assert_direct_scoring();
+
+        let opportunity = self.opportunities.get(&opportunity_id).expect("Opportunity not found");
+        let score = self.score_from_metrics(&opportunity, metrics);
+        self.apply_score(opportunity_id, score.performance, score.reliability, score.safety);
+        score
+    }
+
+    pub fn update_scoring_params(&mut self, params: ScoringParams) {
+        self.assert_owner();
+        require!(params.full_performance_apy > 0, "Full performance APY must be positive");
+        require!(
+            params.methodology_version >= self.scoring_params.methodology_version,
+            "Methodology version cannot go backwards"
+        );
+        for tiers in [
+            &params.gas_tiers,
+            &params.latency_tiers,
+            &params.audit_recency_tiers,
+            &params.incident_recency_tiers,
+        ] {
+            require!(
+                tiers.windows(2).all(|pair| pair[0].below < pair[1].below),
+                "Tiers must be ordered by threshold"
+            );
+        }
+
+        self.scoring_params = params;
+        log!("Updated scoring params to methodology v{}", self.scoring_params.methodology_version);
+    }
+
+    // Helper functions
+    /// Stores the metrics and returns the components they compute to, with the
+    /// opportunity's audit and incident records folded into the safety inputs
+    pub(crate) fn score_from_metrics(&mut self, opportunity: &Opportunity, metrics: RiskMetrics) -> ComputedScore {
+        let inputs = self.with_safety_records(opportunity.id, metrics.clone());
+        let score = self.scoring_params.compute(&inputs, opportunity.apy, env::block_timestamp());
+        self.risk_metrics.insert(&opportunity.id, &metrics);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-registry\",\"version\":\"1.0.0\",\"event\":\"score_computed\",\"data\":[{{\"opportunity_id\":{},\"methodology_version\":{},\"performance\":{},\"reliability\":{},\"safety\":{},\"total_score\":{},\"timestamp\":{}}}]}}",
+            opportunity.id,
+            score.methodology_version,
+            score.performance,
+            score.reliability,
+            score.safety,
+            score.total_score,
+            env::block_timestamp()
+        ));
+        score
+    }
+}
@@ -0,0 +1,44 @@
+//! Shared setup for the registry's `testing_env` unit tests
+
+use crate::*;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::testing_env;
+
+pub(crate) fn owner() -> AccountId {
+    "owner.near".parse().unwrap()
+}
+
+pub(crate) fn account(name: &str) -> AccountId {
+    format!("{}.near", name).parse().unwrap()
+}
+
+pub(crate) fn set_context(predecessor: AccountId, timestamp: Timestamp) {
+    testing_env!(VMContextBuilder::new()
+        .current_account_id("registry.near".parse().unwrap())
+        .predecessor_account_id(predecessor)
+        .block_timestamp(timestamp)
+        .build());
+}
+
+pub(crate) fn setup() -> RegistryContract {
+    set_context(owner(), 0);
+    RegistryContract::new(owner(), None)
+}
+
+/// Lists an Active opportunity in `category` scored 55 from default metrics, as the owner
+pub(crate) fn add_opportunity(contract: &mut RegistryContract, name: &str, category: &str) -> u64 {
+    set_context(owner(), env::block_timestamp());
+    contract.add_opportunity(
+        name.to_string(),
+        String::new(),
+        category.to_string(),
+        account(name),
+        None,
+        2000,
+        RiskMetrics::default(),
+        U128(0),
+        U128(0),
+        U128(0),
+        None,
+    )
+}
@@ -0,0 +1,15 @@
+#!/bin/bash
+
+# Builds the contracts used by the scenarios and runs them against a local sandbox
+set -e
+
+cd "$(dirname "$0")/.."
+
+for contract in mock-ft-contract mock-staking-pool-contract mock-lending-contract mock-amm-contract vault-contract opportunity-contract-v0; do
+    echo "🔨 Building $contract..."
+    (cd "$contract" && cargo build --release --target wasm32-unknown-unknown)
+done
+
+echo "🧪 Running sandbox scenarios..."
+cd scenario-runner
+cargo test -- --test-threads 1 "$@"
@@ -0,0 +1,186 @@
+//! Sandbox scenario runner for Bond.Credit contracts.
+//!
+//! Deploys the vault and opportunity contracts against the mock token and protocol
+//! contracts on a local near-sandbox so deposit-to-yield flows run without testnet.
+//! Build the wasm files first; `run.sh` does both.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use near_workspaces::network::Sandbox;
+use near_workspaces::types::NearToken;
+use near_workspaces::{Account, AccountId, Contract, Worker};
+use serde_json::json;
+
+pub const ONE_YOCTO: NearToken = NearToken::from_yoctonear(1);
+pub const USDC: u128 = 1_000_000; // 6 decimals
+pub const NEAR: u128 = 1_000_000_000_000_000_000_000_000; // 24 decimals
+
+/// Reads a release wasm built under `contracts/<crate_dir>/target`
+pub fn wasm(crate_dir: &str) -> Result<Vec<u8>> {
+    let file_name = format!("{}.wasm", crate_dir.replace('-', "_"));
+    let path: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"),
+        "..",
+        crate_dir,
+        "target",
+        "wasm32-unknown-unknown",
+        "release",
+        &file_name,
+    ]
+    .iter()
+    .collect();
+
+    std::fs::read(&path).with_context(|| format!("Missing {}, run scenario-runner/run.sh", path.display()))
+}
+
+pub async fn deploy(worker: &Worker<Sandbox>, crate_dir: &str, init_args: serde_json::Value) -> Result<Contract> {
+    let contract = worker.dev_deploy(&wasm(crate_dir)?).await?;
+    contract.call("new").args_json(init_args).transact().await?.into_result()?;
+    Ok(contract)
+}
+
+pub async fn deploy_token(worker: &Worker<Sandbox>, owner: &Account, symbol: &str, decimals: u8) -> Result<Contract> {
+    deploy(
+        worker,
+        "mock-ft-contract",
+        json!({
+            "owner_id": owner.id(),
+            "name": format!("Mock {}", symbol),
+            "symbol": symbol,
+            "decimals": decimals,
+            "faucet_limit": (1_000_000 * USDC).to_string(),
+        }),
+    )
+    .await
+}
+
+/// An opportunity-contract-v0 whose `strategy` calls the mock protocol at `protocol_contract`
+pub async fn deploy_opportunity(
+    worker: &Worker<Sandbox>,
+    owner: &Account,
+    strategy: &str,
+    protocol_contract: &AccountId,
+) -> Result<Contract> {
+    deploy(
+        worker,
+        "opportunity-contract-v0",
+        json!({
+            "owner_id": owner.id(),
+            "name": format!("Mock {}", strategy),
+            "description": "",
+            "strategy": strategy,
+            "target_apy": 1_200,
+            "max_allocation": (1_000 * NEAR).to_string(),
+            "total_capacity": (10_000 * NEAR).to_string(),
+            "min_allocation": NEAR.to_string(),
+            "protocol_contract": protocol_contract,
+        }),
+    )
+    .await
+}
+
+/// A vault wired to three mock tokens
+pub struct VaultScenario {
+    pub worker: Worker<Sandbox>,
+    pub owner: Account,
+    pub wnear: Contract,
+    pub usdc: Contract,
+    pub usdt: Contract,
+    pub vault: Contract,
+}
+
+impl VaultScenario {
+    pub async fn new() -> Result<Self> {
+        let worker = near_workspaces::sandbox().await?;
+        let owner = worker.dev_create_account().await?;
+
+        let wnear = deploy_token(&worker, &owner, "wNEAR", 24).await?;
+        let usdc = deploy_token(&worker, &owner, "USDC", 6).await?;
+        let usdt = deploy_token(&worker, &owner, "USDT", 6).await?;
+
+        let vault = deploy(
+            &worker,
+            "vault-contract",
+            json!({
+                "owner_id": owner.id(),
+                "wnear_contract": wnear.id(),
+                "usdc_contract": usdc.id(),
+                "usdt_contract": usdt.id(),
+                "fee_percentage": 0,
+            }),
+        )
+        .await?;
+
+        Ok(Self { worker, owner, wnear, usdc, usdt, vault })
+    }
+
+    pub async fn user(&self, usdc_amount: u128) -> Result<Account> {
+        let user = self.worker.dev_create_account().await?;
+        if usdc_amount > 0 {
+            faucet(&self.usdc, &user, usdc_amount).await?;
+        }
+        Ok(user)
+    }
+
+    /// Deposits through `ft_transfer_call`, the way wallets do
+    pub async fn deposit(&self, token: &Contract, account: &Account, amount: u128) -> Result<()> {
+        self.transfer_call(token, account, amount, "").await
+    }
+
+    pub async fn transfer_call(&self, token: &Contract, account: &Account, amount: u128, msg: &str) -> Result<()> {
+        account
+            .call(token.id(), "ft_transfer_call")
+            .args_json(json!({
+                "receiver_id": self.vault.id(),
+                "amount": amount.to_string(),
+                "msg": msg,
+            }))
+            .deposit(ONE_YOCTO)
+            .max_gas()
+            .transact()
+            .await?
+            .into_result()?;
+        Ok(())
+    }
+
+    pub async fn vault_shares(&self, account_id: &AccountId, token_type: &str) -> Result<u128> {
+        let shares: String = self
+            .vault
+            .view("get_user_vault_shares")
+            .args_json(json!({ "account_id": account_id, "token_type": token_type }))
+            .await?
+            .json()?;
+        Ok(shares.parse()?)
+    }
+}
+
+pub async fn faucet(token: &Contract, account: &Account, amount: u128) -> Result<()> {
+    account
+        .call(token.id(), "faucet")
+        .args_json(json!({ "amount": amount.to_string() }))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(())
+}
+
+pub async fn ft_balance(token: &Contract, account_id: &AccountId) -> Result<u128> {
+    let balance: String = token
+        .view("ft_balance_of")
+        .args_json(json!({ "account_id": account_id }))
+        .await?
+        .json()?;
+    Ok(balance.parse()?)
+}
+
+/// Turns on failure injection for `method_name` on any mock contract
+pub async fn set_failure(owner: &Account, contract: &Contract, method_name: &str, enabled: bool) -> Result<()> {
+    owner
+        .call(contract.id(), "set_failure")
+        .args_json(json!({ "method_name": method_name, "enabled": enabled }))
+        .transact()
+        .await?
+        .into_result()?;
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use near_workspaces::{Account, Contract};
+use scenario_runner::{deploy, set_failure};
+use serde_json::json;
+
+const AMOUNT: u128 = 1_000_000_000_000_000_000_000_000; // 1 NEAR
+
+#[tokio::test]
+async fn staking_pool_accrues_and_claims() -> Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let owner = worker.dev_create_account().await?;
+    let pool = deploy(&worker, "mock-staking-pool-contract", json!({ "owner_id": owner.id(), "reward_apy": 1_000 })).await?;
+
+    call(&owner, &pool, "stake", json!({ "account_id": owner.id(), "amount": AMOUNT.to_string() })).await?;
+    worker.fast_forward(1_000).await?;
+
+    let rewards = claim(&owner, &pool, "claim_rewards").await?;
+    assert!(rewards > 0, "staking rewards should accrue over time");
+
+    assert_injected_failure(&owner, &pool, "stake", json!({ "account_id": owner.id(), "amount": AMOUNT.to_string() })).await
+}
+
+#[tokio::test]
+async fn lending_market_accrues_and_claims() -> Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let owner = worker.dev_create_account().await?;
+    let market = deploy(&worker, "mock-lending-contract", json!({ "owner_id": owner.id() })).await?;
+    call(&owner, &market, "set_market", json!({ "token": "wrap.testnet", "supply_apy": 500 })).await?;
+
+    let args = json!({ "account_id": owner.id(), "amount": AMOUNT.to_string(), "token": "wrap.testnet" });
+    call(&owner, &market, "supply", args.clone()).await?;
+    worker.fast_forward(1_000).await?;
+
+    let rewards = claim(&owner, &market, "claim_rewards").await?;
+    assert!(rewards > 0, "lending interest should accrue over time");
+
+    assert_injected_failure(&owner, &market, "supply", args).await
+}
+
+#[tokio::test]
+async fn amm_shares_swap_fees() -> Result<()> {
+    let worker = near_workspaces::sandbox().await?;
+    let owner = worker.dev_create_account().await?;
+    let amm = deploy(&worker, "mock-amm-contract", json!({ "owner_id": owner.id(), "fee_bps": 30 })).await?;
+
+    let args = json!({ "account_id": owner.id(), "amount": AMOUNT.to_string() });
+    call(&owner, &amm, "add_liquidity", args.clone()).await?;
+    call(&owner, &amm, "swap", json!({ "amount_in": AMOUNT.to_string() })).await?;
+
+    let fees = claim(&owner, &amm, "claim_fees").await?;
+    assert_eq!(fees, AMOUNT * 30 / 10_000);
+
+    assert_injected_failure(&owner, &amm, "add_liquidity", args).await
+}
+
+async fn call(account: &Account, contract: &Contract, method: &str, args: serde_json::Value) -> Result<()> {
+    account.call(contract.id(), method).args_json(args).transact().await?.into_result()?;
+    Ok(())
+}
+
+async fn claim(account: &Account, contract: &Contract, method: &str) -> Result<u128> {
+    let claimed: String = account
+        .call(contract.id(), method)
+        .args_json(json!({ "account_id": account.id() }))
+        .transact()
+        .await?
+        .json()?;
+    Ok(claimed.parse()?)
+}
+
+/// Calls `method` with failure injection on and expects the injected panic
+async fn assert_injected_failure(
+    owner: &Account,
+    contract: &Contract,
+    method: &str,
+    args: serde_json::Value,
+) -> Result<()> {
+    set_failure(owner, contract, method, true).await?;
+    let outcome = owner.call(contract.id(), method).args_json(args).transact().await?;
+    assert!(outcome.is_failure(), "{} should fail while failure injection is on", method);
+
+    set_failure(owner, contract, method, false).await
+}
@@ -0,0 +1,81 @@
+use bond_vault_interface::{
+    merge_events, BondVault, DepositMode, DepositRequest, DepositResponse, EventQuery, VaultEvent, VaultStatus,
+    VaultToken, WithdrawRequest, WithdrawResponse,
+};
+
+use crate::*;
+
+bond_vault_interface::impl_vault_conversions! {
+    token: TokenType { WNEAR, USDC, USDT },
+    deposit_event: DepositEvent,
+    withdraw_event: WithdrawEvent,
+}
+
+#[near_bindgen]
+impl BondVault for SimpleVaultContract {
+    fn vault_status(&self) -> VaultStatus {
+        VaultStatus {
+            owner_id: self.config.owner_id.clone(),
+            total_supply: self.total_supply,
+            is_paused: self.config.is_paused,
+            deposit_mode: DepositMode::Simulated,
+        }
+    }
+
+    fn vault_token_reserves(&self, token: VaultToken) -> U128 {
+        self.get_token_reserves(token.into())
+    }
+
+    fn vault_shares(&self, account_id: AccountId, token: VaultToken) -> U128 {
+        self.get_user_vault_shares(account_id, token.into())
+    }
+
+    fn vault_events(&self, query: EventQuery) -> Vec<VaultEvent> {
+        merge_events(
+            self.deposit_events.iter().map(VaultEvent::from),
+            self.withdraw_events.iter().map(VaultEvent::from),
+            &query,
+        )
+    }
+
+    /// Simulated deposit for the caller, as `deposit` does; no tokens are transferred
+    fn vault_deposit(&mut self, request: DepositRequest) -> DepositResponse {
+        require!(
+            env::predecessor_account_id() == request.account_id,
+            "Only the depositing account can deposit"
+        );
+
+        let vault_shares_minted = self.internal_deposit(request.account_id, request.token.into(), request.amount);
+        DepositResponse { vault_shares_minted }
+    }
+
+    fn vault_withdraw(&mut self, request: WithdrawRequest) -> WithdrawResponse {
+        let amount = SimpleVaultContract::withdraw(self, request.token.into(), request.vault_shares);
+        WithdrawResponse { vault_shares_burned: request.vault_shares, amount }
+    }
+
+    fn vault_pause(&mut self) {
+        self.pause_vault();
+    }
+
+    fn vault_unpause(&mut self) {
+        self.unpause_vault();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(owner: AccountId) -> SimpleVaultContract {
+        SimpleVaultContract::new(
+            owner,
+            "wrap.near".parse().unwrap(),
+            "usdc.near".parse().unwrap(),
+            "usdt.near".parse().unwrap(),
+            0,
+        )
+    }
+
+    bond_vault_interface::bond_vault_conformance_tests!(setup, Simulated);
+}
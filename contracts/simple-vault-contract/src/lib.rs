@@ -1,14 +1,19 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::UnorderedMap;
+use near_sdk::collections::{LookupMap, UnorderedMap};
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, log, near_bindgen, AccountId, Gas, PanicOnDefault, require,
+    env, ext_contract, log, near_bindgen, AccountId, Gas, PanicOnDefault, Promise, PromiseResult, require,
     Timestamp,
 };
 
+mod bond_vault;
+mod migration;
+mod paper;
+
+pub use crate::paper::{PaperLeaderboard, PaperOpportunity, PaperPnl, PaperPosition, RegistryOpportunity};
+
 // Gas constants
-const GAS_FOR_FT_TRANSFER: Gas = Gas::from_gas(10_000_000_000_000);
 
 /// Supported token types
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -50,6 +55,13 @@ pub struct SimpleVaultContract {
     pub user_shares: UnorderedMap<AccountId, UserShares>,
     pub deposit_events: Vec<DepositEvent>,
     pub withdraw_events: Vec<WithdrawEvent>,
+    pub registry_contract: Option<AccountId>,
+    pub paper_opportunities: UnorderedMap<u64, PaperOpportunity>,
+    pub paper_positions: UnorderedMap<AccountId, Vec<PaperPosition>>,
+    pub realized_yield: UnorderedMap<(AccountId, TokenType), U128>,
+    pub paper_leaderboard: PaperLeaderboard,
+    /// PnL each account is currently ranked at on the leaderboard of a token
+    pub paper_ranks: LookupMap<(AccountId, TokenType), U128>,
 }
 
 /// Deposit event
@@ -107,6 +119,12 @@ impl SimpleVaultContract {
             user_shares: UnorderedMap::new(b"user_shares".to_vec()),
             deposit_events: Vec::new(),
             withdraw_events: Vec::new(),
+            registry_contract: None,
+            paper_opportunities: UnorderedMap::new(b"paper_opportunities".to_vec()),
+            paper_positions: UnorderedMap::new(b"paper_positions".to_vec()),
+            realized_yield: UnorderedMap::new(b"realized_yield".to_vec()),
+            paper_leaderboard: PaperLeaderboard::new(),
+            paper_ranks: LookupMap::new(b"paper_ranks".to_vec()),
         }
     }
 
@@ -180,7 +198,14 @@ impl SimpleVaultContract {
     /// Simulate deposit (for testing)
     pub fn deposit(&mut self, token_type: TokenType, amount: U128) -> U128 {
         let sender_id = env::predecessor_account_id();
-        
+        self.internal_deposit(sender_id, token_type, amount)
+    }
+
+    /// Credit simulated deposit to an account
+    fn internal_deposit(&mut self, sender_id: AccountId, token_type: TokenType, amount: U128) -> U128 {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
         log!("Simulating deposit of {} {:?} from {}", amount.0, token_type, sender_id);
 
         // Calculate vault shares to mint (1:1 for simplicity)
@@ -226,6 +251,9 @@ impl SimpleVaultContract {
 
     /// Simulate withdraw (for testing)
     pub fn withdraw(&mut self, token_type: TokenType, vault_shares_amount: U128) -> U128 {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(vault_shares_amount.0 > 0, "Amount must be greater than zero");
+
         let sender_id = env::predecessor_account_id();
         
         log!("Simulating withdrawal of {} {:?} shares from {}", vault_shares_amount.0, token_type, sender_id);
@@ -280,4 +308,24 @@ impl SimpleVaultContract {
 
         withdrawal_amount
     }
+
+    /// Pause vault operations (owner only)
+    pub fn pause_vault(&mut self) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can pause vault"
+        );
+        self.config.is_paused = true;
+        log!("Vault paused by owner");
+    }
+
+    /// Unpause vault operations (owner only)
+    pub fn unpause_vault(&mut self) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can unpause vault"
+        );
+        self.config.is_paused = false;
+        log!("Vault unpaused by owner");
+    }
 }
@@ -0,0 +1,93 @@
+use crate::*;
+
+/// State layout before paper trading
+mod v0 {
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::collections::UnorderedMap;
+    use near_sdk::json_types::U128;
+    use near_sdk::AccountId;
+
+    use crate::{DepositEvent, TokenType, UserShares, VaultConfig, WithdrawEvent};
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct SimpleVaultContract {
+        pub config: VaultConfig,
+        pub total_supply: U128,
+        pub token_reserves: UnorderedMap<TokenType, U128>,
+        pub user_shares: UnorderedMap<AccountId, UserShares>,
+        pub deposit_events: Vec<DepositEvent>,
+        pub withdraw_events: Vec<WithdrawEvent>,
+    }
+}
+
+#[near_bindgen]
+impl SimpleVaultContract {
+    /// Upgrades a deployment that predates paper trading. No registry is set and
+    /// nothing is virtually allocated.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let legacy: v0::SimpleVaultContract = env::state_read().expect("No vault state to migrate");
+
+        log!("Migrated simple vault with {} depositors", legacy.user_shares.len());
+
+        Self {
+            config: legacy.config,
+            total_supply: legacy.total_supply,
+            token_reserves: legacy.token_reserves,
+            user_shares: legacy.user_shares,
+            deposit_events: legacy.deposit_events,
+            withdraw_events: legacy.withdraw_events,
+            registry_contract: None,
+            paper_opportunities: UnorderedMap::new(b"paper_opportunities".to_vec()),
+            paper_positions: UnorderedMap::new(b"paper_positions".to_vec()),
+            realized_yield: UnorderedMap::new(b"realized_yield".to_vec()),
+            paper_leaderboard: PaperLeaderboard::new(),
+            paper_ranks: LookupMap::new(b"paper_ranks".to_vec()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn migrate_keeps_balances_and_starts_without_paper_state() {
+        testing_env!(VMContextBuilder::new().build());
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let mut user_shares = UnorderedMap::new(b"user_shares".to_vec());
+        user_shares.insert(
+            &alice,
+            &UserShares { wnear_shares: U128(0), usdc_shares: U128(400), usdt_shares: U128(0) },
+        );
+        let mut token_reserves = UnorderedMap::new(b"token_reserves".to_vec());
+        token_reserves.insert(&TokenType::USDC, &U128(400));
+        env::state_write(&v0::SimpleVaultContract {
+            config: VaultConfig {
+                owner_id: "owner.near".parse().unwrap(),
+                wnear_contract: "wrap.near".parse().unwrap(),
+                usdc_contract: "usdc.near".parse().unwrap(),
+                usdt_contract: "usdt.near".parse().unwrap(),
+                fee_percentage: 0,
+                is_paused: false,
+            },
+            total_supply: U128(400),
+            token_reserves,
+            user_shares,
+            deposit_events: Vec::new(),
+            withdraw_events: Vec::new(),
+        });
+
+        let contract = SimpleVaultContract::migrate();
+
+        assert_eq!(contract.get_user_vault_shares(alice.clone(), TokenType::USDC), U128(400));
+        assert_eq!(contract.get_token_reserves(TokenType::USDC), U128(400));
+        assert_eq!(contract.get_registry_contract(), None);
+        assert!(contract.get_paper_positions(alice).is_empty());
+        assert_eq!(contract.get_paper_trader_count(), 0);
+        assert_eq!(contract.get_paper_leaderboard_size(TokenType::USDC), 0);
+    }
+}
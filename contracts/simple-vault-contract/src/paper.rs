@@ -0,0 +1,555 @@
+use crate::*;
+use bond_math::mul_div;
+use near_sdk::collections::TreeMap;
+
+const GAS_FOR_GET_OPPORTUNITY: Gas = Gas::from_gas(10_000_000_000_000);
+const GAS_FOR_SYNC_CALLBACK: Gas = Gas::from_gas(10_000_000_000_000);
+const NANOS_PER_YEAR: u128 = 365 * 24 * 60 * 60 * 1_000_000_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+/// Fixed-point scale of the per-opportunity yield index
+const YIELD_INDEX_SCALE: u128 = 1_000_000_000_000_000_000_000_000;
+
+/// Registry interface
+#[allow(dead_code)]
+#[ext_contract(ext_registry)]
+trait Registry {
+    fn get_opportunity(&self, opportunity_id: u64) -> Option<RegistryOpportunity>;
+}
+
+#[allow(dead_code)]
+#[ext_contract(ext_self)]
+trait ExtSelf {
+    fn on_opportunity_synced(&mut self, opportunity_id: u64) -> Option<PaperOpportunity>;
+}
+
+/// Fields read from a registry opportunity
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RegistryOpportunity {
+    pub id: u64,
+    pub name: String,
+    pub apy: u16,
+    pub status: String,
+}
+
+/// Registry opportunity cached for paper trading
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PaperOpportunity {
+    pub opportunity_id: u64,
+    pub name: String,
+    pub apy: u16, // Basis points
+    pub is_active: bool,
+    /// Cumulative yield per unit of principal, scaled by `YIELD_INDEX_SCALE`, as of `synced_at`
+    pub yield_index: U128,
+    pub synced_at: Timestamp,
+}
+
+/// Virtual allocation of vault shares to a registry opportunity
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PaperPosition {
+    pub opportunity_id: u64,
+    pub token_type: TokenType,
+    pub principal: U128,
+    pub accrued_yield: U128,
+    /// Opportunity yield index at the last accrual
+    pub yield_index: U128,
+    pub last_accrued_at: Timestamp,
+}
+
+/// Simulated profit and loss of an account in one token
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PaperPnl {
+    pub account_id: AccountId,
+    pub token_type: TokenType,
+    pub allocated: U128,
+    pub realized_yield: U128,
+    pub unrealized_yield: U128,
+    pub total_pnl: U128,
+}
+
+/// Accounts ordered by the PnL recorded at their last ranking, one tree per token
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PaperLeaderboard {
+    wnear: TreeMap<(u128, AccountId), ()>,
+    usdc: TreeMap<(u128, AccountId), ()>,
+    usdt: TreeMap<(u128, AccountId), ()>,
+}
+
+impl PaperLeaderboard {
+    pub(crate) fn new() -> Self {
+        Self {
+            wnear: TreeMap::new(b"paper_leaderboard_wnear".to_vec()),
+            usdc: TreeMap::new(b"paper_leaderboard_usdc".to_vec()),
+            usdt: TreeMap::new(b"paper_leaderboard_usdt".to_vec()),
+        }
+    }
+
+    fn tree(&self, token_type: &TokenType) -> &TreeMap<(u128, AccountId), ()> {
+        match token_type {
+            TokenType::WNEAR => &self.wnear,
+            TokenType::USDC => &self.usdc,
+            TokenType::USDT => &self.usdt,
+        }
+    }
+
+    fn tree_mut(&mut self, token_type: &TokenType) -> &mut TreeMap<(u128, AccountId), ()> {
+        match token_type {
+            TokenType::WNEAR => &mut self.wnear,
+            TokenType::USDC => &mut self.usdc,
+            TokenType::USDT => &mut self.usdt,
+        }
+    }
+}
+
+#[near_bindgen]
+impl SimpleVaultContract {
+    /// Get the registry used for paper trading APYs
+    pub fn get_registry_contract(&self) -> Option<AccountId> {
+        self.registry_contract.clone()
+    }
+
+    /// Get a cached registry opportunity
+    pub fn get_paper_opportunity(&self, opportunity_id: u64) -> Option<PaperOpportunity> {
+        self.paper_opportunities.get(&opportunity_id)
+    }
+
+    /// Get all cached registry opportunities
+    pub fn get_paper_opportunities(&self) -> Vec<PaperOpportunity> {
+        self.paper_opportunities.values().collect()
+    }
+
+    /// Get virtual positions with yield accrued up to now
+    pub fn get_paper_positions(&self, account_id: AccountId) -> Vec<PaperPosition> {
+        self.paper_positions
+            .get(&account_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|position| self.accrue_position(position))
+            .collect()
+    }
+
+    /// Get simulated PnL of an account in one token
+    pub fn get_paper_pnl(&self, account_id: AccountId, token_type: TokenType) -> PaperPnl {
+        let positions = self.get_paper_positions(account_id.clone());
+        let (allocated, unrealized_yield) = positions
+            .iter()
+            .filter(|position| position.token_type == token_type)
+            .fold((0u128, 0u128), |(allocated, unrealized), position| {
+                (allocated + position.principal.0, unrealized + position.accrued_yield.0)
+            });
+        let realized_yield = self
+            .realized_yield
+            .get(&(account_id.clone(), token_type.clone()))
+            .unwrap_or(U128(0));
+
+        PaperPnl {
+            account_id,
+            token_type,
+            allocated: U128(allocated),
+            realized_yield,
+            unrealized_yield: U128(unrealized_yield),
+            total_pnl: U128(realized_yield.0 + unrealized_yield),
+        }
+    }
+
+    /// Get the number of accounts with open virtual positions
+    pub fn get_paper_trader_count(&self) -> u64 {
+        self.paper_positions.len()
+    }
+
+    /// Get the number of accounts ranked on the leaderboard of one token
+    pub fn get_paper_leaderboard_size(&self, token_type: TokenType) -> u64 {
+        self.paper_leaderboard.tree(&token_type).len()
+    }
+
+    /// Get up to `limit` accounts that traded one token, highest PnL first, continuing after
+    /// `from_account` when given. Accounts are ordered by the PnL recorded at their last
+    /// allocation, deallocation or `refresh_paper_rank`; the returned PnL is current.
+    pub fn get_paper_leaderboard(
+        &self,
+        token_type: TokenType,
+        from_account: Option<AccountId>,
+        limit: u32,
+    ) -> Vec<PaperPnl> {
+        let tree = self.paper_leaderboard.tree(&token_type);
+        let ranked: Box<dyn Iterator<Item = ((u128, AccountId), ())>> = match from_account {
+            Some(account_id) => {
+                let rank = self
+                    .paper_ranks
+                    .get(&(account_id.clone(), token_type.clone()))
+                    .unwrap_or_else(|| env::panic_str("Account is not on the leaderboard"));
+                Box::new(tree.iter_rev_from((rank.0, account_id)))
+            }
+            None => Box::new(tree.iter_rev()),
+        };
+
+        ranked
+            .take(limit as usize)
+            .map(|((_, account_id), _)| self.get_paper_pnl(account_id, token_type.clone()))
+            .collect()
+    }
+
+    /// Re-rank an account on the leaderboard of one token at its current PnL
+    pub fn refresh_paper_rank(&mut self, account_id: AccountId, token_type: TokenType) -> U128 {
+        require!(
+            self.paper_ranks.get(&(account_id.clone(), token_type.clone())).is_some(),
+            "Account is not on the leaderboard"
+        );
+        self.update_paper_rank(&account_id, &token_type)
+    }
+
+    /// Set the registry used for paper trading APYs
+    pub fn set_registry_contract(&mut self, registry_contract: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can set registry contract"
+        );
+        self.registry_contract = Some(registry_contract);
+        log!("Registry contract set to {:?}", self.registry_contract);
+    }
+
+    /// Fetch an opportunity's APY from the registry
+    pub fn sync_opportunity(&mut self, opportunity_id: u64) -> Promise {
+        let registry_contract = self
+            .registry_contract
+            .clone()
+            .unwrap_or_else(|| env::panic_str("Registry contract not set"));
+
+        ext_registry::ext(registry_contract)
+            .with_static_gas(GAS_FOR_GET_OPPORTUNITY)
+            .get_opportunity(opportunity_id)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_SYNC_CALLBACK)
+                    .on_opportunity_synced(opportunity_id),
+            )
+    }
+
+    /// Cache the registry opportunity
+    #[private]
+    pub fn on_opportunity_synced(&mut self, opportunity_id: u64) -> Option<PaperOpportunity> {
+        let opportunity = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<Option<RegistryOpportunity>>(&value)
+                    .ok()
+                    .flatten()
+            }
+            _ => None,
+        };
+
+        let opportunity = match opportunity {
+            Some(opportunity) => opportunity,
+            None => {
+                log!("Opportunity {} not found in registry", opportunity_id);
+                return None;
+            }
+        };
+
+        // Settle the yield earned so far at the previous APY before the new one applies
+        let yield_index = self
+            .paper_opportunities
+            .get(&opportunity_id)
+            .map_or(0, |cached| Self::current_yield_index(&cached));
+
+        let paper_opportunity = PaperOpportunity {
+            opportunity_id,
+            name: opportunity.name,
+            apy: opportunity.apy,
+            is_active: opportunity.status == "Active",
+            yield_index: U128(yield_index),
+            synced_at: env::block_timestamp(),
+        };
+        self.paper_opportunities.insert(&opportunity_id, &paper_opportunity);
+
+        log!("Synced opportunity {}: {} bps APY", opportunity_id, paper_opportunity.apy);
+        Some(paper_opportunity)
+    }
+
+    /// Virtually allocate vault shares to a registry opportunity
+    pub fn allocate_virtual(&mut self, token_type: TokenType, opportunity_id: u64, amount: U128) -> PaperPosition {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
+        let opportunity = self
+            .paper_opportunities
+            .get(&opportunity_id)
+            .unwrap_or_else(|| env::panic_str("Opportunity not synced"));
+        require!(opportunity.is_active, "Opportunity is not active");
+
+        let account_id = env::predecessor_account_id();
+        let available_shares = self.get_user_vault_shares(account_id.clone(), token_type.clone());
+        require!(available_shares.0 >= amount.0, "Insufficient vault shares");
+        self.adjust_user_shares(&account_id, &token_type, amount.0, false);
+
+        let mut positions = self.paper_positions.get(&account_id).unwrap_or_default();
+        let index = positions
+            .iter()
+            .position(|p| p.opportunity_id == opportunity_id && p.token_type == token_type);
+        let position = match index {
+            Some(index) => {
+                let mut position = self.accrue_position(positions[index].clone());
+                position.principal = U128(position.principal.0 + amount.0);
+                positions[index] = position.clone();
+                position
+            }
+            None => {
+                let position = PaperPosition {
+                    opportunity_id,
+                    token_type: token_type.clone(),
+                    principal: amount,
+                    accrued_yield: U128(0),
+                    yield_index: U128(Self::current_yield_index(&opportunity)),
+                    last_accrued_at: env::block_timestamp(),
+                };
+                positions.push(position.clone());
+                position
+            }
+        };
+        self.paper_positions.insert(&account_id, &positions);
+        self.update_paper_rank(&account_id, &token_type);
+
+        log!(
+            "Virtually allocated {} {:?} from {} to opportunity {}",
+            amount.0,
+            token_type,
+            account_id,
+            opportunity_id
+        );
+
+        position
+    }
+
+    /// Exit a virtual allocation, crediting principal and simulated yield back as vault shares
+    pub fn deallocate_virtual(&mut self, token_type: TokenType, opportunity_id: u64, amount: U128) -> U128 {
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
+        let account_id = env::predecessor_account_id();
+        let mut positions = self.paper_positions.get(&account_id).unwrap_or_default();
+        let index = positions
+            .iter()
+            .position(|p| p.opportunity_id == opportunity_id && p.token_type == token_type)
+            .unwrap_or_else(|| env::panic_str("No virtual position for this opportunity"));
+
+        let mut position = self.accrue_position(positions[index].clone());
+        require!(position.principal.0 >= amount.0, "Insufficient virtual allocation");
+
+        // Realize the yield earned on the withdrawn part of the principal
+        let yield_realized = mul_div(position.accrued_yield.0, amount.0, position.principal.0);
+        position.principal = U128(position.principal.0 - amount.0);
+        position.accrued_yield = U128(position.accrued_yield.0 - yield_realized);
+
+        if position.principal.0 == 0 {
+            positions.remove(index);
+        } else {
+            positions[index] = position;
+        }
+        // Realized yield is kept in `realized_yield`, so the account stays ranked
+        if positions.is_empty() {
+            self.paper_positions.remove(&account_id);
+        } else {
+            self.paper_positions.insert(&account_id, &positions);
+        }
+
+        // Simulated yield is minted as new vault shares
+        let payout = amount.0 + yield_realized;
+        self.adjust_user_shares(&account_id, &token_type, payout, true);
+        let current_reserve = self.token_reserves.get(&token_type).unwrap_or(U128(0));
+        self.token_reserves.insert(&token_type, &U128(current_reserve.0 + yield_realized));
+        self.total_supply = U128(self.total_supply.0 + yield_realized);
+
+        let key = (account_id.clone(), token_type.clone());
+        let realized = self.realized_yield.get(&key).unwrap_or(U128(0));
+        self.realized_yield.insert(&key, &U128(realized.0 + yield_realized));
+        self.update_paper_rank(&account_id, &token_type);
+
+        log!(
+            "Virtually deallocated {} {:?} for {} from opportunity {}, realized {} yield",
+            amount.0,
+            token_type,
+            account_id,
+            opportunity_id,
+            yield_realized
+        );
+
+        U128(payout)
+    }
+
+    /// Move the account's leaderboard entry to its current PnL
+    fn update_paper_rank(&mut self, account_id: &AccountId, token_type: &TokenType) -> U128 {
+        let total_pnl = self.get_paper_pnl(account_id.clone(), token_type.clone()).total_pnl;
+        let key = (account_id.clone(), token_type.clone());
+        let tree = self.paper_leaderboard.tree_mut(token_type);
+        if let Some(previous) = self.paper_ranks.insert(&key, &total_pnl) {
+            tree.remove(&(previous.0, account_id.clone()));
+        }
+        tree.insert(&(total_pnl.0, account_id.clone()), &());
+        total_pnl
+    }
+
+    /// Yield index of an opportunity at the current block; it stops growing while the opportunity is inactive
+    fn current_yield_index(opportunity: &PaperOpportunity) -> u128 {
+        if !opportunity.is_active {
+            return opportunity.yield_index.0;
+        }
+        let elapsed = env::block_timestamp().saturating_sub(opportunity.synced_at) as u128;
+        opportunity.yield_index.0
+            + mul_div(
+                opportunity.apy as u128 * elapsed,
+                YIELD_INDEX_SCALE,
+                BPS_DENOMINATOR * NANOS_PER_YEAR,
+            )
+    }
+
+    /// Accrue simple interest at the APYs the opportunity had since the last accrual
+    fn accrue_position(&self, mut position: PaperPosition) -> PaperPosition {
+        let yield_index = self
+            .paper_opportunities
+            .get(&position.opportunity_id)
+            .map_or(position.yield_index.0, |opportunity| Self::current_yield_index(&opportunity));
+
+        let earned = mul_div(
+            position.principal.0,
+            yield_index.saturating_sub(position.yield_index.0),
+            YIELD_INDEX_SCALE,
+        );
+        position.accrued_yield = U128(position.accrued_yield.0 + earned);
+        position.yield_index = U128(yield_index);
+        position.last_accrued_at = env::block_timestamp();
+        position
+    }
+
+    fn adjust_user_shares(&mut self, account_id: &AccountId, token_type: &TokenType, amount: u128, is_increase: bool) {
+        let mut user_shares = self.user_shares.get(account_id).unwrap_or(UserShares {
+            wnear_shares: U128(0),
+            usdc_shares: U128(0),
+            usdt_shares: U128(0),
+        });
+
+        let shares = match token_type {
+            TokenType::WNEAR => &mut user_shares.wnear_shares,
+            TokenType::USDC => &mut user_shares.usdc_shares,
+            TokenType::USDT => &mut user_shares.usdt_shares,
+        };
+        shares.0 = if is_increase { shares.0 + amount } else { shares.0 - amount };
+
+        self.user_shares.insert(account_id, &user_shares);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    const YEAR: Timestamp = NANOS_PER_YEAR as Timestamp;
+
+    fn account(name: &str) -> AccountId {
+        format!("{}.near", name).parse().unwrap()
+    }
+
+    fn set_context(predecessor: AccountId, timestamp: Timestamp) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(predecessor)
+            .block_timestamp(timestamp)
+            .build());
+    }
+
+    /// Vault with opportunity 1 synced at 10% APY
+    fn setup() -> SimpleVaultContract {
+        set_context(account("owner"), 0);
+        let mut contract = SimpleVaultContract::new(
+            account("owner"),
+            "wrap.near".parse().unwrap(),
+            "usdc.near".parse().unwrap(),
+            "usdt.near".parse().unwrap(),
+            0,
+        );
+        contract.paper_opportunities.insert(
+            &1,
+            &PaperOpportunity {
+                opportunity_id: 1,
+                name: "Lending".to_string(),
+                apy: 1000,
+                is_active: true,
+                yield_index: U128(0),
+                synced_at: 0,
+            },
+        );
+        contract
+    }
+
+    fn allocate(contract: &mut SimpleVaultContract, account_id: &AccountId, amount: u128) {
+        set_context(account_id.clone(), 0);
+        contract.deposit(TokenType::USDC, U128(amount));
+        contract.allocate_virtual(TokenType::USDC, 1, U128(amount));
+    }
+
+    #[test]
+    fn full_deallocation_removes_the_account_entry() {
+        let mut contract = setup();
+        let alice = account("alice");
+        allocate(&mut contract, &alice, 1_000);
+
+        set_context(alice.clone(), YEAR);
+        contract.deallocate_virtual(TokenType::USDC, 1, U128(400));
+        assert_eq!(contract.get_paper_trader_count(), 1);
+
+        let payout = contract.deallocate_virtual(TokenType::USDC, 1, U128(600));
+
+        assert_eq!(payout, U128(660));
+        assert_eq!(contract.get_paper_trader_count(), 0);
+        assert!(contract.paper_positions.get(&alice).is_none());
+        assert_eq!(contract.get_paper_pnl(alice, TokenType::USDC).realized_yield, U128(100));
+    }
+
+    #[test]
+    fn leaderboard_pages_through_accounts_by_pnl() {
+        let mut contract = setup();
+        for (name, amount) in [("alice", 1_000), ("bob", 3_000), ("carol", 2_000)] {
+            allocate(&mut contract, &account(name), amount);
+        }
+        set_context(account("keeper"), YEAR);
+        for name in ["alice", "bob", "carol"] {
+            contract.refresh_paper_rank(account(name), TokenType::USDC);
+        }
+
+        let first_page = contract.get_paper_leaderboard(TokenType::USDC, None, 2);
+        let last_page = contract.get_paper_leaderboard(TokenType::USDC, Some(account("carol")), 2);
+
+        let pnl: Vec<u128> = first_page.iter().map(|entry| entry.total_pnl.0).collect();
+        assert_eq!(pnl, vec![300, 200]);
+        assert_eq!(last_page.len(), 1);
+        assert_eq!(last_page[0].account_id, account("alice"));
+        assert!(contract.get_paper_leaderboard(TokenType::USDC, Some(account("alice")), 2).is_empty());
+        assert!(contract.get_paper_leaderboard(TokenType::USDT, None, u32::MAX).is_empty());
+    }
+
+    #[test]
+    fn closed_out_accounts_stay_ranked_by_realized_pnl() {
+        let mut contract = setup();
+        let (alice, bob) = (account("alice"), account("bob"));
+        allocate(&mut contract, &alice, 1_000);
+        allocate(&mut contract, &bob, 500);
+
+        set_context(alice.clone(), YEAR);
+        contract.deallocate_virtual(TokenType::USDC, 1, U128(1_000));
+
+        let leaderboard = contract.get_paper_leaderboard(TokenType::USDC, None, 10);
+        assert_eq!(contract.get_paper_trader_count(), 1);
+        assert_eq!(contract.get_paper_leaderboard_size(TokenType::USDC), 2);
+        assert_eq!(leaderboard[0].account_id, alice);
+        assert_eq!((leaderboard[0].allocated, leaderboard[0].realized_yield), (U128(0), U128(100)));
+        assert_eq!(leaderboard[1].account_id, bob);
+    }
+
+    #[test]
+    #[should_panic(expected = "Account is not on the leaderboard")]
+    fn refresh_rejects_accounts_that_never_traded() {
+        let mut contract = setup();
+        contract.refresh_paper_rank(account("alice"), TokenType::USDC);
+    }
+}
@@ -0,0 +1,98 @@
+use bond_vault_interface::{
+    merge_events, BondVault, DepositMode, DepositRequest, DepositResponse, EventQuery, VaultEvent, VaultStatus,
+    VaultToken, WithdrawRequest, WithdrawResponse,
+};
+
+use crate::*;
+
+bond_vault_interface::impl_vault_conversions! {
+    token: TokenType { WNEAR, USDC },
+    deposit_event: DepositEvent,
+    withdraw_event: WithdrawEvent,
+}
+
+/// `BondVault` over the v0 account model, where per-token balances stand in for
+/// per-token vault shares
+#[near_bindgen]
+impl BondVault for VaultContract {
+    fn vault_status(&self) -> VaultStatus {
+        VaultStatus {
+            owner_id: self.config.owner_id.clone(),
+            total_supply: self.total_supply,
+            is_paused: self.config.is_paused,
+            deposit_mode: DepositMode::TokenTransfer,
+        }
+    }
+
+    fn vault_token_reserves(&self, token: VaultToken) -> U128 {
+        match TokenType::from(token) {
+            TokenType::WNEAR => self.token_reserves.wnear_reserve,
+            TokenType::USDC => self.token_reserves.usdc_reserve,
+        }
+    }
+
+    fn vault_shares(&self, account_id: AccountId, token: VaultToken) -> U128 {
+        let token_type = TokenType::from(token);
+        match self.accounts.get(&account_id) {
+            Some(account) => match token_type {
+                TokenType::WNEAR => account.wnear_balance,
+                TokenType::USDC => account.usdc_balance,
+            },
+            None => U128(0),
+        }
+    }
+
+    fn vault_events(&self, query: EventQuery) -> Vec<VaultEvent> {
+        merge_events(
+            self.deposit_events.iter().map(VaultEvent::from),
+            self.withdraw_events.iter().map(VaultEvent::from),
+            &query,
+        )
+    }
+
+    /// Credits tokens that already reached the vault, as `ft_on_transfer` does
+    fn vault_deposit(&mut self, request: DepositRequest) -> DepositResponse {
+        assert!(!self.config.is_paused, "Vault is paused");
+        assert!(request.amount.0 > 0, "Amount must be greater than zero");
+
+        let token_type = TokenType::from(request.token);
+        let token_contract = match token_type {
+            TokenType::WNEAR => &self.config.wnear_contract,
+            TokenType::USDC => &self.config.usdc_contract,
+        };
+        assert!(&env::predecessor_account_id() == token_contract, "Only the token contract can deposit");
+        let vault_shares_minted = self.credit_account(&request.account_id, &token_type, request.amount);
+        self.record_deposit_event(&request.account_id, &token_type, request.amount);
+
+        DepositResponse { vault_shares_minted }
+    }
+
+    fn vault_withdraw(&mut self, request: WithdrawRequest) -> WithdrawResponse {
+        // Shares and tokens are 1:1 in v0
+        VaultContract::withdraw(self, request.token.into(), request.vault_shares);
+
+        WithdrawResponse {
+            vault_shares_burned: request.vault_shares,
+            amount: request.vault_shares,
+        }
+    }
+
+    fn vault_pause(&mut self) {
+        self.pause_vault();
+    }
+
+    fn vault_unpause(&mut self) {
+        self.unpause_vault();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(owner: AccountId) -> VaultContract {
+        VaultContract::new(owner, "wrap.near".parse().unwrap(), "usdc.near".parse().unwrap())
+    }
+
+    bond_vault_interface::bond_vault_conformance_tests!(setup);
+}
@@ -1,22 +1,22 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::UnorderedMap;
-use near_sdk::json_types::{U128, U64};
+use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, log, near_bindgen, AccountId, Balance, Gas, PanicOnDefault, Promise, PromiseResult,
-    PublicKey, Timestamp,
+    env, log, near_bindgen, AccountId, Gas, NearToken, PanicOnDefault, Promise, PromiseResult,
+    Timestamp,
 };
 
+use bond_vault_interface::{BondVault, DepositRequest};
+
+mod bond_vault;
+
 // Gas constants
-const GAS_FOR_FT_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas(25_000_000_000_000);
-const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
-const GAS_FOR_NFT_TRANSFER_CALL: Gas = Gas(10_000_000_000_000);
+const GAS_FOR_FT_TRANSFER: Gas = Gas::from_gas(10_000_000_000_000);
+const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_gas(25_000_000_000_000);
 
 // Storage keys
 const STORAGE_KEY_ACCOUNTS: &[u8] = b"accounts";
-const STORAGE_KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
-const STORAGE_KEY_TOKEN_RESERVES: &[u8] = b"token_reserves";
 
 /// Supported token types
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -65,7 +65,7 @@ pub struct TokenReserves {
 }
 
 /// Deposit event
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct DepositEvent {
     pub account_id: AccountId,
@@ -73,11 +73,11 @@ pub struct DepositEvent {
     pub amount: U128,
     pub vault_shares_minted: U128,
     pub timestamp: Timestamp,
-    pub tx_hash: String,
+    pub tx_hash: String, // Block height; near-sdk 5 exposes no block or transaction hash
 }
 
 /// Withdraw event
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Debug, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct WithdrawEvent {
     pub account_id: AccountId,
@@ -85,7 +85,7 @@ pub struct WithdrawEvent {
     pub amount: U128,
     pub vault_shares_burned: U128,
     pub timestamp: Timestamp,
-    pub tx_hash: String,
+    pub tx_hash: String, // Block height; near-sdk 5 exposes no block or transaction hash
 }
 
 /// Main vault contract
@@ -174,11 +174,31 @@ impl VaultContract {
         let sender_id = env::predecessor_account_id();
         log!("Deposit: {} {} from {}", amount.0, format!("{:?}", token_type), sender_id);
 
+        self.credit_account(&sender_id, &token_type, amount);
+
+        // Transfer tokens from user to vault
+        let token_contract = token_type.get_contract_id();
+        
+        Promise::new(token_contract)
+            .function_call(
+                "ft_transfer_call".to_string(),
+                serde_json::to_vec(&serde_json::json!({
+                    "receiver_id": env::current_account_id(),
+                    "amount": amount.0.to_string(),
+                    "msg": ""
+                })).unwrap(),
+                NearToken::from_yoctonear(1),
+                GAS_FOR_FT_TRANSFER_CALL,
+            )
+    }
+
+    /// Mint vault shares and credit the deposited balance to an account
+    fn credit_account(&mut self, sender_id: &AccountId, token_type: &TokenType, amount: U128) -> U128 {
         // Calculate vault shares to mint (1:1 for now, can be improved with proper LP calculation)
         let shares_to_mint = amount;
 
         // Update user account
-        let mut user_account = self.accounts.get(&sender_id).unwrap_or(VaultAccount {
+        let mut user_account = self.accounts.get(sender_id).unwrap_or(VaultAccount {
             account_id: sender_id.clone(),
             vault_shares: U128(0),
             wnear_balance: U128(0),
@@ -198,23 +218,10 @@ impl VaultContract {
             }
         }
 
-        self.accounts.insert(&sender_id, &user_account);
+        self.accounts.insert(sender_id, &user_account);
         self.total_supply = U128(self.total_supply.0 + shares_to_mint.0);
 
-        // Transfer tokens from user to vault
-        let token_contract = token_type.get_contract_id();
-        
-        Promise::new(token_contract)
-            .function_call(
-                "ft_transfer_call".to_string(),
-                serde_json::to_vec(&serde_json::json!({
-                    "receiver_id": env::current_account_id(),
-                    "amount": amount.0.to_string(),
-                    "msg": ""
-                })).unwrap(),
-                1,
-                GAS_FOR_FT_TRANSFER_CALL,
-            )
+        shares_to_mint
     }
 
     /// Callback after token transfer
@@ -227,46 +234,47 @@ impl VaultContract {
     ) {
         match env::promise_result(0) {
             PromiseResult::Successful(_) => {
-                // Log deposit event
-                let deposit_event = DepositEvent {
-                    account_id: sender_id.clone(),
-                    token_type: token_type.clone(),
-                    amount,
-                    vault_shares_minted: amount, // 1:1 for now
-                    timestamp: env::block_timestamp(),
-                    tx_hash: env::block_hash().to_string(),
-                };
-
-                self.deposit_events.push(deposit_event.clone());
-                
-                // Limit events to last 1000
-                if self.deposit_events.len() > 1000 {
-                    self.deposit_events.remove(0);
-                }
-
-                log!("Deposit successful: {} {} from {}", amount.0, format!("{:?}", token_type), sender_id);
-                
-                // Emit event for indexing
-                env::log_str(&format!(
-                    "EVENT_JSON:{{\"standard\":\"bond-credit-vault\",\"version\":\"1.0.0\",\"event\":\"deposit\",\"data\":[{{\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_minted\":\"{}\",\"timestamp\":{}}}]}}",
-                    sender_id,
-                    token_type,
-                    amount.0,
-                    amount.0,
-                    env::block_timestamp()
-                ));
+                self.record_deposit_event(&sender_id, &token_type, amount);
             }
             PromiseResult::Failed => {
                 // Revert the changes if transfer failed
                 log!("Token transfer failed, reverting deposit for {}", sender_id);
                 panic!("Token transfer failed");
             }
-            _ => {
-                panic!("Unexpected promise result");
-            }
         }
     }
 
+    /// Record and emit a deposit event
+    fn record_deposit_event(&mut self, sender_id: &AccountId, token_type: &TokenType, amount: U128) {
+        let deposit_event = DepositEvent {
+            account_id: sender_id.clone(),
+            token_type: token_type.clone(),
+            amount,
+            vault_shares_minted: amount, // 1:1 for now
+            timestamp: env::block_timestamp(),
+            tx_hash: env::block_height().to_string(),
+        };
+
+        self.deposit_events.push(deposit_event.clone());
+        
+        // Limit events to last 1000
+        if self.deposit_events.len() > 1000 {
+            self.deposit_events.remove(0);
+        }
+
+        log!("Deposit successful: {} {} from {}", amount.0, format!("{:?}", token_type), sender_id);
+        
+        // Emit event for indexing
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"standard\":\"bond-credit-vault\",\"version\":\"1.0.0\",\"event\":\"deposit\",\"data\":[{{\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_minted\":\"{}\",\"timestamp\":{}}}]}}",
+            sender_id,
+            token_type,
+            amount.0,
+            amount.0,
+            env::block_timestamp()
+        ));
+    }
+
     /// Withdraw tokens from the vault
     pub fn withdraw(&mut self, token_type: TokenType, amount: U128) -> Promise {
         assert!(!self.config.is_paused, "Vault is paused");
@@ -275,9 +283,9 @@ impl VaultContract {
         let sender_id = env::predecessor_account_id();
         log!("Withdraw: {} {} from {}", amount.0, format!("{:?}", token_type), sender_id);
 
-        // Check if user has enough vault shares
+        // Check if user has enough vault shares; an unknown account holds none
         let user_account = self.accounts.get(&sender_id)
-            .expect("Account not found");
+            .unwrap_or_else(|| panic!("Insufficient vault shares"));
         
         let required_shares = amount; // 1:1 for now
         
@@ -327,7 +335,7 @@ impl VaultContract {
             amount,
             vault_shares_burned: required_shares,
             timestamp: env::block_timestamp(),
-            tx_hash: env::block_hash().to_string(),
+            tx_hash: env::block_height().to_string(),
         };
 
         self.withdraw_events.push(withdraw_event.clone());
@@ -357,14 +365,14 @@ impl VaultContract {
                     "receiver_id": sender_id,
                     "amount": amount.0.to_string()
                 })).unwrap(),
-                1,
+                NearToken::from_yoctonear(1),
                 GAS_FOR_FT_TRANSFER,
             )
     }
 
     /// Get deposit events
     pub fn get_deposit_events(&self, limit: Option<u32>) -> Vec<DepositEvent> {
-        let limit = limit.unwrap_or(50);
+        let limit = limit.unwrap_or(50) as usize;
         let start = if self.deposit_events.len() > limit {
             self.deposit_events.len() - limit
         } else {
@@ -376,7 +384,7 @@ impl VaultContract {
 
     /// Get withdraw events
     pub fn get_withdraw_events(&self, limit: Option<u32>) -> Vec<WithdrawEvent> {
-        let limit = limit.unwrap_or(50);
+        let limit = limit.unwrap_or(50) as usize;
         let start = if self.withdraw_events.len() > limit {
             self.withdraw_events.len() - limit
         } else {
@@ -418,14 +426,22 @@ impl VaultContract {
 
     /// Pause vault operations (owner only)
     pub fn pause_vault(&mut self) {
-        self.assert_owner();
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.config.owner_id,
+            "Only owner can pause vault"
+        );
         self.config.is_paused = true;
         log!("Vault paused by owner");
     }
 
     /// Unpause vault operations (owner only)
     pub fn unpause_vault(&mut self) {
-        self.assert_owner();
+        assert_eq!(
+            env::predecessor_account_id(),
+            self.config.owner_id,
+            "Only owner can unpause vault"
+        );
         self.config.is_paused = false;
         log!("Vault unpaused by owner");
     }
@@ -460,6 +476,7 @@ impl VaultContract {
     ) -> U128 {
         // For now, we don't handle the msg parameter
         // In future versions, this could specify which token type to deposit
+        let _ = msg;
         
         // Determine token type based on the contract that called this
         let token_contract = env::predecessor_account_id();
@@ -471,8 +488,8 @@ impl VaultContract {
             panic!("Unsupported token contract");
         };
 
-        // Call the on_tokens_transferred callback
-        self.on_tokens_transferred(sender_id, amount, token_type);
+        // The tokens have arrived, so credit them through the common vault interface
+        self.vault_deposit(DepositRequest { account_id: sender_id, token: token_type.into(), amount });
         
         U128(0) // Return 0 to indicate we don't want to refund any tokens
     }
@@ -0,0 +1,131 @@
+use crate::*;
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_allowance(&self, owner_id: AccountId, spender_id: AccountId, token_type: TokenType) -> U128 {
+        self.allowances
+            .get(&(owner_id, spender_id, token_type))
+            .unwrap_or(U128(0))
+    }
+
+    /// Allows `spender` to withdraw up to `amount` vault shares of `token_type` on the caller's behalf.
+    /// Requires 1 yoctoNEAR so a function-call access key can't grant allowances.
+    #[payable]
+    pub fn approve_withdrawal(&mut self, spender: AccountId, token_type: TokenType, amount: U128) {
+        assert_one_yocto();
+        let owner_id = env::predecessor_account_id();
+        require!(spender != owner_id, "Cannot approve self");
+
+        self.allowances
+            .insert(&(owner_id.clone(), spender.clone(), token_type.clone()), &amount);
+        self.emit_approval_event(&owner_id, &spender, &token_type, amount);
+    }
+
+    pub fn revoke_approval(&mut self, spender: AccountId, token_type: TokenType) {
+        let owner_id = env::predecessor_account_id();
+
+        self.allowances
+            .remove(&(owner_id.clone(), spender.clone(), token_type.clone()));
+        self.emit_approval_event(&owner_id, &spender, &token_type, U128(0));
+    }
+
+    /// Withdraws `owner`'s shares to `receiver`, spending the caller's allowance
+    pub fn withdraw_from(
+        &mut self,
+        owner: AccountId,
+        receiver: AccountId,
+        token_type: TokenType,
+        shares: U128,
+        min_amount_out: Option<U128>,
+    ) -> Promise {
+        require!(!self.config.is_paused, "Vault is paused");
+
+        let spender_id = env::predecessor_account_id();
+        let key = (owner.clone(), spender_id.clone(), token_type.clone());
+        let allowance = self.allowances.get(&key).unwrap_or(U128(0));
+        require!(allowance.0 >= shares.0, "Insufficient withdrawal allowance");
+
+        let withdrawal = self.internal_withdraw(&owner, &token_type, shares, min_amount_out);
+        let shares = withdrawal.vault_shares_burned;
+
+        // Only the shares the circuit breaker let through spend the allowance
+        let remaining = U128(allowance.0 - shares.0);
+        if remaining.0 == 0 {
+            self.allowances.remove(&key);
+        } else {
+            self.allowances.insert(&key, &remaining);
+        }
+
+        log!(
+            "Delegated withdrawal: {} withdrew {} {:?} shares of {} to {}",
+            spender_id,
+            shares.0,
+            token_type,
+            owner,
+            receiver
+        );
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"withdrawal_allowance_spent\",\"owner_id\":\"{}\",\"spender_id\":\"{}\",\"receiver_id\":\"{}\",\"token_type\":\"{:?}\",\"vault_shares_burned\":\"{}\",\"amount\":\"{}\",\"remaining_allowance\":\"{}\",\"timestamp\":{}}}",
+            owner,
+            spender_id,
+            receiver,
+            token_type,
+            shares.0,
+            withdrawal.amount.0,
+            remaining.0,
+            env::block_timestamp()
+        ));
+
+        let token_contract = self.get_token_contract(&token_type);
+        ext_fungible_token::ext(token_contract)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver, withdrawal.amount, Some(format!("Withdraw {:?}", token_type)))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_withdraw_from_resolved(owner, spender_id, token_type, shares, withdrawal.amount)
+            )
+    }
+
+    /// Gives the owner back their shares and the spender its allowance if the transfer failed
+    #[private]
+    pub fn on_withdraw_from_resolved(
+        &mut self,
+        owner: AccountId,
+        spender_id: AccountId,
+        token_type: TokenType,
+        shares: U128,
+        amount: U128,
+    ) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+
+        self.restore_withdrawal(&owner, &token_type, shares, amount);
+        let key = (owner, spender_id, token_type);
+        let allowance = self.allowances.get(&key).unwrap_or(U128(0));
+        self.allowances.insert(&key, &U128(allowance.0 + shares.0));
+    }
+
+    fn emit_approval_event(&self, owner_id: &AccountId, spender_id: &AccountId, token_type: &TokenType, amount: U128) {
+        log!(
+            "Withdrawal approval: {} allowed {} to withdraw {} {:?} shares",
+            owner_id,
+            spender_id,
+            amount.0,
+            token_type
+        );
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"withdrawal_approval\",\"owner_id\":\"{}\",\"spender_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"timestamp\":{}}}",
+            owner_id,
+            spender_id,
+            token_type,
+            amount.0,
+            env::block_timestamp()
+        ));
+    }
+}
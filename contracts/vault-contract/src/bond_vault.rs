@@ -0,0 +1,107 @@
+use bond_vault_interface::{
+    merge_events, BondVault, DepositMode, DepositRequest, DepositResponse, EventQuery, VaultEvent, VaultStatus,
+    VaultToken, WithdrawRequest, WithdrawResponse,
+};
+
+use crate::*;
+
+bond_vault_interface::impl_vault_conversions! {
+    token: TokenType { WNEAR, USDC, USDT },
+    deposit_event: DepositEvent,
+    withdraw_event: WithdrawEvent,
+}
+
+#[near_bindgen]
+impl BondVault for VaultContract {
+    fn vault_status(&self) -> VaultStatus {
+        VaultStatus {
+            owner_id: self.config.owner_id.clone(),
+            total_supply: self.total_supply,
+            is_paused: self.config.is_paused,
+            deposit_mode: DepositMode::TokenTransfer,
+        }
+    }
+
+    fn vault_token_reserves(&self, token: VaultToken) -> U128 {
+        self.get_token_reserves(token.into())
+    }
+
+    fn vault_shares(&self, account_id: AccountId, token: VaultToken) -> U128 {
+        self.get_user_vault_shares(account_id, token.into())
+    }
+
+    fn vault_events(&self, query: EventQuery) -> Vec<VaultEvent> {
+        merge_events(
+            self.deposit_events.iter().map(VaultEvent::from),
+            self.withdraw_events.iter().map(VaultEvent::from),
+            &query,
+        )
+    }
+
+    /// Credits tokens that already reached the vault, as `ft_on_transfer` does
+    fn vault_deposit(&mut self, request: DepositRequest) -> DepositResponse {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(request.amount.0 > 0, "Amount must be greater than zero");
+        let token_type: TokenType = request.token.into();
+        require!(
+            env::predecessor_account_id() == self.get_token_contract(&token_type),
+            "Only the token contract can deposit"
+        );
+
+        let vault_shares_minted = self.internal_deposit(request.account_id, token_type, request.amount, None, None);
+        DepositResponse { vault_shares_minted }
+    }
+
+    fn vault_withdraw(&mut self, request: WithdrawRequest) -> WithdrawResponse {
+        require!(!self.config.is_paused, "Vault is paused");
+
+        let sender_id = env::predecessor_account_id();
+        let token_type: TokenType = request.token.into();
+        let withdrawal = self.internal_withdraw(&sender_id, &token_type, request.vault_shares, None);
+
+        ext_fungible_token::ext(self.get_token_contract(&token_type))
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(sender_id.clone(), withdrawal.amount, Some(format!("Withdraw {:?}", token_type)))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_withdraw_resolved(
+                        sender_id,
+                        token_type,
+                        withdrawal.vault_shares_burned,
+                        withdrawal.amount,
+                    )
+            );
+
+        WithdrawResponse {
+            vault_shares_burned: withdrawal.vault_shares_burned,
+            amount: withdrawal.amount,
+        }
+    }
+
+    fn vault_pause(&mut self) {
+        self.pause_vault();
+    }
+
+    fn vault_unpause(&mut self) {
+        self.unpause_vault();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(owner: AccountId) -> VaultContract {
+        VaultContract::new(
+            owner,
+            "wrap.near".parse().unwrap(),
+            "usdc.near".parse().unwrap(),
+            "usdt.near".parse().unwrap(),
+            0,
+        )
+    }
+
+    bond_vault_interface::bond_vault_conformance_tests!(setup);
+}
@@ -0,0 +1,378 @@
+use bond_math::mul_div;
+use crate::*;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CircuitBreakerConfig {
+    pub is_enabled: bool,
+    pub guardian_id: Option<AccountId>,
+    pub window_sec: u64, // Length of the rolling window
+    pub max_outflow_bps: u16, // Share of reserves that may leave per rolling window
+    pub throttled_withdrawal_bps: u16, // Per-withdrawal cap while throttled, share of current reserves
+    pub max_throttled_outflow_bps: u16, // Total cap while throttled, share of reserves at the trip
+}
+
+/// Outflow tracked for one token. The rolling outflow counts all of the current
+/// window and the overlapping share of the previous one.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OutflowWindow {
+    pub token_type: TokenType,
+    pub window_start: Timestamp,
+    pub reserves_at_start: U128,
+    pub outflow: U128,
+    pub previous_outflow: U128, // Outflow of the window before `window_start`
+    pub is_throttled: bool,
+    pub tripped_at: Option<Timestamp>,
+    pub throttled_outflow: U128, // Let through since the trip
+    pub throttled_limit: U128,
+}
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_circuit_breaker_config(&self) -> CircuitBreakerConfig {
+        self.circuit_breaker_config.clone()
+    }
+
+    pub fn get_outflow_window(&self, token_type: TokenType) -> OutflowWindow {
+        self.current_outflow_window(&token_type)
+    }
+
+    /// Largest withdrawal of `token_type` the breaker currently lets through in full;
+    /// larger withdrawals are filled up to this amount
+    pub fn get_remaining_outflow(&self, token_type: TokenType) -> U128 {
+        let reserves = self.get_token_reserves(token_type.clone());
+        if !self.circuit_breaker_config.is_enabled {
+            return reserves;
+        }
+
+        let window = self.current_outflow_window(&token_type);
+        if window.is_throttled {
+            return U128(self.throttled_allowance(&token_type, &window));
+        }
+        let remaining = self.outflow_limit(&window).saturating_sub(self.rolling_outflow(&window));
+        U128(remaining.min(reserves.0))
+    }
+
+    // Admin functions
+    pub fn update_circuit_breaker_config(&mut self, circuit_breaker_config: CircuitBreakerConfig) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can update circuit breaker config"
+        );
+        require!(circuit_breaker_config.window_sec > 0, "Window must be greater than zero");
+        require!(
+            circuit_breaker_config.max_outflow_bps as u128 <= BPS_DENOMINATOR
+                && circuit_breaker_config.throttled_withdrawal_bps as u128 <= BPS_DENOMINATOR
+                && circuit_breaker_config.max_throttled_outflow_bps as u128 <= BPS_DENOMINATOR,
+            "Limits cannot exceed 10000 basis points"
+        );
+        // A throttle that lets nothing through would refuse the withdrawal that trips it,
+        // rolling back the trip; pause the vault to stop withdrawals instead
+        require!(
+            circuit_breaker_config.throttled_withdrawal_bps > 0 && circuit_breaker_config.max_throttled_outflow_bps > 0,
+            "Throttled limits must be greater than zero"
+        );
+        self.circuit_breaker_config = circuit_breaker_config;
+        log!("Circuit breaker config updated");
+    }
+
+    /// Leaves throttled mode and starts a fresh window for `token_type`
+    pub fn reset_circuit_breaker(&mut self, token_type: TokenType) {
+        let caller = env::predecessor_account_id();
+        require!(
+            caller == self.config.owner_id || Some(&caller) == self.circuit_breaker_config.guardian_id.as_ref(),
+            "Only guardian or owner can reset the circuit breaker"
+        );
+
+        let window = self.new_outflow_window(&token_type);
+        self.outflow_windows.insert(&token_type, &window);
+
+        log!("Circuit breaker for {:?} reset by {}", token_type, caller);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"circuit_breaker_reset\",\"token_type\":\"{:?}\",\"reset_by\":\"{}\",\"timestamp\":{}}}",
+            token_type,
+            caller,
+            env::block_timestamp()
+        ));
+    }
+
+    /// Counts a withdrawal against the rolling window and returns how much of `amount`
+    /// may leave. Crossing the window limit trips the breaker into throttled mode,
+    /// which lets each withdrawal through up to the throttled caps until a guardian
+    /// resets it. Never panics, so a trip is stored even when the withdrawal is cut short.
+    pub(crate) fn record_outflow(&mut self, account_id: &AccountId, token_type: &TokenType, amount: u128) -> u128 {
+        if !self.circuit_breaker_config.is_enabled {
+            return amount;
+        }
+
+        let mut window = self.current_outflow_window(token_type);
+        if !window.is_throttled {
+            let rolling_outflow = self.rolling_outflow(&window) + amount;
+            let limit = self.outflow_limit(&window);
+            if rolling_outflow <= limit {
+                window.outflow = U128(window.outflow.0 + amount);
+                self.outflow_windows.insert(token_type, &window);
+                return amount;
+            }
+
+            let reserves = self.get_token_reserves(token_type.clone());
+            window.is_throttled = true;
+            window.tripped_at = Some(env::block_timestamp());
+            window.throttled_outflow = U128(0);
+            window.throttled_limit = U128(
+                reserves.0 * self.circuit_breaker_config.max_throttled_outflow_bps as u128 / BPS_DENOMINATOR,
+            );
+
+            log!("Circuit breaker tripped for {:?}: outflow {} exceeds limit {}", token_type, rolling_outflow, limit);
+
+            env::log_str(&format!(
+                "EVENT_JSON:{{\"type\":\"circuit_breaker_tripped\",\"token_type\":\"{:?}\",\"outflow\":\"{}\",\"limit\":\"{}\",\"window_start\":{},\"timestamp\":{}}}",
+                token_type,
+                rolling_outflow,
+                limit,
+                window.window_start,
+                env::block_timestamp()
+            ));
+        }
+
+        let allowed = amount.min(self.throttled_allowance(token_type, &window));
+        window.outflow = U128(window.outflow.0 + allowed);
+        window.throttled_outflow = U128(window.throttled_outflow.0 + allowed);
+        self.outflow_windows.insert(token_type, &window);
+
+        if allowed < amount {
+            env::log_str(&format!(
+                "EVENT_JSON:{{\"type\":\"withdrawal_throttled\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"requested\":\"{}\",\"allowed\":\"{}\",\"timestamp\":{}}}",
+                account_id,
+                token_type,
+                amount,
+                allowed,
+                env::block_timestamp()
+            ));
+        }
+        allowed
+    }
+
+    /// Takes a rolled-back withdrawal out of the current window
+    pub(crate) fn release_outflow(&mut self, token_type: &TokenType, amount: u128) {
+        if self.outflow_windows.get(token_type).is_some() {
+            let mut window = self.current_outflow_window(token_type);
+            window.outflow = U128(window.outflow.0.saturating_sub(amount));
+            if window.is_throttled {
+                window.throttled_outflow = U128(window.throttled_outflow.0.saturating_sub(amount));
+            }
+            self.outflow_windows.insert(token_type, &window);
+        }
+    }
+
+    /// Stored window rolled forward to the current block. Throttled mode outlives the window.
+    fn current_outflow_window(&self, token_type: &TokenType) -> OutflowWindow {
+        let mut window = match self.outflow_windows.get(token_type) {
+            Some(window) => window,
+            None => return self.new_outflow_window(token_type),
+        };
+
+        let window_ns = self.circuit_breaker_config.window_sec * NANOS_PER_SEC;
+        let elapsed_windows = env::block_timestamp().saturating_sub(window.window_start) / window_ns;
+        if elapsed_windows > 0 {
+            // Only the window right before the current one overlaps the rolling window
+            window.previous_outflow = if elapsed_windows == 1 { window.outflow } else { U128(0) };
+            window.outflow = U128(0);
+            window.window_start += elapsed_windows * window_ns;
+            window.reserves_at_start = self.get_token_reserves(token_type.clone());
+        }
+        window
+    }
+
+    fn new_outflow_window(&self, token_type: &TokenType) -> OutflowWindow {
+        OutflowWindow {
+            token_type: token_type.clone(),
+            window_start: env::block_timestamp(),
+            reserves_at_start: self.get_token_reserves(token_type.clone()),
+            outflow: U128(0),
+            previous_outflow: U128(0),
+            is_throttled: false,
+            tripped_at: None,
+            throttled_outflow: U128(0),
+            throttled_limit: U128(0),
+        }
+    }
+
+    /// Current window's outflow plus the previous window's, weighted by how much of
+    /// it the rolling window still covers
+    fn rolling_outflow(&self, window: &OutflowWindow) -> u128 {
+        let window_ns = (self.circuit_breaker_config.window_sec * NANOS_PER_SEC) as u128;
+        let elapsed = (env::block_timestamp().saturating_sub(window.window_start) as u128).min(window_ns);
+        window.outflow.0 + mul_div(window.previous_outflow.0, window_ns - elapsed, window_ns)
+    }
+
+    fn outflow_limit(&self, window: &OutflowWindow) -> u128 {
+        window.reserves_at_start.0 * self.circuit_breaker_config.max_outflow_bps as u128 / BPS_DENOMINATOR
+    }
+
+    /// What a single withdrawal may take while throttled
+    fn throttled_allowance(&self, token_type: &TokenType, window: &OutflowWindow) -> u128 {
+        let reserves = self.get_token_reserves(token_type.clone());
+        let per_withdrawal = reserves.0 * self.circuit_breaker_config.throttled_withdrawal_bps as u128 / BPS_DENOMINATOR;
+        per_withdrawal.min(window.throttled_limit.0.saturating_sub(window.throttled_outflow.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn guardian() -> AccountId {
+        account("guardian")
+    }
+
+    /// Alice holds 10000 USDC shares; the breaker allows 20% per day, then 1% per
+    /// withdrawal up to 5% of the reserves at the trip
+    fn setup_breaker() -> (VaultContract, AccountId) {
+        let mut contract = setup();
+        contract.update_circuit_breaker_config(CircuitBreakerConfig {
+            is_enabled: true,
+            guardian_id: Some(guardian()),
+            window_sec: 24 * 60 * 60,
+            max_outflow_bps: 2_000,
+            throttled_withdrawal_bps: 100,
+            max_throttled_outflow_bps: 500,
+        });
+        let alice = account("alice");
+        deposit_usdc(&mut contract, &alice, 10_000, 0);
+        (contract, alice)
+    }
+
+    fn withdraw(contract: &mut VaultContract, account_id: &AccountId, shares: u128, timestamp: Timestamp) -> Option<Redemption> {
+        set_context(account_id.clone(), timestamp);
+        contract.try_burn_vault_shares(account_id, &TokenType::USDC, U128(shares), None)
+    }
+
+    #[test]
+    fn withdrawals_within_the_window_limit_pass_in_full() {
+        let (mut contract, alice) = setup_breaker();
+
+        let redemption = withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+
+        assert_eq!(redemption.amount, U128(1_500));
+        assert_eq!(contract.get_remaining_outflow(TokenType::USDC), U128(500));
+        assert!(!contract.get_outflow_window(TokenType::USDC).is_throttled);
+    }
+
+    #[test]
+    fn previous_window_outflow_fades_out_of_the_rolling_window() {
+        let (mut contract, alice) = setup_breaker();
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+
+        // Half of the previous window still overlaps; the new limit is 20% of 8500
+        set_context(alice.clone(), DAY + DAY / 2);
+        let window = contract.get_outflow_window(TokenType::USDC);
+        assert_eq!((window.window_start, window.previous_outflow, window.outflow), (DAY, U128(1_500), U128(0)));
+        assert_eq!(contract.get_remaining_outflow(TokenType::USDC), U128(1_700 - 750));
+
+        // Two windows later nothing overlaps
+        set_context(alice, 2 * DAY + DAY / 2);
+        assert_eq!(contract.get_outflow_window(TokenType::USDC).previous_outflow, U128(0));
+        assert_eq!(contract.get_remaining_outflow(TokenType::USDC), U128(1_700));
+    }
+
+    #[test]
+    fn rolled_over_window_admits_a_withdrawal_the_full_one_would_not() {
+        let (mut contract, alice) = setup_breaker();
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+
+        let redemption = withdraw(&mut contract, &alice, 900, DAY + DAY / 2).unwrap();
+
+        assert_eq!(redemption.amount, U128(900));
+        assert!(!contract.get_outflow_window(TokenType::USDC).is_throttled);
+    }
+
+    #[test]
+    fn crossing_the_limit_trips_throttled_mode_and_fills_partially() {
+        let (mut contract, alice) = setup_breaker();
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+
+        set_context(alice.clone(), 10 * SECOND);
+        let redemption = contract
+            .try_burn_vault_shares(&alice, &TokenType::USDC, U128(1_000), Some(U128(1_000)))
+            .unwrap();
+
+        // 1% of the 8500 left, with the minimum scaled to the shares actually burned
+        assert_eq!((redemption.vault_shares_burned, redemption.amount), (U128(85), U128(85)));
+        assert_eq!(contract.get_user_vault_shares(alice, TokenType::USDC), U128(8_415));
+        let window = contract.get_outflow_window(TokenType::USDC);
+        assert!(window.is_throttled);
+        assert_eq!(window.tripped_at, Some(10 * SECOND));
+        assert_eq!((window.throttled_limit, window.throttled_outflow), (U128(425), U128(85)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Slippage: withdrawal amount below min_amount_out")]
+    fn scaled_min_amount_out_still_binds_a_partial_fill() {
+        let (mut contract, alice) = setup_breaker();
+        contract.holding_config.min_holding_period_sec = 60;
+        contract.holding_config.early_exit_fee_bps = 1_000;
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+
+        // Partial fill of 85 has a minimum of 85, but the early exit fee leaves 77
+        set_context(alice.clone(), 10 * SECOND);
+        contract.try_burn_vault_shares(&alice, &TokenType::USDC, U128(1_000), Some(U128(1_000)));
+    }
+
+    #[test]
+    fn throttled_mode_stops_at_its_total_cap_and_outlives_the_window() {
+        let (mut contract, alice) = setup_breaker();
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+
+        let mut released = 0;
+        while let Some(redemption) = withdraw(&mut contract, &alice, 1_000, SECOND) {
+            released += redemption.amount.0;
+        }
+
+        assert_eq!(released, 425);
+        assert_eq!(contract.get_remaining_outflow(TokenType::USDC), U128(0));
+        assert!(withdraw(&mut contract, &alice, 1, 3 * DAY).is_none());
+        assert!(contract.get_outflow_window(TokenType::USDC).is_throttled);
+    }
+
+    #[test]
+    #[should_panic(expected = "Circuit breaker: throttled outflow limit reached")]
+    fn burn_rejects_withdrawals_once_the_throttled_cap_is_spent() {
+        let (mut contract, alice) = setup_breaker();
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+        while withdraw(&mut contract, &alice, 1_000, SECOND).is_some() {}
+
+        contract.burn_vault_shares(&alice, &TokenType::USDC, U128(1), None);
+    }
+
+    #[test]
+    fn guardian_reset_restores_the_full_window() {
+        let (mut contract, alice) = setup_breaker();
+        withdraw(&mut contract, &alice, 1_500, 0).unwrap();
+        withdraw(&mut contract, &alice, 1_000, SECOND).unwrap();
+
+        set_context(guardian(), 2 * SECOND);
+        contract.reset_circuit_breaker(TokenType::USDC);
+
+        let window = contract.get_outflow_window(TokenType::USDC);
+        assert!(!window.is_throttled);
+        assert_eq!((window.window_start, window.reserves_at_start), (2 * SECOND, U128(8_415)));
+        assert_eq!(contract.get_remaining_outflow(TokenType::USDC), U128(1_683));
+        assert_eq!(withdraw(&mut contract, &alice, 1_000, 3 * SECOND).unwrap().amount, U128(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Only guardian or owner can reset the circuit breaker")]
+    fn only_guardian_or_owner_can_reset() {
+        let (mut contract, alice) = setup_breaker();
+        set_context(alice, 0);
+        contract.reset_circuit_breaker(TokenType::USDC);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::*;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+
+/// Split of a deposit fee between the protocol and the depositor's referrer
+pub struct FeeBreakdown {
+    pub total_fee: u128,
+    pub referral_fee: u128,
+}
+
+#[near_bindgen]
+impl VaultContract {
+    pub fn get_protocol_fees(&self, token_type: TokenType) -> U128 {
+        self.protocol_fees.get(&token_type).unwrap_or(U128(0))
+    }
+
+    // Admin functions
+    /// Sends booked protocol revenue of `token_type` to `receiver_id`, the owner by default
+    pub fn withdraw_protocol_fees(
+        &mut self,
+        token_type: TokenType,
+        amount: Option<U128>,
+        receiver_id: Option<AccountId>,
+    ) -> Promise {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can withdraw protocol fees"
+        );
+        let protocol_fees = self.get_protocol_fees(token_type.clone());
+        let amount = amount.unwrap_or(protocol_fees);
+        require!(amount.0 > 0, "No protocol fees to withdraw");
+        require!(amount.0 <= protocol_fees.0, "Amount exceeds protocol fees");
+        self.protocol_fees.insert(&token_type, &U128(protocol_fees.0 - amount.0));
+
+        let receiver_id = receiver_id.unwrap_or_else(|| self.config.owner_id.clone());
+        log!("Withdrawing {} {:?} protocol fees to {}", amount.0, token_type, receiver_id);
+
+        let token_contract = self.get_token_contract(&token_type);
+        ext_fungible_token::ext(token_contract)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(receiver_id.clone(), amount, Some("Vault protocol fees".to_string()))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_protocol_fees_withdrawn(receiver_id, token_type, amount)
+            )
+    }
+
+    #[private]
+    pub fn on_protocol_fees_withdrawn(&mut self, receiver_id: AccountId, token_type: TokenType, amount: U128) {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                env::log_str(&format!(
+                    "EVENT_JSON:{{\"type\":\"protocol_fees_withdrawn\",\"receiver_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"timestamp\":{}}}",
+                    receiver_id,
+                    token_type,
+                    amount.0,
+                    env::block_timestamp()
+                ));
+            }
+            _ => {
+                // Transfer failed, book the fees again
+                let protocol_fees = self.get_protocol_fees(token_type.clone());
+                self.protocol_fees.insert(&token_type, &U128(protocol_fees.0 + amount.0));
+                log!("Protocol fee transfer failed, restored {} {:?}", amount.0, token_type);
+            }
+        }
+    }
+
+    // Helper functions
+    /// Charges `fee_percentage` on a deposit when `deposit_fee_enabled` is set. The referral
+    /// share is carved out of the fee and returned to the caller; only the remainder is
+    /// booked as protocol revenue.
+    pub(crate) fn charge_deposit_fee(
+        &mut self,
+        token_type: &TokenType,
+        amount: u128,
+        has_referrer: bool,
+    ) -> FeeBreakdown {
+        let total_fee = self.deposit_fee(amount);
+        let referral_fee = if has_referrer {
+            total_fee * self.config.referral_fee_share as u128 / BPS_DENOMINATOR
+        } else {
+            0
+        };
+
+        let protocol_fees = self.protocol_fees.get(token_type).unwrap_or(U128(0));
+        self.protocol_fees
+            .insert(token_type, &U128(protocol_fees.0 + total_fee - referral_fee));
+
+        FeeBreakdown { total_fee, referral_fee }
+    }
+
+    pub(crate) fn deposit_fee(&self, amount: u128) -> u128 {
+        if !self.config.deposit_fee_enabled {
+            return 0;
+        }
+        amount * self.config.fee_percentage as u128 / BPS_DENOMINATOR
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    fn referred_deposit(contract: &mut VaultContract, account_id: &AccountId, amount: u128) {
+        set_context(usdc(), 0);
+        let msg = format!("{{\"action\":\"deposit\",\"referrer\":\"{}\"}}", account("referrer"));
+        contract.ft_on_transfer(account_id.clone(), U128(amount), msg);
+    }
+
+    fn enable_deposit_fee(contract: &mut VaultContract, fee_percentage: u16, referral_fee_share: u16) {
+        let mut config = contract.get_config();
+        config.fee_percentage = fee_percentage;
+        config.referral_fee_share = referral_fee_share;
+        config.deposit_fee_enabled = true;
+        set_context(owner(), 0);
+        contract.update_config(config);
+    }
+
+    #[test]
+    fn deposits_are_fee_free_by_default() {
+        let mut contract = setup();
+        set_context(owner(), 0);
+        let mut config = contract.get_config();
+        config.fee_percentage = 100;
+        contract.update_config(config);
+
+        let alice = account("alice");
+        referred_deposit(&mut contract, &alice, 10_000);
+
+        assert_eq!(contract.get_user_vault_shares(alice.clone(), TokenType::USDC), U128(10_000));
+        assert_eq!(contract.get_protocol_fees(TokenType::USDC), U128(0));
+        assert_eq!(contract.get_referrer(alice), Some(account("referrer")));
+    }
+
+    #[test]
+    fn enabled_deposit_fee_is_split_with_the_referrer() {
+        let mut contract = setup();
+        enable_deposit_fee(&mut contract, 100, 2_000);
+
+        let alice = account("alice");
+        referred_deposit(&mut contract, &alice, 10_000);
+
+        assert_eq!(contract.get_user_vault_shares(alice, TokenType::USDC), U128(9_900));
+        assert_eq!(contract.get_protocol_fees(TokenType::USDC), U128(80));
+        let stats = contract.get_referral_stats(account("referrer"));
+        assert_eq!(stats.tokens[0].unclaimed, U128(20));
+    }
+}
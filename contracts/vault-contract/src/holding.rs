@@ -0,0 +1,133 @@
+use bond_math::mul_div;
+use crate::*;
+
+const BPS_DENOMINATOR: u128 = 10_000;
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// What happens to withdrawals before the minimum holding period has passed
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EarlyExitPolicy {
+    Reject,
+    ExitFee,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct HoldingConfig {
+    pub min_holding_period_sec: u64, // 0 disables the holding period
+    pub early_exit_policy: EarlyExitPolicy,
+    pub early_exit_fee_bps: u16, // Charged on the withdrawn amount under ExitFee
+}
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_holding_config(&self) -> HoldingConfig {
+        self.holding_config.clone()
+    }
+
+    /// Current position in a share class with its share-weighted deposit time
+    pub fn get_holding_position(&self, account_id: AccountId, token_type: TokenType) -> Option<VaultShare> {
+        let deposited_at = self.deposited_at.get(&(account_id.clone(), token_type.clone()))?;
+        Some(VaultShare {
+            amount: self.get_user_vault_shares(account_id.clone(), token_type.clone()),
+            account_id,
+            token_type,
+            deposited_at,
+        })
+    }
+
+    /// Earliest time the account can withdraw `token_type` without hitting the early exit policy
+    pub fn get_earliest_exit_time(&self, account_id: AccountId, token_type: TokenType) -> Option<Timestamp> {
+        self.deposited_at
+            .get(&(account_id, token_type))
+            .map(|deposited_at| deposited_at + self.holding_config.min_holding_period_sec * NANOS_PER_SEC)
+    }
+
+    // Admin functions
+    pub fn update_holding_config(&mut self, holding_config: HoldingConfig) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can update holding config"
+        );
+        require!(
+            holding_config.early_exit_fee_bps as u128 <= BPS_DENOMINATOR,
+            "Exit fee cannot exceed 10000 basis points"
+        );
+
+        self.holding_config = holding_config;
+        log!(
+            "Holding config updated: {}s minimum, {:?} policy",
+            self.holding_config.min_holding_period_sec,
+            self.holding_config.early_exit_policy
+        );
+    }
+
+    /// Moves the deposit time of a position towards now, weighted by the shares being
+    /// added, so topping up an old position cannot skip the holding period.
+    /// Must run before the new shares are credited.
+    pub(crate) fn record_deposit_time(&mut self, account_id: &AccountId, token_type: &TokenType, shares_minted: u128) {
+        let key = (account_id.clone(), token_type.clone());
+        let now = env::block_timestamp();
+        let existing_shares = self.get_user_vault_shares(account_id.clone(), token_type.clone()).0;
+
+        let deposited_at = match self.deposited_at.get(&key) {
+            Some(previous) if existing_shares > 0 => {
+                let elapsed = now.saturating_sub(previous) as u128;
+                now - mul_div(elapsed, existing_shares, existing_shares + shares_minted) as u64
+            }
+            _ => now,
+        };
+        self.deposited_at.insert(&key, &deposited_at);
+    }
+
+    /// Applies the early exit policy to a withdrawal of `amount`. Rejects the withdrawal or
+    /// books the exit fee as protocol revenue and returns it.
+    pub(crate) fn charge_exit_fee(&mut self, account_id: &AccountId, token_type: &TokenType, amount: u128) -> u128 {
+        let earliest_exit = match self.get_earliest_exit_time(account_id.clone(), token_type.clone()) {
+            Some(earliest_exit) => earliest_exit,
+            None => return 0,
+        };
+        if env::block_timestamp() >= earliest_exit {
+            return 0;
+        }
+
+        require!(
+            self.holding_config.early_exit_policy != EarlyExitPolicy::Reject,
+            "Minimum holding period not reached"
+        );
+
+        let exit_fee = amount * self.holding_config.early_exit_fee_bps as u128 / BPS_DENOMINATOR;
+        if exit_fee == 0 {
+            return 0;
+        }
+
+        let protocol_fees = self.protocol_fees.get(token_type).unwrap_or(U128(0));
+        self.protocol_fees.insert(token_type, &U128(protocol_fees.0 + exit_fee));
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"early_exit_fee\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"exit_fee\":\"{}\",\"penalty_free_at\":{},\"timestamp\":{}}}",
+            account_id,
+            token_type,
+            amount,
+            exit_fee,
+            earliest_exit,
+            env::block_timestamp()
+        ));
+
+        exit_fee
+    }
+
+    /// Returns an exit fee to reserves when the withdrawal it was charged on is rolled back
+    pub(crate) fn refund_exit_fee(&mut self, token_type: &TokenType, exit_fee: u128) {
+        let protocol_fees = self.protocol_fees.get(token_type).unwrap_or(U128(0));
+        let refunded = exit_fee.min(protocol_fees.0);
+        if refunded == 0 {
+            return;
+        }
+
+        self.protocol_fees.insert(token_type, &U128(protocol_fees.0 - refunded));
+        self.update_token_reserves(token_type, refunded, true);
+    }
+}
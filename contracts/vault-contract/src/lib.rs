@@ -1,19 +1,48 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{UnorderedMap, UnorderedSet};
+use near_sdk::collections::{LookupMap, UnorderedMap, UnorderedSet};
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, ext_contract, near_bindgen, AccountId, Gas, Promise, 
-    Timestamp, PanicOnDefault, require, log
+    env, ext_contract, near_bindgen, AccountId, Gas, NearToken, Promise, PromiseOrValue,
+    PromiseResult, Timestamp, PanicOnDefault, require, log, assert_one_yocto
 };
 
+mod allowance;
+mod bond_vault;
+mod circuit_breaker;
+mod fees;
+mod holding;
+mod lockup;
+mod migration;
+mod native;
+mod reconcile;
+mod redeem;
+mod referral;
+mod rewards;
+mod swap;
+#[cfg(test)]
+mod test_utils;
+mod token_receiver;
+
+use bond_math::mul_div;
+
+pub use crate::circuit_breaker::{CircuitBreakerConfig, OutflowWindow};
+pub use crate::holding::{EarlyExitPolicy, HoldingConfig};
+pub use crate::lockup::{LockBoost, LockConfig, LockPosition, LockTerm};
+pub use crate::reconcile::{ReconciliationReport, TokenReconciliation};
+pub use crate::redeem::Redemption;
+pub use crate::referral::{ReferralStats, ReferralTokenStats};
+pub use crate::rewards::{PendingReward, RewardProgram};
+pub use crate::swap::{SwapAction, SwapConfig, SwapMsg};
+pub use crate::token_receiver::TokenReceiverMsg;
+
 // Constants
 const GAS_FOR_FT_TRANSFER: Gas = Gas::from_gas(10_000_000_000_000);
-const GAS_FOR_FT_TRANSFER_CALL: Gas = Gas::from_gas(20_000_000_000_000);
 const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas::from_gas(10_000_000_000_000);
 const INITIAL_SUPPLY: u128 = 1_000_000_000_000_000_000_000_000; // 1M tokens with 24 decimals
 
 // External contract interfaces
+#[allow(dead_code)]
 #[ext_contract(ext_fungible_token)]
 trait FungibleToken {
     fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
@@ -28,9 +57,48 @@ trait FungibleToken {
     fn ft_balance_of(&self, account_id: AccountId) -> U128;
 }
 
+#[allow(dead_code)]
 #[ext_contract(ext_self)]
 trait ExtSelf {
-    fn on_tokens_transferred(&mut self, sender_id: AccountId, amount: U128, token_id: AccountId);
+    fn on_tokens_transferred(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        token_id: AccountId,
+        referrer: Option<AccountId>,
+    );
+    fn on_rewards_claimed(&mut self, account_id: AccountId, reward_token: AccountId, amount: U128);
+    fn on_rewards_swept(&mut self, token_type: TokenType, reward_token: AccountId, amount: U128);
+    fn on_referral_rewards_claimed(&mut self, referrer_id: AccountId, token_type: TokenType, amount: U128);
+    fn on_protocol_fees_withdrawn(&mut self, receiver_id: AccountId, token_type: TokenType, amount: U128);
+    fn on_reconcile(&mut self, requested_by: AccountId) -> ReconciliationReport;
+    fn on_near_wrapped(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        referrer: Option<AccountId>,
+        min_shares_out: Option<U128>,
+    ) -> PromiseOrValue<U128>;
+    fn on_near_unwrapped(&mut self, account_id: AccountId, amount: U128, vault_shares_amount: U128);
+    fn on_withdraw_resolved(&mut self, account_id: AccountId, token_type: TokenType, shares: U128, amount: U128);
+    fn on_withdraw_from_resolved(
+        &mut self,
+        owner: AccountId,
+        spender_id: AccountId,
+        token_type: TokenType,
+        shares: U128,
+        amount: U128,
+    );
+    fn on_redeem_resolved(&mut self, account_id: AccountId, percentage_bps: u16, redemptions: Vec<Redemption>);
+    fn on_swap_withdraw_resolved(
+        &mut self,
+        account_id: AccountId,
+        token_type: TokenType,
+        token_out: TokenType,
+        vault_shares_amount: U128,
+        amount_in: U128,
+        min_amount_out: U128,
+    ) -> U128;
 }
 
 // Data structures
@@ -60,6 +128,8 @@ pub struct VaultConfig {
     pub usdt_contract: AccountId,
     pub fee_percentage: u16, // Basis points (e.g., 100 = 1%)
     pub is_paused: bool,
+    pub referral_fee_share: u16, // Basis points of the deposit fee paid to referrers
+    pub deposit_fee_enabled: bool, // Deducts fee_percentage from deposits; off by default
 }
 
 // Events
@@ -70,6 +140,9 @@ pub struct DepositEvent {
     pub token_type: TokenType,
     pub amount: U128,
     pub vault_shares_minted: U128,
+    pub fee: U128,
+    pub referrer: Option<AccountId>,
+    pub referral_fee: U128,
     pub timestamp: Timestamp,
 }
 
@@ -81,6 +154,7 @@ pub struct WithdrawEvent {
     pub amount: U128,
     pub vault_shares_burned: U128,
     pub yield_earned: U128,
+    pub exit_fee: U128,
     pub timestamp: Timestamp,
 }
 
@@ -102,6 +176,47 @@ pub struct VaultContract {
     // Events log
     pub deposit_events: Vec<DepositEvent>,
     pub withdraw_events: Vec<WithdrawEvent>,
+
+    // Rewards distribution
+    pub reward_programs: UnorderedMap<(TokenType, AccountId), RewardProgram>,
+    pub reward_checkpoints: LookupMap<(AccountId, TokenType, AccountId), U128>,
+    pub pending_rewards: LookupMap<(AccountId, AccountId), U128>,
+    pub reward_tokens: UnorderedSet<AccountId>, // Accepted besides the vault's own tokens
+
+    // Fees and referrals
+    pub protocol_fees: UnorderedMap<TokenType, U128>,
+    pub referrers: LookupMap<AccountId, AccountId>,
+    pub referral_stats: LookupMap<AccountId, ReferralStats>,
+
+    // Delegated withdrawal allowances: (owner, spender, token type) -> shares
+    pub allowances: LookupMap<(AccountId, AccountId, TokenType), U128>,
+
+    // Latest solvency report against on-chain token balances
+    pub last_reconciliation: Option<ReconciliationReport>,
+
+    // DEX routing for withdraw-with-swap: (token in, token out) -> pool id
+    pub swap_config: SwapConfig,
+    pub swap_pools: UnorderedMap<(TokenType, TokenType), u64>,
+
+    // Time-locked positions and the extra reward weight they carry
+    pub lock_config: LockConfig,
+    pub lock_positions: LookupMap<AccountId, Vec<LockPosition>>,
+    pub next_lock_id: u64,
+    pub lock_boosts: LookupMap<(AccountId, TokenType), U128>,
+    pub boosted_supply: UnorderedMap<TokenType, U128>,
+
+    // Minimum holding period: (account, token type) -> share-weighted deposit time
+    pub holding_config: HoldingConfig,
+    pub deposited_at: LookupMap<(AccountId, TokenType), Timestamp>,
+
+    // Outflow circuit breaker: per-token withdrawal window
+    pub circuit_breaker_config: CircuitBreakerConfig,
+    pub outflow_windows: UnorderedMap<TokenType, OutflowWindow>,
+
+    // Tokens held for others: funded rewards not yet paid out, per reward token, and
+    // unclaimed referral fees, per class
+    pub reward_liabilities: LookupMap<AccountId, U128>,
+    pub referral_liabilities: LookupMap<TokenType, U128>,
 }
 
 #[near_bindgen]
@@ -114,7 +229,8 @@ impl VaultContract {
         usdt_contract: AccountId,
         fee_percentage: u16,
     ) -> Self {
-        require!(env::state_exists() == false, "Already initialized");
+        require!(!env::state_exists(), "Already initialized");
+        require!(fee_percentage <= 10_000, "Fee cannot exceed 10000 bps");
         
         let config = VaultConfig {
             owner_id: owner_id.clone(),
@@ -123,17 +239,11 @@ impl VaultContract {
             usdt_contract,
             fee_percentage,
             is_paused: false,
+            referral_fee_share: 0,
+            deposit_fee_enabled: false,
         };
 
-        Self {
-            config,
-            total_supply: U128(INITIAL_SUPPLY),
-            total_deposits: UnorderedMap::new(b"total_deposits".to_vec()),
-            vault_shares: UnorderedMap::new(b"vault_shares".to_vec()),
-            token_reserves: UnorderedMap::new(b"token_reserves".to_vec()),
-            deposit_events: Vec::new(),
-            withdraw_events: Vec::new(),
-        }
+        Self::with_config(config)
     }
 
     // View functions
@@ -168,10 +278,32 @@ impl VaultContract {
         U128(total)
     }
 
+    /// Vault shares minted for depositing `amount` at the current fee
+    pub fn preview_deposit(&self, amount: U128) -> U128 {
+        U128(amount.0 - self.deposit_fee(amount.0))
+    }
+
+    /// Tokens paid out for burning `vault_shares_amount`
+    pub fn preview_withdraw(&self, vault_shares_amount: U128) -> U128 {
+        vault_shares_amount
+    }
+
     // Deposit function
-    pub fn deposit(&mut self, token_type: TokenType, amount: U128) -> Promise {
+    pub fn deposit(
+        &mut self,
+        token_type: TokenType,
+        amount: U128,
+        referrer: Option<AccountId>,
+        min_shares_out: Option<U128>,
+    ) -> Promise {
         require!(!self.config.is_paused, "Vault is paused");
         require!(amount.0 > 0, "Amount must be greater than zero");
+        if let Some(min_shares_out) = min_shares_out {
+            require!(
+                self.preview_deposit(amount).0 >= min_shares_out.0,
+                "Slippage: vault shares minted below min_shares_out"
+            );
+        }
 
         let sender_id = env::predecessor_account_id();
         let token_contract = self.get_token_contract(&token_type);
@@ -188,24 +320,181 @@ impl VaultContract {
             )
             .then(
                 ext_self::ext(env::current_account_id())
-                    .on_tokens_transferred(sender_id, amount, token_contract)
+                    .on_tokens_transferred(sender_id, amount, token_contract, referrer)
             )
     }
 
+    /// Mints shares for what the transfer actually delivered. `min_shares_out` is checked
+    /// before the transfer starts, since a panic here could not undo it.
     #[private]
     pub fn on_tokens_transferred(
         &mut self,
         sender_id: AccountId,
         amount: U128,
         token_id: AccountId,
+        referrer: Option<AccountId>,
     ) {
+        // `ft_transfer_call` resolves to the amount the receiver kept
+        let received = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<U128>(&value).unwrap_or(U128(0))
+            }
+            _ => U128(0),
+        };
+        if received.0 == 0 {
+            log!("Deposit of {} from {} was not transferred, no vault shares minted", amount.0, sender_id);
+            return;
+        }
+
         let token_type = self.get_token_type_from_contract(&token_id);
-        
+        self.internal_deposit(sender_id, token_type, received, referrer, None);
+    }
+
+    // Withdraw function
+    pub fn withdraw(
+        &mut self,
+        token_type: TokenType,
+        vault_shares_amount: U128,
+        min_amount_out: Option<U128>,
+    ) -> Promise {
+        require!(!self.config.is_paused, "Vault is paused");
+
+        let sender_id = env::predecessor_account_id();
+        let withdrawal =
+            self.internal_withdraw(&sender_id, &token_type, vault_shares_amount, min_amount_out);
+
+        // Transfer tokens back to user
+        let token_contract = self.get_token_contract(&token_type);
+        ext_fungible_token::ext(token_contract)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(sender_id.clone(), withdrawal.amount, Some(format!("Withdraw {:?}", token_type)))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_withdraw_resolved(sender_id, token_type, withdrawal.vault_shares_burned, withdrawal.amount)
+            )
+    }
+
+    /// Gives the burned shares back if the transfer failed
+    #[private]
+    pub fn on_withdraw_resolved(&mut self, account_id: AccountId, token_type: TokenType, shares: U128, amount: U128) {
+        if let PromiseResult::Successful(_) = env::promise_result(0) {
+            return;
+        }
+        self.restore_withdrawal(&account_id, &token_type, shares, amount);
+    }
+
+    // Admin functions
+    pub fn update_config(&mut self, new_config: VaultConfig) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can update config"
+        );
+        require!(new_config.fee_percentage <= 10_000, "Fee cannot exceed 10000 bps");
+        require!(new_config.referral_fee_share <= 10_000, "Referral fee share cannot exceed 10000 bps");
+        self.config = new_config;
+    }
+
+    pub fn pause_vault(&mut self) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can pause vault"
+        );
+        self.config.is_paused = true;
+        log!("Vault paused by owner");
+    }
+
+    pub fn unpause_vault(&mut self) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can unpause vault"
+        );
+        self.config.is_paused = false;
+        log!("Vault unpaused by owner");
+    }
+
+    // Helper functions
+    fn with_config(config: VaultConfig) -> Self {
+        Self {
+            config,
+            total_supply: U128(INITIAL_SUPPLY),
+            total_deposits: UnorderedMap::new(b"total_deposits".to_vec()),
+            vault_shares: UnorderedMap::new(b"vault_shares".to_vec()),
+            token_reserves: UnorderedMap::new(b"token_reserves".to_vec()),
+            deposit_events: Vec::new(),
+            withdraw_events: Vec::new(),
+            reward_programs: UnorderedMap::new(b"reward_programs".to_vec()),
+            reward_checkpoints: LookupMap::new(b"reward_checkpoints".to_vec()),
+            pending_rewards: LookupMap::new(b"pending_rewards".to_vec()),
+            reward_tokens: UnorderedSet::new(b"reward_tokens".to_vec()),
+            protocol_fees: UnorderedMap::new(b"protocol_fees".to_vec()),
+            referrers: LookupMap::new(b"referrers".to_vec()),
+            referral_stats: LookupMap::new(b"referral_stats".to_vec()),
+            allowances: LookupMap::new(b"allowances".to_vec()),
+            last_reconciliation: None,
+            swap_config: SwapConfig { dex_contract: None },
+            swap_pools: UnorderedMap::new(b"swap_pools".to_vec()),
+            lock_config: LockConfig {
+                multiplier_30d_bps: 11_000,
+                multiplier_90d_bps: 12_500,
+                multiplier_180d_bps: 15_000,
+                early_exit_penalty_bps: 1_000,
+            },
+            lock_positions: LookupMap::new(b"lock_positions".to_vec()),
+            next_lock_id: 0,
+            lock_boosts: LookupMap::new(b"lock_boosts".to_vec()),
+            boosted_supply: UnorderedMap::new(b"boosted_supply".to_vec()),
+            holding_config: HoldingConfig {
+                min_holding_period_sec: 0,
+                early_exit_policy: EarlyExitPolicy::ExitFee,
+                early_exit_fee_bps: 50,
+            },
+            deposited_at: LookupMap::new(b"deposited_at".to_vec()),
+            circuit_breaker_config: CircuitBreakerConfig {
+                is_enabled: false,
+                guardian_id: None,
+                window_sec: 24 * 60 * 60,
+                max_outflow_bps: 2_000,
+                throttled_withdrawal_bps: 100,
+                max_throttled_outflow_bps: 500,
+            },
+            outflow_windows: UnorderedMap::new(b"outflow_windows".to_vec()),
+            reward_liabilities: LookupMap::new(b"reward_liabilities".to_vec()),
+            referral_liabilities: LookupMap::new(b"referral_liabilities".to_vec()),
+        }
+    }
+
+    /// Mints shares for tokens already received by the vault and returns the shares minted
+    pub(crate) fn internal_deposit(
+        &mut self,
+        sender_id: AccountId,
+        token_type: TokenType,
+        amount: U128,
+        referrer: Option<AccountId>,
+        min_shares_out: Option<U128>,
+    ) -> U128 {
+        let referrer = self.resolve_referrer(&sender_id, referrer);
+
+        // Take the deposit fee and credit the referrer's share
+        let fee = self.charge_deposit_fee(&token_type, amount.0, referrer.is_some());
+        if let Some(referrer_id) = &referrer {
+            self.record_referred_deposit(referrer_id, &token_type, amount.0, fee.referral_fee);
+        }
+        let net_amount = amount.0 - fee.total_fee;
+
         // Calculate vault shares to mint (1:1 ratio for now)
-        let vault_shares_to_mint = amount;
+        let vault_shares_to_mint = U128(net_amount);
+        if let Some(min_shares_out) = min_shares_out {
+            require!(
+                vault_shares_to_mint.0 >= min_shares_out.0,
+                "Slippage: vault shares minted below min_shares_out"
+            );
+        }
 
         // Update vault state
-        self.update_token_reserves(&token_type, amount.0, true);
+        self.record_deposit_time(&sender_id, &token_type, vault_shares_to_mint.0);
+        self.update_token_reserves(&token_type, net_amount, true);
         self.update_user_vault_shares(&sender_id, &token_type, vault_shares_to_mint.0, true);
         self.total_supply = U128(self.total_supply.0 + vault_shares_to_mint.0);
 
@@ -215,6 +504,9 @@ impl VaultContract {
             token_type: token_type.clone(),
             amount,
             vault_shares_minted: vault_shares_to_mint,
+            fee: U128(fee.total_fee),
+            referrer: referrer.clone(),
+            referral_fee: U128(fee.referral_fee),
             timestamp: env::block_timestamp(),
         };
         self.deposit_events.push(deposit_event.clone());
@@ -229,30 +521,110 @@ impl VaultContract {
 
         // Log event for external systems
         env::log_str(&format!(
-            "EVENT_JSON:{{\"type\":\"deposit\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_minted\":\"{}\",\"timestamp\":{}}}",
+            "EVENT_JSON:{{\"type\":\"deposit\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_minted\":\"{}\",\"fee\":\"{}\",\"referrer\":{},\"referral_fee\":\"{}\",\"timestamp\":{}}}",
             sender_id,
             token_type,
             amount.0,
             vault_shares_to_mint.0,
+            fee.total_fee,
+            referrer.as_ref().map_or("null".to_string(), |r| format!("\"{}\"", r)),
+            fee.referral_fee,
             env::block_timestamp()
         ));
+
+        vault_shares_to_mint
     }
 
-    // Withdraw function
-    pub fn withdraw(&mut self, token_type: TokenType, vault_shares_amount: U128) -> Promise {
-        require!(!self.config.is_paused, "Vault is paused");
+    /// Burns shares of `account_id`, emits the withdraw event and returns the shares
+    /// burned and the token amount owed for them. The circuit breaker may cut the
+    /// withdrawal short. The caller is responsible for sending the tokens.
+    pub(crate) fn internal_withdraw(
+        &mut self,
+        account_id: &AccountId,
+        token_type: &TokenType,
+        vault_shares_amount: U128,
+        min_amount_out: Option<U128>,
+    ) -> Redemption {
+        let withdrawal = self.burn_vault_shares(account_id, token_type, vault_shares_amount, min_amount_out);
+
+        // Log event for external systems
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"withdraw\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_burned\":\"{}\",\"timestamp\":{}}}",
+            account_id,
+            token_type,
+            withdrawal.amount.0,
+            withdrawal.vault_shares_burned.0,
+            env::block_timestamp()
+        ));
+
+        withdrawal
+    }
+
+    /// Burns shares and records the withdrawal without emitting an indexer event.
+    /// While the circuit breaker throttles `token_type` only part of the shares may
+    /// be burned, with `min_amount_out` scaled down to match.
+    pub(crate) fn burn_vault_shares(
+        &mut self,
+        account_id: &AccountId,
+        token_type: &TokenType,
+        vault_shares_amount: U128,
+        min_amount_out: Option<U128>,
+    ) -> Redemption {
+        self.try_burn_vault_shares(account_id, token_type, vault_shares_amount, min_amount_out)
+            .unwrap_or_else(|| env::panic_str("Circuit breaker: throttled outflow limit reached"))
+    }
+
+    /// Like `burn_vault_shares`, but returns None instead of panicking when the circuit
+    /// breaker throttles the withdrawal down to zero shares
+    pub(crate) fn try_burn_vault_shares(
+        &mut self,
+        account_id: &AccountId,
+        token_type: &TokenType,
+        vault_shares_amount: U128,
+        min_amount_out: Option<U128>,
+    ) -> Option<Redemption> {
         require!(vault_shares_amount.0 > 0, "Amount must be greater than zero");
 
-        let sender_id = env::predecessor_account_id();
+        let sender_id = account_id.clone();
+        let token_type = token_type.clone();
         let user_shares = self.get_user_vault_shares(sender_id.clone(), token_type.clone());
         
         require!(user_shares.0 >= vault_shares_amount.0, "Insufficient vault shares");
+        self.release_expired_locks(&sender_id, &token_type);
+        require!(
+            self.available_shares(&sender_id, &token_type) >= vault_shares_amount.0,
+            "Vault shares are locked"
+        );
 
         // Calculate withdrawal amount (1:1 ratio for now, will add yield calculation later)
-        let withdrawal_amount = vault_shares_amount;
+        let requested_amount = self.preview_withdraw(vault_shares_amount);
+        let allowed_amount = self.record_outflow(&sender_id, &token_type, requested_amount.0);
+        let (vault_shares_amount, min_amount_out) = if allowed_amount < requested_amount.0 {
+            let burned = mul_div(vault_shares_amount.0, allowed_amount, requested_amount.0);
+            if burned == 0 {
+                self.release_outflow(&token_type, allowed_amount);
+                return None;
+            }
+            (
+                U128(burned),
+                min_amount_out.map(|min| U128(mul_div(min.0, burned, vault_shares_amount.0))),
+            )
+        } else {
+            (vault_shares_amount, min_amount_out)
+        };
+
+        let gross_amount = self.preview_withdraw(vault_shares_amount);
+        let exit_fee = self.charge_exit_fee(&sender_id, &token_type, gross_amount.0);
+        let withdrawal_amount = U128(gross_amount.0 - exit_fee);
+        if let Some(min_amount_out) = min_amount_out {
+            require!(
+                withdrawal_amount.0 >= min_amount_out.0,
+                "Slippage: withdrawal amount below min_amount_out"
+            );
+        }
 
         // Update vault state
-        self.update_token_reserves(&token_type, withdrawal_amount.0, false);
+        self.update_token_reserves(&token_type, gross_amount.0, false);
         self.update_user_vault_shares(&sender_id, &token_type, vault_shares_amount.0, false);
         self.total_supply = U128(self.total_supply.0 - vault_shares_amount.0);
 
@@ -263,6 +635,7 @@ impl VaultContract {
             amount: withdrawal_amount,
             vault_shares_burned: vault_shares_amount,
             yield_earned: U128(0), // Will calculate yield in future versions
+            exit_fee: U128(exit_fee),
             timestamp: env::block_timestamp(),
         };
         self.withdraw_events.push(withdraw_event.clone());
@@ -275,50 +648,49 @@ impl VaultContract {
             token_type
         );
 
-        // Log event for external systems
-        env::log_str(&format!(
-            "EVENT_JSON:{{\"type\":\"withdraw\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_burned\":\"{}\",\"timestamp\":{}}}",
-            sender_id,
+        Some(Redemption {
             token_type,
-            withdrawal_amount.0,
-            vault_shares_amount.0,
-            env::block_timestamp()
-        ));
-
-        // Transfer tokens back to user
-        let token_contract = self.get_token_contract(&token_type);
-        ext_fungible_token::ext(token_contract)
-            .ft_transfer(sender_id, withdrawal_amount, Some(format!("Withdraw {:?}", token_type)))
+            vault_shares_burned: vault_shares_amount,
+            amount: withdrawal_amount,
+        })
     }
 
-    // Admin functions
-    pub fn update_config(&mut self, new_config: VaultConfig) {
-        require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can update config"
-        );
-        self.config = new_config;
-    }
+    /// Re-credits shares burned by a withdrawal whose outbound transfer failed
+    pub(crate) fn restore_withdrawal(
+        &mut self,
+        account_id: &AccountId,
+        token_type: &TokenType,
+        vault_shares_amount: U128,
+        amount: U128,
+    ) {
+        // Anything short of the shares' value was an early exit fee; return it too
+        let gross_amount = self.preview_withdraw(vault_shares_amount);
+        if gross_amount.0 > amount.0 {
+            self.refund_exit_fee(token_type, gross_amount.0 - amount.0);
+        }
 
-    pub fn pause_vault(&mut self) {
-        require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can pause vault"
-        );
-        self.config.is_paused = true;
-        log!("Vault paused by owner");
-    }
+        self.release_outflow(token_type, gross_amount.0.max(amount.0));
+        self.update_token_reserves(token_type, amount.0, true);
+        self.update_user_vault_shares(account_id, token_type, vault_shares_amount.0, true);
+        self.total_supply = U128(self.total_supply.0 + vault_shares_amount.0);
 
-    pub fn unpause_vault(&mut self) {
-        require!(
-            env::predecessor_account_id() == self.config.owner_id,
-            "Only owner can unpause vault"
+        log!(
+            "Withdrawal transfer failed, restored {} {:?} vault shares to {}",
+            vault_shares_amount.0,
+            token_type,
+            account_id
         );
-        self.config.is_paused = false;
-        log!("Vault unpaused by owner");
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"withdraw_refunded\",\"account_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"vault_shares_restored\":\"{}\",\"timestamp\":{}}}",
+            account_id,
+            token_type,
+            amount.0,
+            vault_shares_amount.0,
+            env::block_timestamp()
+        ));
     }
 
-    // Helper functions
     fn get_token_contract(&self, token_type: &TokenType) -> AccountId {
         match token_type {
             TokenType::WNEAR => self.config.wnear_contract.clone(),
@@ -356,6 +728,9 @@ impl VaultContract {
         amount: u128,
         is_deposit: bool,
     ) {
+        // Settle rewards against the balance held before this change
+        self.update_rewards(account_id, token_type);
+
         let mut user_shares = self
             .vault_shares
             .get(account_id)
@@ -370,6 +745,15 @@ impl VaultContract {
 
         user_shares.insert(token_type, &U128(new_shares));
         self.vault_shares.insert(account_id, &user_shares);
+
+        // Track outstanding shares per class for reward accounting and reconciliation
+        let class_supply = self.total_deposits.get(token_type).unwrap_or(U128(0));
+        let new_class_supply = if is_deposit {
+            class_supply.0 + amount
+        } else {
+            class_supply.0.saturating_sub(amount)
+        };
+        self.total_deposits.insert(token_type, &U128(new_class_supply));
     }
 
     // Events query functions
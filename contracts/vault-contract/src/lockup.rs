@@ -0,0 +1,450 @@
+use crate::*;
+
+const NANOS_PER_DAY: u64 = 24 * 60 * 60 * 1_000_000_000;
+const BPS_DENOMINATOR: u128 = 10_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum LockTerm {
+    Days30,
+    Days90,
+    Days180,
+}
+
+impl LockTerm {
+    pub fn duration(&self) -> u64 {
+        match self {
+            LockTerm::Days30 => 30 * NANOS_PER_DAY,
+            LockTerm::Days90 => 90 * NANOS_PER_DAY,
+            LockTerm::Days180 => 180 * NANOS_PER_DAY,
+        }
+    }
+}
+
+/// Reward multipliers per lock term and the early-exit penalty, in basis points
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockConfig {
+    pub multiplier_30d_bps: u16, // e.g. 11000 = 1.1x
+    pub multiplier_90d_bps: u16,
+    pub multiplier_180d_bps: u16,
+    pub early_exit_penalty_bps: u16,
+}
+
+impl LockConfig {
+    pub fn multiplier(&self, term: &LockTerm) -> u16 {
+        match term {
+            LockTerm::Days30 => self.multiplier_30d_bps,
+            LockTerm::Days90 => self.multiplier_90d_bps,
+            LockTerm::Days180 => self.multiplier_180d_bps,
+        }
+    }
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockPosition {
+    pub id: u64,
+    pub token_type: TokenType,
+    pub shares: U128,
+    pub term: LockTerm,
+    pub multiplier_bps: u16,
+    pub locked_at: Timestamp,
+    pub unlock_at: Timestamp,
+}
+
+impl LockPosition {
+    /// Reward weight this position adds on top of its plain shares
+    fn boost(&self) -> u128 {
+        self.shares.0 * (self.multiplier_bps as u128).saturating_sub(BPS_DENOMINATOR) / BPS_DENOMINATOR
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LockBoost {
+    pub token_type: TokenType,
+    pub shares: U128,
+    pub locked_shares: U128,
+    pub reward_weight: U128,
+    pub boost_bps: u32, // Effective reward multiplier across all shares, 10000 = 1x
+}
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_lock_config(&self) -> LockConfig {
+        self.lock_config.clone()
+    }
+
+    pub fn get_locks(&self, account_id: AccountId) -> Vec<LockPosition> {
+        self.lock_positions.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_lock_boost(&self, account_id: AccountId, token_type: TokenType) -> LockBoost {
+        let shares = self.get_user_vault_shares(account_id.clone(), token_type.clone());
+        let reward_weight = self.reward_weight(&account_id, &token_type);
+        // Without shares there is nothing to boost
+        let boost_bps = reward_weight
+            .checked_mul(BPS_DENOMINATOR)
+            .and_then(|weight_bps| weight_bps.checked_div(shares.0))
+            .map_or(BPS_DENOMINATOR as u32, |boost_bps| boost_bps.min(u32::MAX as u128) as u32);
+
+        LockBoost {
+            locked_shares: U128(self.locked_shares(&account_id, &token_type)),
+            token_type,
+            shares,
+            reward_weight: U128(reward_weight),
+            boost_bps,
+        }
+    }
+
+    /// Locks unlocked shares for a fixed term in exchange for a larger reward share
+    pub fn lock_shares(&mut self, token_type: TokenType, shares: U128, term: LockTerm) -> LockPosition {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(shares.0 > 0, "Amount must be greater than zero");
+
+        let account_id = env::predecessor_account_id();
+        self.release_expired_locks(&account_id, &token_type);
+        require!(
+            self.available_shares(&account_id, &token_type) >= shares.0,
+            "Insufficient unlocked vault shares"
+        );
+
+        let now = env::block_timestamp();
+        let position = LockPosition {
+            id: self.next_lock_id,
+            token_type: token_type.clone(),
+            shares,
+            multiplier_bps: self.lock_config.multiplier(&term),
+            unlock_at: now + term.duration(),
+            term,
+            locked_at: now,
+        };
+        self.next_lock_id += 1;
+
+        self.update_rewards(&account_id, &token_type);
+        self.adjust_lock_boost(&account_id, &token_type, position.boost(), true);
+
+        let mut positions = self.get_locks(account_id.clone());
+        positions.push(position.clone());
+        self.lock_positions.insert(&account_id, &positions);
+
+        self.emit_lock_event("lock_created", &account_id, &position, 0);
+        position
+    }
+
+    /// Restarts a position's lock for `term` from now; the new unlock time may not be earlier
+    pub fn extend_lock(&mut self, lock_id: u64, term: LockTerm) -> LockPosition {
+        let account_id = env::predecessor_account_id();
+        let mut positions = self.get_locks(account_id.clone());
+        let index = positions
+            .iter()
+            .position(|p| p.id == lock_id)
+            .unwrap_or_else(|| env::panic_str("Lock not found"));
+
+        let mut position = positions[index].clone();
+        let new_unlock_at = env::block_timestamp() + term.duration();
+        require!(new_unlock_at >= position.unlock_at, "Lock can only be extended");
+
+        self.update_rewards(&account_id, &position.token_type);
+        self.adjust_lock_boost(&account_id, &position.token_type, position.boost(), false);
+        position.multiplier_bps = self.lock_config.multiplier(&term);
+        position.term = term;
+        position.unlock_at = new_unlock_at;
+        self.adjust_lock_boost(&account_id, &position.token_type, position.boost(), true);
+
+        positions[index] = position.clone();
+        self.lock_positions.insert(&account_id, &positions);
+
+        self.emit_lock_event("lock_extended", &account_id, &position, 0);
+        position
+    }
+
+    /// Unlocks a position before its term ends. The penalty is taken from the locked
+    /// shares and distributed to the remaining holders of the share class.
+    pub fn exit_lock_early(&mut self, lock_id: u64) -> U128 {
+        require!(!self.config.is_paused, "Vault is paused");
+
+        let account_id = env::predecessor_account_id();
+        let mut positions = self.get_locks(account_id.clone());
+        let index = positions
+            .iter()
+            .position(|p| p.id == lock_id)
+            .unwrap_or_else(|| env::panic_str("Lock not found"));
+        let position = positions.remove(index);
+        let token_type = position.token_type.clone();
+
+        self.update_rewards(&account_id, &token_type);
+        self.adjust_lock_boost(&account_id, &token_type, position.boost(), false);
+        self.lock_positions.insert(&account_id, &positions);
+
+        let penalty = if env::block_timestamp() < position.unlock_at {
+            position.shares.0 * self.lock_config.early_exit_penalty_bps as u128 / BPS_DENOMINATOR
+        } else {
+            0
+        };
+
+        if penalty > 0 {
+            // Burn the penalty shares and hand the underlying tokens to the other holders
+            self.update_token_reserves(&token_type, penalty, false);
+            self.update_user_vault_shares(&account_id, &token_type, penalty, false);
+            self.total_supply = U128(self.total_supply.0 - penalty);
+
+            if !self.distribute_to_holders(&token_type, penalty, &account_id) {
+                let protocol_fees = self.protocol_fees.get(&token_type).unwrap_or(U128(0));
+                self.protocol_fees.insert(&token_type, &U128(protocol_fees.0 + penalty));
+            }
+        }
+
+        self.emit_lock_event("lock_exited", &account_id, &position, penalty);
+        U128(position.shares.0 - penalty)
+    }
+
+    // Admin functions
+    pub fn update_lock_config(&mut self, lock_config: LockConfig) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can update lock config"
+        );
+        require!(
+            lock_config.multiplier_30d_bps as u128 >= BPS_DENOMINATOR
+                && lock_config.multiplier_90d_bps as u128 >= BPS_DENOMINATOR
+                && lock_config.multiplier_180d_bps as u128 >= BPS_DENOMINATOR,
+            "Multipliers must be at least 10000 basis points"
+        );
+        require!(
+            lock_config.early_exit_penalty_bps as u128 <= BPS_DENOMINATOR,
+            "Penalty cannot exceed 10000 basis points"
+        );
+        self.lock_config = lock_config;
+        log!("Lock config updated");
+    }
+
+    /// Shares of `token_type` not held by an active lock
+    pub(crate) fn available_shares(&self, account_id: &AccountId, token_type: &TokenType) -> u128 {
+        let shares = self.get_user_vault_shares(account_id.clone(), token_type.clone());
+        shares.0.saturating_sub(self.locked_shares(account_id, token_type))
+    }
+
+    /// Drops matured positions and their boost. Rewards are settled first so the
+    /// boost counts up to this point.
+    pub(crate) fn release_expired_locks(&mut self, account_id: &AccountId, token_type: &TokenType) {
+        let positions = match self.lock_positions.get(account_id) {
+            Some(positions) => positions,
+            None => return,
+        };
+
+        let now = env::block_timestamp();
+        let (expired, active): (Vec<LockPosition>, Vec<LockPosition>) = positions
+            .into_iter()
+            .partition(|p| &p.token_type == token_type && p.unlock_at <= now);
+        if expired.is_empty() {
+            return;
+        }
+
+        self.update_rewards(account_id, token_type);
+        for position in expired.iter() {
+            self.adjust_lock_boost(account_id, token_type, position.boost(), false);
+            self.emit_lock_event("lock_released", account_id, position, 0);
+        }
+
+        if active.is_empty() {
+            self.lock_positions.remove(account_id);
+        } else {
+            self.lock_positions.insert(account_id, &active);
+        }
+    }
+
+    /// Reward weight of an account: its shares plus any lock boost
+    pub(crate) fn reward_weight(&self, account_id: &AccountId, token_type: &TokenType) -> u128 {
+        let shares = self.get_user_vault_shares(account_id.clone(), token_type.clone());
+        let boost = self
+            .lock_boosts
+            .get(&(account_id.clone(), token_type.clone()))
+            .unwrap_or(U128(0));
+        shares.0 + boost.0
+    }
+
+    /// Total reward weight of a share class
+    pub(crate) fn reward_supply(&self, token_type: &TokenType) -> u128 {
+        let shares = self.total_deposits.get(token_type).unwrap_or(U128(0));
+        let boost = self.boosted_supply.get(token_type).unwrap_or(U128(0));
+        shares.0 + boost.0
+    }
+
+    fn locked_shares(&self, account_id: &AccountId, token_type: &TokenType) -> u128 {
+        let now = env::block_timestamp();
+        self.get_locks(account_id.clone())
+            .iter()
+            .filter(|p| &p.token_type == token_type && p.unlock_at > now)
+            .map(|p| p.shares.0)
+            .sum()
+    }
+
+    fn adjust_lock_boost(&mut self, account_id: &AccountId, token_type: &TokenType, boost: u128, is_increase: bool) {
+        let key = (account_id.clone(), token_type.clone());
+        let user_boost = self.lock_boosts.get(&key).unwrap_or(U128(0)).0;
+        let class_boost = self.boosted_supply.get(token_type).unwrap_or(U128(0)).0;
+
+        let (new_user_boost, new_class_boost) = if is_increase {
+            (user_boost + boost, class_boost + boost)
+        } else {
+            (user_boost.saturating_sub(boost), class_boost.saturating_sub(boost))
+        };
+
+        if new_user_boost == 0 {
+            self.lock_boosts.remove(&key);
+        } else {
+            self.lock_boosts.insert(&key, &U128(new_user_boost));
+        }
+        self.boosted_supply.insert(token_type, &U128(new_class_boost));
+    }
+
+    fn emit_lock_event(&self, event_type: &str, account_id: &AccountId, position: &LockPosition, penalty: u128) {
+        log!(
+            "{}: {} {:?} shares of {} (lock {}, unlocks at {})",
+            event_type,
+            position.shares.0,
+            position.token_type,
+            account_id,
+            position.id,
+            position.unlock_at
+        );
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"{}\",\"account_id\":\"{}\",\"lock_id\":{},\"token_type\":\"{:?}\",\"shares\":\"{}\",\"term\":\"{:?}\",\"multiplier_bps\":{},\"unlock_at\":{},\"penalty\":\"{}\",\"timestamp\":{}}}",
+            event_type,
+            account_id,
+            position.id,
+            position.token_type,
+            position.shares.0,
+            position.term,
+            position.multiplier_bps,
+            position.unlock_at,
+            penalty,
+            env::block_timestamp()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    /// Alice and Bob each hold 1000 USDC shares
+    fn setup_holders() -> (VaultContract, AccountId, AccountId) {
+        let mut contract = setup();
+        let (alice, bob) = (account("alice"), account("bob"));
+        deposit_usdc(&mut contract, &alice, 1_000, 0);
+        deposit_usdc(&mut contract, &bob, 1_000, 0);
+        (contract, alice, bob)
+    }
+
+    fn lock(contract: &mut VaultContract, account_id: &AccountId, shares: u128, term: LockTerm) -> LockPosition {
+        set_context(account_id.clone(), 0);
+        contract.lock_shares(TokenType::USDC, U128(shares), term)
+    }
+
+    #[test]
+    fn lock_adds_boost_to_reward_weight_and_supply() {
+        let (mut contract, alice, _) = setup_holders();
+
+        lock(&mut contract, &alice, 1_000, LockTerm::Days180);
+
+        let boost = contract.get_lock_boost(alice.clone(), TokenType::USDC);
+        assert_eq!((boost.locked_shares, boost.reward_weight, boost.boost_bps), (U128(1_000), U128(1_500), 15_000));
+        assert_eq!(contract.boosted_supply.get(&TokenType::USDC), Some(U128(500)));
+        assert_eq!(contract.reward_supply(&TokenType::USDC), 2_500);
+    }
+
+    #[test]
+    fn boosted_holders_earn_a_larger_reward_share() {
+        let (mut contract, alice, bob) = setup_holders();
+        lock(&mut contract, &alice, 1_000, LockTerm::Days180);
+        fund_usdc_rewards(&mut contract, 25, 100, 0);
+
+        set_context(bob.clone(), 100 * SECOND);
+
+        // 1500 of 2500 weight for alice, 1000 for bob
+        assert_eq!(pending_usdc_rewards(&contract, &alice), 1_500);
+        assert_eq!(pending_usdc_rewards(&contract, &bob), 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Insufficient unlocked vault shares")]
+    fn locked_shares_cannot_be_locked_twice() {
+        let (mut contract, alice, _) = setup_holders();
+        lock(&mut contract, &alice, 600, LockTerm::Days30);
+        lock(&mut contract, &alice, 600, LockTerm::Days30);
+    }
+
+    #[test]
+    fn extend_lock_moves_the_boost_to_the_new_term() {
+        let (mut contract, alice, _) = setup_holders();
+        let position = lock(&mut contract, &alice, 1_000, LockTerm::Days30);
+        assert_eq!(contract.boosted_supply.get(&TokenType::USDC), Some(U128(100)));
+
+        set_context(alice.clone(), 10 * DAY);
+        let extended = contract.extend_lock(position.id, LockTerm::Days90);
+
+        assert_eq!(extended.unlock_at, 100 * DAY);
+        assert_eq!(extended.multiplier_bps, 12_500);
+        assert_eq!(contract.boosted_supply.get(&TokenType::USDC), Some(U128(250)));
+        assert_eq!(contract.get_lock_boost(alice, TokenType::USDC).reward_weight, U128(1_250));
+    }
+
+    #[test]
+    #[should_panic(expected = "Lock can only be extended")]
+    fn extend_lock_cannot_shorten_the_lock() {
+        let (mut contract, alice, _) = setup_holders();
+        let position = lock(&mut contract, &alice, 1_000, LockTerm::Days180);
+
+        set_context(alice, DAY);
+        contract.extend_lock(position.id, LockTerm::Days90);
+    }
+
+    #[test]
+    fn early_exit_penalty_is_distributed_to_other_holders() {
+        let (mut contract, alice, bob) = setup_holders();
+        let position = lock(&mut contract, &alice, 1_000, LockTerm::Days90);
+
+        set_context(alice.clone(), DAY);
+        let released = contract.exit_lock_early(position.id);
+
+        assert_eq!(released, U128(900));
+        assert_eq!(contract.get_user_vault_shares(alice.clone(), TokenType::USDC), U128(900));
+        assert_eq!(contract.get_token_reserves(TokenType::USDC), U128(1_900));
+        assert_eq!(contract.boosted_supply.get(&TokenType::USDC), Some(U128(0)));
+        assert_eq!(pending_usdc_rewards(&contract, &bob), 100);
+        assert_eq!(pending_usdc_rewards(&contract, &alice), 0);
+        assert_eq!(contract.reward_liability(&usdc()), 100);
+    }
+
+    #[test]
+    fn early_exit_penalty_without_other_holders_is_protocol_revenue() {
+        let mut contract = setup();
+        let alice = account("alice");
+        deposit_usdc(&mut contract, &alice, 1_000, 0);
+        let position = lock(&mut contract, &alice, 1_000, LockTerm::Days30);
+
+        set_context(alice, DAY);
+        contract.exit_lock_early(position.id);
+
+        assert_eq!(contract.get_protocol_fees(TokenType::USDC), U128(100));
+        assert_eq!(contract.reward_liability(&usdc()), 0);
+    }
+
+    #[test]
+    fn exit_after_unlock_has_no_penalty() {
+        let (mut contract, alice, _) = setup_holders();
+        let position = lock(&mut contract, &alice, 1_000, LockTerm::Days30);
+
+        set_context(alice.clone(), 30 * DAY);
+        let released = contract.exit_lock_early(position.id);
+
+        assert_eq!(released, U128(1_000));
+        assert_eq!(contract.get_user_vault_shares(alice, TokenType::USDC), U128(1_000));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::*;
+
+/// State layout of the vault before rewards, fees, locks and the circuit breaker
+mod v0 {
+    use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+    use near_sdk::collections::UnorderedMap;
+    use near_sdk::json_types::U128;
+    use near_sdk::{AccountId, Timestamp};
+
+    use crate::TokenType;
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct VaultConfig {
+        pub owner_id: AccountId,
+        pub wnear_contract: AccountId,
+        pub usdc_contract: AccountId,
+        pub usdt_contract: AccountId,
+        pub fee_percentage: u16,
+        pub is_paused: bool,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct DepositEvent {
+        pub account_id: AccountId,
+        pub token_type: TokenType,
+        pub amount: U128,
+        pub vault_shares_minted: U128,
+        pub timestamp: Timestamp,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct WithdrawEvent {
+        pub account_id: AccountId,
+        pub token_type: TokenType,
+        pub amount: U128,
+        pub vault_shares_burned: U128,
+        pub yield_earned: U128,
+        pub timestamp: Timestamp,
+    }
+
+    #[derive(BorshDeserialize, BorshSerialize)]
+    pub struct VaultContract {
+        pub config: VaultConfig,
+        pub total_supply: U128,
+        pub total_deposits: UnorderedMap<TokenType, U128>,
+        pub vault_shares: UnorderedMap<AccountId, UnorderedMap<TokenType, U128>>,
+        pub token_reserves: UnorderedMap<TokenType, U128>,
+        pub deposit_events: Vec<DepositEvent>,
+        pub withdraw_events: Vec<WithdrawEvent>,
+    }
+}
+
+#[near_bindgen]
+impl VaultContract {
+    /// Upgrades a vault deployed before rewards, referrals, locks, the holding period and the
+    /// circuit breaker. Every new feature starts at its `new` default.
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let legacy: v0::VaultContract = env::state_read().expect("No vault state to migrate");
+
+        let config = VaultConfig {
+            owner_id: legacy.config.owner_id,
+            wnear_contract: legacy.config.wnear_contract,
+            usdc_contract: legacy.config.usdc_contract,
+            usdt_contract: legacy.config.usdt_contract,
+            fee_percentage: legacy.config.fee_percentage,
+            is_paused: legacy.config.is_paused,
+            referral_fee_share: 0,
+            deposit_fee_enabled: false,
+        };
+        let mut contract = Self::with_config(config);
+        contract.total_supply = legacy.total_supply;
+        contract.vault_shares = legacy.vault_shares;
+        contract.token_reserves = legacy.token_reserves;
+
+        contract.deposit_events = legacy
+            .deposit_events
+            .into_iter()
+            .map(|event| DepositEvent {
+                account_id: event.account_id,
+                token_type: event.token_type,
+                amount: event.amount,
+                vault_shares_minted: event.vault_shares_minted,
+                fee: U128(0),
+                referrer: None,
+                referral_fee: U128(0),
+                timestamp: event.timestamp,
+            })
+            .collect();
+        contract.withdraw_events = legacy
+            .withdraw_events
+            .into_iter()
+            .map(|event| WithdrawEvent {
+                account_id: event.account_id,
+                token_type: event.token_type,
+                amount: event.amount,
+                vault_shares_burned: event.vault_shares_burned,
+                yield_earned: event.yield_earned,
+                exit_fee: U128(0),
+                timestamp: event.timestamp,
+            })
+            .collect();
+
+        // The old vault never wrote per-class supply, which rewards and reconciliation read
+        let token_types = [TokenType::WNEAR, TokenType::USDC, TokenType::USDT];
+        let mut class_supply = [0u128; 3];
+        for (_, shares_map) in contract.vault_shares.iter() {
+            for (supply, token_type) in class_supply.iter_mut().zip(token_types.iter()) {
+                *supply += shares_map.get(token_type).unwrap_or(U128(0)).0;
+            }
+        }
+        for (supply, token_type) in class_supply.iter().zip(token_types.iter()) {
+            contract.total_deposits.insert(token_type, &U128(*supply));
+        }
+
+        log!("Migrated vault with {} depositors", contract.vault_shares.len());
+        contract
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::testing_env;
+
+    #[test]
+    fn migrate_rebuilds_class_supply_and_keeps_balances() {
+        testing_env!(VMContextBuilder::new().build());
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let mut vault_shares = UnorderedMap::new(b"vault_shares".to_vec());
+        let mut alice_shares = UnorderedMap::new(format!("shares_{}", alice).as_bytes().to_vec());
+        alice_shares.insert(&TokenType::USDC, &U128(700));
+        vault_shares.insert(&alice, &alice_shares);
+        let mut token_reserves = UnorderedMap::new(b"token_reserves".to_vec());
+        token_reserves.insert(&TokenType::USDC, &U128(700));
+
+        env::state_write(&v0::VaultContract {
+            config: v0::VaultConfig {
+                owner_id: "owner.near".parse().unwrap(),
+                wnear_contract: "wrap.near".parse().unwrap(),
+                usdc_contract: "usdc.near".parse().unwrap(),
+                usdt_contract: "usdt.near".parse().unwrap(),
+                fee_percentage: 100,
+                is_paused: false,
+            },
+            total_supply: U128(INITIAL_SUPPLY + 700),
+            total_deposits: UnorderedMap::new(b"total_deposits".to_vec()),
+            vault_shares,
+            token_reserves,
+            deposit_events: vec![v0::DepositEvent {
+                account_id: alice.clone(),
+                token_type: TokenType::USDC,
+                amount: U128(700),
+                vault_shares_minted: U128(700),
+                timestamp: 0,
+            }],
+            withdraw_events: Vec::new(),
+        });
+
+        let contract = VaultContract::migrate();
+
+        assert_eq!(contract.get_user_vault_shares(alice, TokenType::USDC), U128(700));
+        assert_eq!(contract.get_token_reserves(TokenType::USDC), U128(700));
+        assert_eq!(contract.total_deposits.get(&TokenType::USDC), Some(U128(700)));
+        assert_eq!(contract.total_deposits.get(&TokenType::USDT), Some(U128(0)));
+        assert_eq!(contract.get_config().referral_fee_share, 0);
+        assert!(!contract.get_config().deposit_fee_enabled);
+        assert_eq!(contract.get_deposit_events(None)[0].fee, U128(0));
+    }
+}
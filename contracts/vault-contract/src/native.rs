@@ -0,0 +1,119 @@
+use crate::*;
+
+const GAS_FOR_NEAR_DEPOSIT: Gas = Gas::from_gas(10_000_000_000_000);
+const GAS_FOR_NEAR_WITHDRAW: Gas = Gas::from_gas(10_000_000_000_000);
+const GAS_FOR_NATIVE_CALLBACK: Gas = Gas::from_gas(30_000_000_000_000);
+
+// wNEAR wrapping interface
+#[allow(dead_code)]
+#[ext_contract(ext_wrap_near)]
+trait WrapNear {
+    fn near_deposit(&mut self);
+    fn near_withdraw(&mut self, amount: U128);
+}
+
+#[near_bindgen]
+impl VaultContract {
+    /// Wraps the attached NEAR into wNEAR and credits WNEAR vault shares
+    #[payable]
+    pub fn deposit_near(&mut self, referrer: Option<AccountId>, min_shares_out: Option<U128>) -> Promise {
+        require!(!self.config.is_paused, "Vault is paused");
+
+        let amount = U128(env::attached_deposit().as_yoctonear());
+        require!(amount.0 > 0, "Attached deposit must be greater than zero");
+        if let Some(min_shares_out) = min_shares_out {
+            require!(
+                self.preview_deposit(amount).0 >= min_shares_out.0,
+                "Slippage: vault shares minted below min_shares_out"
+            );
+        }
+
+        let sender_id = env::predecessor_account_id();
+        log!("Wrapping {} NEAR from {}", amount.0, sender_id);
+
+        ext_wrap_near::ext(self.config.wnear_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(amount.0))
+            .with_static_gas(GAS_FOR_NEAR_DEPOSIT)
+            .near_deposit()
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_NATIVE_CALLBACK)
+                    .on_near_wrapped(sender_id, amount, referrer, min_shares_out)
+            )
+    }
+
+    #[private]
+    pub fn on_near_wrapped(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        referrer: Option<AccountId>,
+        min_shares_out: Option<U128>,
+    ) -> PromiseOrValue<U128> {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                let shares_out = self.preview_deposit(amount);
+                if min_shares_out.is_some_and(|min| shares_out.0 < min.0) {
+                    // Fee changed while wrapping; hand back the wrapped tokens instead of panicking
+                    log!("Slippage exceeded, returning {} wNEAR to {}", amount.0, sender_id);
+                    return PromiseOrValue::Promise(
+                        ext_fungible_token::ext(self.config.wnear_contract.clone())
+                            .with_attached_deposit(NearToken::from_yoctonear(1))
+                            .with_static_gas(GAS_FOR_FT_TRANSFER)
+                            .ft_transfer(sender_id, amount, Some("Deposit refund".to_string())),
+                    );
+                }
+
+                PromiseOrValue::Value(self.internal_deposit(
+                    sender_id,
+                    TokenType::WNEAR,
+                    amount,
+                    referrer,
+                    min_shares_out,
+                ))
+            }
+            _ => {
+                log!("Wrapping failed, refunding {} NEAR to {}", amount.0, sender_id);
+                PromiseOrValue::Promise(Promise::new(sender_id).transfer(NearToken::from_yoctonear(amount.0)))
+            }
+        }
+    }
+
+    /// Burns WNEAR vault shares, unwraps the wNEAR and sends native NEAR to the caller
+    pub fn withdraw_near(&mut self, vault_shares_amount: U128, min_amount_out: Option<U128>) -> Promise {
+        require!(!self.config.is_paused, "Vault is paused");
+
+        let sender_id = env::predecessor_account_id();
+        let withdrawal =
+            self.internal_withdraw(&sender_id, &TokenType::WNEAR, vault_shares_amount, min_amount_out);
+
+        ext_wrap_near::ext(self.config.wnear_contract.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_NEAR_WITHDRAW)
+            .near_withdraw(withdrawal.amount)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_NATIVE_CALLBACK)
+                    .on_near_unwrapped(sender_id, withdrawal.amount, withdrawal.vault_shares_burned)
+            )
+    }
+
+    #[private]
+    pub fn on_near_unwrapped(
+        &mut self,
+        account_id: AccountId,
+        amount: U128,
+        vault_shares_amount: U128,
+    ) -> PromiseOrValue<()> {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                log!("Sending {} NEAR to {}", amount.0, account_id);
+                PromiseOrValue::Promise(Promise::new(account_id).transfer(NearToken::from_yoctonear(amount.0)))
+            }
+            _ => {
+                self.restore_withdrawal(&account_id, &TokenType::WNEAR, vault_shares_amount, amount);
+                PromiseOrValue::Value(())
+            }
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::*;
+
+const GAS_FOR_FT_BALANCE_OF: Gas = Gas::from_gas(5_000_000_000_000);
+const GAS_FOR_RECONCILE_CALLBACK: Gas = Gas::from_gas(50_000_000_000_000);
+
+/// Per-token comparison of on-chain balance against internal bookkeeping: reserves,
+/// protocol fees, rewards funded in the token but not paid out, and unclaimed referral fees.
+/// `user_shares` is the running `total_deposits` for the token, not a fresh sum over accounts.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenReconciliation {
+    pub token_type: TokenType,
+    pub token_contract: AccountId,
+    pub on_chain_balance: Option<U128>, // None when the balance query failed
+    pub token_reserves: U128,
+    pub protocol_fees: U128,
+    pub reward_liabilities: U128,
+    pub referral_liabilities: U128,
+    pub user_shares: U128,
+    pub surplus: U128,
+    pub deficit: U128,
+}
+
+/// Token balances are checked per token only, since share amounts in tokens with different
+/// decimals don't add up to anything meaningful
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReconciliationReport {
+    pub tokens: Vec<TokenReconciliation>,
+    pub is_solvent: bool,
+    pub requested_by: AccountId,
+    pub timestamp: Timestamp,
+}
+
+#[near_bindgen]
+impl VaultContract {
+    pub fn get_reconciliation_report(&self) -> Option<ReconciliationReport> {
+        self.last_reconciliation.clone()
+    }
+
+    /// Queries the vault's balance on every supported token and stores a solvency report
+    pub fn reconcile(&mut self) -> Promise {
+        let requested_by = env::predecessor_account_id();
+        log!("Reconciliation requested by {}", requested_by);
+
+        let mut balances: Option<Promise> = None;
+        for token_type in [TokenType::WNEAR, TokenType::USDC, TokenType::USDT] {
+            let balance_of = ext_fungible_token::ext(self.get_token_contract(&token_type))
+                .with_static_gas(GAS_FOR_FT_BALANCE_OF)
+                .ft_balance_of(env::current_account_id());
+            balances = Some(match balances {
+                Some(promise) => promise.and(balance_of),
+                None => balance_of,
+            });
+        }
+
+        balances.unwrap().then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RECONCILE_CALLBACK)
+                .on_reconcile(requested_by)
+        )
+    }
+
+    #[private]
+    pub fn on_reconcile(&mut self, requested_by: AccountId) -> ReconciliationReport {
+        let token_types = [TokenType::WNEAR, TokenType::USDC, TokenType::USDT];
+        // Running per-class totals, so the callback's cost doesn't grow with the number of accounts
+        let share_sums = token_types
+            .clone()
+            .map(|token_type| self.total_deposits.get(&token_type).unwrap_or(U128(0)).0);
+
+        let mut tokens = Vec::new();
+        for (index, token_type) in token_types.iter().enumerate() {
+            let on_chain_balance = match env::promise_result(index as u64) {
+                PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value).ok(),
+                _ => None,
+            };
+
+            let token_reserves = self.get_token_reserves(token_type.clone());
+            let protocol_fees = self.get_protocol_fees(token_type.clone());
+            let reward_liabilities = self.reward_liability(&self.get_token_contract(token_type));
+            let referral_liabilities = self.referral_liability(token_type);
+            let expected = token_reserves.0 + protocol_fees.0 + reward_liabilities + referral_liabilities;
+            let (surplus, deficit) = match on_chain_balance {
+                Some(balance) if balance.0 >= expected => (balance.0 - expected, 0),
+                Some(balance) => (0, expected - balance.0),
+                None => (0, 0),
+            };
+
+            tokens.push(TokenReconciliation {
+                token_type: token_type.clone(),
+                token_contract: self.get_token_contract(token_type),
+                on_chain_balance,
+                token_reserves,
+                protocol_fees,
+                reward_liabilities: U128(reward_liabilities),
+                referral_liabilities: U128(referral_liabilities),
+                user_shares: U128(share_sums[index]),
+                surplus: U128(surplus),
+                deficit: U128(deficit),
+            });
+        }
+
+        let report = ReconciliationReport {
+            is_solvent: tokens
+                .iter()
+                .all(|t| t.on_chain_balance.is_some() && t.deficit.0 == 0 && t.token_reserves.0 >= t.user_shares.0),
+            tokens,
+            requested_by: requested_by.clone(),
+            timestamp: env::block_timestamp(),
+        };
+        self.last_reconciliation = Some(report.clone());
+
+        let token_entries: Vec<String> = report
+            .tokens
+            .iter()
+            .map(|t| {
+                format!(
+                    "{{\"token_type\":\"{:?}\",\"on_chain_balance\":{},\"token_reserves\":\"{}\",\"surplus\":\"{}\",\"deficit\":\"{}\"}}",
+                    t.token_type,
+                    t.on_chain_balance.map_or("null".to_string(), |b| format!("\"{}\"", b.0)),
+                    t.token_reserves.0,
+                    t.surplus.0,
+                    t.deficit.0
+                )
+            })
+            .collect();
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"reconciliation\",\"requested_by\":\"{}\",\"is_solvent\":{},\"tokens\":[{}],\"timestamp\":{}}}",
+            requested_by,
+            report.is_solvent,
+            token_entries.join(","),
+            report.timestamp
+        ));
+
+        report
+    }
+}
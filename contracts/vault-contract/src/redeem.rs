@@ -0,0 +1,118 @@
+use crate::*;
+
+const GAS_FOR_RESOLVE_REDEEM: Gas = Gas::from_gas(30_000_000_000_000);
+
+/// Shares of one class burned by a withdrawal or `redeem_all`, and the tokens owed for them
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Redemption {
+    pub token_type: TokenType,
+    pub vault_shares_burned: U128,
+    pub amount: U128,
+}
+
+#[near_bindgen]
+impl VaultContract {
+    /// Burns `percentage_bps` of every unlocked share class held by the caller and
+    /// sends the underlying tokens back in a single batch of transfers
+    pub fn redeem_all(&mut self, percentage_bps: u16) -> Promise {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(
+            percentage_bps > 0 && percentage_bps <= 10_000,
+            "Percentage must be between 1 and 10000 basis points"
+        );
+
+        let account_id = env::predecessor_account_id();
+        let mut redemptions: Vec<Redemption> = Vec::new();
+        let mut throttled = false;
+
+        for token_type in [TokenType::WNEAR, TokenType::USDC, TokenType::USDT] {
+            self.release_expired_locks(&account_id, &token_type);
+            let shares = self.available_shares(&account_id, &token_type);
+            let shares_to_burn = shares * percentage_bps as u128 / 10_000;
+            if shares_to_burn == 0 {
+                continue;
+            }
+
+            // Classes the circuit breaker throttles down to nothing are skipped
+            match self.try_burn_vault_shares(&account_id, &token_type, U128(shares_to_burn), None) {
+                Some(redemption) => redemptions.push(redemption),
+                None => throttled = true,
+            }
+        }
+        require!(
+            !redemptions.is_empty(),
+            if throttled { "Circuit breaker: throttled outflow limit reached" } else { "No vault shares to redeem" }
+        );
+
+        let mut transfers: Option<Promise> = None;
+        for redemption in redemptions.iter() {
+            let transfer = ext_fungible_token::ext(self.get_token_contract(&redemption.token_type))
+                .with_attached_deposit(NearToken::from_yoctonear(1))
+                .with_static_gas(GAS_FOR_FT_TRANSFER)
+                .ft_transfer(
+                    account_id.clone(),
+                    redemption.amount,
+                    Some(format!("Redeem {:?}", redemption.token_type)),
+                );
+            transfers = Some(match transfers {
+                Some(promise) => promise.and(transfer),
+                None => transfer,
+            });
+        }
+
+        transfers.unwrap().then(
+            ext_self::ext(env::current_account_id())
+                .with_static_gas(GAS_FOR_RESOLVE_REDEEM)
+                .on_redeem_resolved(account_id, percentage_bps, redemptions)
+        )
+    }
+
+    /// Restores shares for every transfer that failed and emits the summary event.
+    /// Returns the redemptions that were actually paid out.
+    #[private]
+    pub fn on_redeem_resolved(
+        &mut self,
+        account_id: AccountId,
+        percentage_bps: u16,
+        redemptions: Vec<Redemption>,
+    ) -> Vec<Redemption> {
+        let mut paid: Vec<Redemption> = Vec::new();
+        let mut entries: Vec<String> = Vec::new();
+
+        for (index, redemption) in redemptions.into_iter().enumerate() {
+            let succeeded = matches!(env::promise_result(index as u64), PromiseResult::Successful(_));
+            if !succeeded {
+                self.restore_withdrawal(
+                    &account_id,
+                    &redemption.token_type,
+                    redemption.vault_shares_burned,
+                    redemption.amount,
+                );
+            }
+
+            entries.push(format!(
+                "{{\"token_type\":\"{:?}\",\"vault_shares_burned\":\"{}\",\"amount\":\"{}\",\"status\":\"{}\"}}",
+                redemption.token_type,
+                redemption.vault_shares_burned.0,
+                redemption.amount.0,
+                if succeeded { "transferred" } else { "refunded" }
+            ));
+            if succeeded {
+                paid.push(redemption);
+            }
+        }
+
+        log!("Redeemed {} bps of vault shares for {}", percentage_bps, account_id);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"redeem_all\",\"account_id\":\"{}\",\"percentage_bps\":{},\"redemptions\":[{}],\"timestamp\":{}}}",
+            account_id,
+            percentage_bps,
+            entries.join(","),
+            env::block_timestamp()
+        ));
+
+        paid
+    }
+}
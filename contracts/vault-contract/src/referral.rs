@@ -0,0 +1,163 @@
+use crate::*;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferralTokenStats {
+    pub token_type: TokenType,
+    pub referred_volume: U128,
+    pub total_earned: U128,
+    pub unclaimed: U128,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReferralStats {
+    pub referrer_id: AccountId,
+    pub referred_accounts: u64,
+    pub tokens: Vec<ReferralTokenStats>,
+}
+
+impl ReferralStats {
+    fn new(referrer_id: AccountId) -> Self {
+        Self {
+            referrer_id,
+            referred_accounts: 0,
+            tokens: Vec::new(),
+        }
+    }
+
+    fn token_stats_mut(&mut self, token_type: &TokenType) -> &mut ReferralTokenStats {
+        if let Some(index) = self.tokens.iter().position(|t| &t.token_type == token_type) {
+            return &mut self.tokens[index];
+        }
+        self.tokens.push(ReferralTokenStats {
+            token_type: token_type.clone(),
+            referred_volume: U128(0),
+            total_earned: U128(0),
+            unclaimed: U128(0),
+        });
+        self.tokens.last_mut().unwrap()
+    }
+}
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_referrer(&self, account_id: AccountId) -> Option<AccountId> {
+        self.referrers.get(&account_id)
+    }
+
+    pub fn get_referral_stats(&self, referrer: AccountId) -> ReferralStats {
+        self.referral_stats
+            .get(&referrer)
+            .unwrap_or_else(|| ReferralStats::new(referrer))
+    }
+
+    // Claim function
+    pub fn claim_referral_rewards(&mut self, token_type: TokenType) -> Promise {
+        let referrer_id = env::predecessor_account_id();
+        let mut stats = self
+            .referral_stats
+            .get(&referrer_id)
+            .unwrap_or_else(|| env::panic_str("No referral rewards"));
+
+        let token_stats = stats.token_stats_mut(&token_type);
+        let amount = token_stats.unclaimed;
+        require!(amount.0 > 0, "No referral rewards to claim");
+        token_stats.unclaimed = U128(0);
+        self.referral_stats.insert(&referrer_id, &stats);
+
+        log!("Claiming {} {:?} referral rewards for {}", amount.0, token_type, referrer_id);
+
+        let token_contract = self.get_token_contract(&token_type);
+        ext_fungible_token::ext(token_contract)
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(referrer_id.clone(), amount, Some("Vault referral rewards".to_string()))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_referral_rewards_claimed(referrer_id, token_type, amount)
+            )
+    }
+
+    #[private]
+    pub fn on_referral_rewards_claimed(&mut self, referrer_id: AccountId, token_type: TokenType, amount: U128) {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                let liability = self.referral_liability(&token_type);
+                self.referral_liabilities
+                    .insert(&token_type, &U128(liability.saturating_sub(amount.0)));
+                env::log_str(&format!(
+                    "EVENT_JSON:{{\"type\":\"referral_rewards_claimed\",\"referrer_id\":\"{}\",\"token_type\":\"{:?}\",\"amount\":\"{}\",\"timestamp\":{}}}",
+                    referrer_id,
+                    token_type,
+                    amount.0,
+                    env::block_timestamp()
+                ));
+            }
+            _ => {
+                // Transfer failed, restore the claimable balance
+                let mut stats = self
+                    .referral_stats
+                    .get(&referrer_id)
+                    .unwrap_or_else(|| ReferralStats::new(referrer_id.clone()));
+                let token_stats = stats.token_stats_mut(&token_type);
+                token_stats.unclaimed = U128(token_stats.unclaimed.0 + amount.0);
+                self.referral_stats.insert(&referrer_id, &stats);
+                log!("Referral reward transfer failed, restored {} {:?} to {}", amount.0, token_type, referrer_id);
+            }
+        }
+    }
+
+    /// Binds `referrer` to an account on its first deposit and returns the referrer
+    /// the deposit is attributed to. Later deposits keep the original binding.
+    pub(crate) fn resolve_referrer(&mut self, account_id: &AccountId, referrer: Option<AccountId>) -> Option<AccountId> {
+        if let Some(bound) = self.referrers.get(account_id) {
+            return Some(bound);
+        }
+
+        let referrer_id = referrer?;
+        if &referrer_id == account_id || self.vault_shares.get(account_id).is_some() {
+            return None;
+        }
+
+        self.referrers.insert(account_id, &referrer_id);
+
+        let mut stats = self
+            .referral_stats
+            .get(&referrer_id)
+            .unwrap_or_else(|| ReferralStats::new(referrer_id.clone()));
+        stats.referred_accounts += 1;
+        self.referral_stats.insert(&referrer_id, &stats);
+
+        log!("Bound referrer {} to {}", referrer_id, account_id);
+        Some(referrer_id)
+    }
+
+    pub(crate) fn record_referred_deposit(
+        &mut self,
+        referrer_id: &AccountId,
+        token_type: &TokenType,
+        amount: u128,
+        referral_fee: u128,
+    ) {
+        let mut stats = self
+            .referral_stats
+            .get(referrer_id)
+            .unwrap_or_else(|| ReferralStats::new(referrer_id.clone()));
+        let token_stats = stats.token_stats_mut(token_type);
+        token_stats.referred_volume = U128(token_stats.referred_volume.0 + amount);
+        token_stats.total_earned = U128(token_stats.total_earned.0 + referral_fee);
+        token_stats.unclaimed = U128(token_stats.unclaimed.0 + referral_fee);
+        self.referral_stats.insert(referrer_id, &stats);
+
+        let liability = self.referral_liability(token_type);
+        self.referral_liabilities.insert(token_type, &U128(liability + referral_fee));
+    }
+
+    /// Referral fees of `token_type` earned by referrers and not claimed yet
+    pub(crate) fn referral_liability(&self, token_type: &TokenType) -> u128 {
+        self.referral_liabilities.get(token_type).unwrap_or(U128(0)).0
+    }
+}
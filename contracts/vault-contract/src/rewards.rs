@@ -0,0 +1,538 @@
+use bond_math::mul_div;
+use crate::*;
+
+// Reward-per-share values are scaled by this factor to keep precision on small deposits
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000_000_000_000_000;
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+const SECONDS_PER_YEAR: u128 = 365 * 24 * 60 * 60;
+// Every deposit and withdrawal settles each program of its class, so keep the list short
+const MAX_REWARD_PROGRAMS_PER_CLASS: usize = 5;
+// Longest reward period a single funding may start
+const MAX_REWARD_DURATION_SEC: u64 = SECONDS_PER_YEAR as u64;
+
+/// A funded reward period paying `reward_token` to holders of one share class
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RewardProgram {
+    pub token_type: TokenType,
+    pub reward_token: AccountId,
+    pub reward_rate: U128, // Reward tokens per second
+    pub period_start: Timestamp,
+    pub period_finish: Timestamp,
+    pub last_update_time: Timestamp,
+    pub reward_per_share: U128, // Scaled by REWARD_PRECISION
+    pub total_funded: U128,
+    pub unallocated: U128, // Funded but claimable by nobody, see `sweep_unallocated_rewards`
+}
+
+impl RewardProgram {
+    /// Reward-per-share accumulator advanced to `now` for the given share supply
+    fn reward_per_share_at(&self, now: Timestamp, share_supply: u128) -> u128 {
+        if share_supply == 0 {
+            return self.reward_per_share.0;
+        }
+        self.reward_per_share.0 + mul_div(self.emitted_until(now), REWARD_PRECISION, share_supply)
+    }
+
+    /// Rewards emitted between the last update and `now`
+    fn emitted_until(&self, now: Timestamp) -> u128 {
+        let applicable_time = now.min(self.period_finish);
+        if applicable_time <= self.last_update_time {
+            return 0;
+        }
+        let elapsed = (applicable_time - self.last_update_time) as u128;
+        mul_div(self.reward_rate.0, elapsed, NANOS_PER_SECOND)
+    }
+
+    /// Adds `amount` to the accumulator for `share_supply` shares. Whatever the shares
+    /// cannot claim, all of it without shares and the rounding dust otherwise, is booked
+    /// as unallocated.
+    fn allocate(&mut self, amount: u128, share_supply: u128) {
+        let increment = if share_supply == 0 { 0 } else { mul_div(amount, REWARD_PRECISION, share_supply) };
+        let allocated = mul_div(increment, share_supply, REWARD_PRECISION);
+        self.reward_per_share = U128(self.reward_per_share.0 + increment);
+        self.unallocated = U128(self.unallocated.0 + amount - allocated);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingReward {
+    pub reward_token: AccountId,
+    pub amount: U128,
+}
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_reward_programs(&self) -> Vec<RewardProgram> {
+        self.reward_programs.values().collect()
+    }
+
+    pub fn get_reward_program(&self, token_type: TokenType, reward_token: AccountId) -> Option<RewardProgram> {
+        self.reward_programs.get(&(token_type, reward_token))
+    }
+
+    /// Reward tokens accepted besides the vault's own wNEAR, USDC and USDT
+    pub fn get_reward_tokens(&self) -> Vec<AccountId> {
+        self.reward_tokens.to_vec()
+    }
+
+    pub fn get_pending_rewards(&self, account_id: AccountId) -> Vec<PendingReward> {
+        let now = env::block_timestamp();
+        let mut pending: Vec<PendingReward> = Vec::new();
+
+        for ((token_type, reward_token), program) in self.reward_programs.iter() {
+            let share_supply = self.reward_supply(&token_type);
+            let reward_per_share = program.reward_per_share_at(now, share_supply);
+            let earned = self.earned_rewards(&account_id, &token_type, &reward_token, reward_per_share);
+
+            match pending.iter_mut().find(|p| p.reward_token == reward_token) {
+                Some(entry) => entry.amount = U128(entry.amount.0 + earned),
+                None => pending.push(PendingReward {
+                    reward_token: reward_token.clone(),
+                    amount: U128(earned),
+                }),
+            }
+        }
+
+        for entry in pending.iter_mut() {
+            let stored = self
+                .pending_rewards
+                .get(&(account_id.clone(), entry.reward_token.clone()))
+                .unwrap_or(U128(0));
+            entry.amount = U128(entry.amount.0 + stored.0);
+        }
+
+        pending
+    }
+
+    /// Annualized reward rate in basis points of the share class supply
+    pub fn get_reward_apr(&self, token_type: TokenType, reward_token: AccountId) -> u32 {
+        let program = match self.reward_programs.get(&(token_type.clone(), reward_token)) {
+            Some(program) => program,
+            None => return 0,
+        };
+        if env::block_timestamp() >= program.period_finish {
+            return 0;
+        }
+
+        let share_supply = self.reward_supply(&token_type);
+        if share_supply == 0 {
+            return 0;
+        }
+
+        let yearly_rewards = program.reward_rate.0.saturating_mul(SECONDS_PER_YEAR);
+        mul_div(yearly_rewards, 10_000, share_supply).min(u32::MAX as u128) as u32
+    }
+
+    // Claim function
+    pub fn claim_rewards(&mut self, reward_token: AccountId) -> Promise {
+        let account_id = env::predecessor_account_id();
+
+        for token_type in [TokenType::WNEAR, TokenType::USDC, TokenType::USDT] {
+            self.update_rewards(&account_id, &token_type);
+        }
+
+        let key = (account_id.clone(), reward_token.clone());
+        let amount = self.pending_rewards.get(&key).unwrap_or(U128(0));
+        require!(amount.0 > 0, "No rewards to claim");
+        self.pending_rewards.remove(&key);
+
+        log!("Claiming {} rewards of {} for {}", amount.0, reward_token, account_id);
+
+        ext_fungible_token::ext(reward_token.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(account_id.clone(), amount, Some("Vault rewards".to_string()))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_rewards_claimed(account_id, reward_token, amount)
+            )
+    }
+
+    #[private]
+    pub fn on_rewards_claimed(&mut self, account_id: AccountId, reward_token: AccountId, amount: U128) {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                self.release_reward_liability(&reward_token, amount.0);
+                env::log_str(&format!(
+                    "EVENT_JSON:{{\"type\":\"rewards_claimed\",\"account_id\":\"{}\",\"reward_token\":\"{}\",\"amount\":\"{}\",\"timestamp\":{}}}",
+                    account_id,
+                    reward_token,
+                    amount.0,
+                    env::block_timestamp()
+                ));
+            }
+            _ => {
+                // Transfer failed, restore the claimable balance
+                let key = (account_id.clone(), reward_token.clone());
+                let pending = self.pending_rewards.get(&key).unwrap_or(U128(0));
+                self.pending_rewards.insert(&key, &U128(pending.0 + amount.0));
+                log!("Reward transfer failed, restored {} {} to {}", amount.0, reward_token, account_id);
+            }
+        }
+    }
+
+    // Admin functions
+    /// Sends the owner the rewards of a program that no holder can claim: emissions while
+    /// the share class was empty and accumulator rounding dust
+    #[payable]
+    pub fn sweep_unallocated_rewards(&mut self, token_type: TokenType, reward_token: AccountId) -> Promise {
+        assert_one_yocto();
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can sweep rewards"
+        );
+
+        let key = (token_type.clone(), reward_token.clone());
+        self.checkpoint_reward_program(&key);
+        let mut program = self.reward_programs.get(&key).expect("Reward program not found");
+        let amount = program.unallocated;
+        require!(amount.0 > 0, "No unallocated rewards");
+        program.unallocated = U128(0);
+        self.reward_programs.insert(&key, &program);
+        self.release_reward_liability(&reward_token, amount.0);
+
+        let owner_id = self.config.owner_id.clone();
+        log!("Sweeping {} unallocated {} rewards to {}", amount.0, reward_token, owner_id);
+
+        ext_fungible_token::ext(reward_token.clone())
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_FT_TRANSFER)
+            .ft_transfer(owner_id, amount, Some("Unallocated vault rewards".to_string()))
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .on_rewards_swept(token_type, reward_token, amount)
+            )
+    }
+
+    #[private]
+    pub fn on_rewards_swept(&mut self, token_type: TokenType, reward_token: AccountId, amount: U128) {
+        match env::promise_result(0) {
+            PromiseResult::Successful(_) => {
+                env::log_str(&format!(
+                    "EVENT_JSON:{{\"type\":\"rewards_swept\",\"token_type\":\"{:?}\",\"reward_token\":\"{}\",\"amount\":\"{}\",\"timestamp\":{}}}",
+                    token_type,
+                    reward_token,
+                    amount.0,
+                    env::block_timestamp()
+                ));
+            }
+            _ => {
+                // Transfer failed, book the rewards as unallocated again
+                let key = (token_type, reward_token.clone());
+                if let Some(mut program) = self.reward_programs.get(&key) {
+                    program.unallocated = U128(program.unallocated.0 + amount.0);
+                    self.reward_programs.insert(&key, &program);
+                }
+                self.add_reward_liability(&reward_token, amount.0);
+                log!("Reward sweep failed, restored {} {}", amount.0, reward_token);
+            }
+        }
+    }
+
+    pub fn add_reward_token(&mut self, reward_token: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can add reward tokens"
+        );
+        self.reward_tokens.insert(&reward_token);
+        log!("Reward token {} accepted", reward_token);
+    }
+
+    /// Stops new funding in `reward_token`; running programs keep paying out
+    pub fn remove_reward_token(&mut self, reward_token: AccountId) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can remove reward tokens"
+        );
+        self.reward_tokens.remove(&reward_token);
+        log!("Reward token {} removed", reward_token);
+    }
+
+    /// Starts a reward period from tokens received through `ft_transfer_call`.
+    /// Returns the unused part of `amount` to be refunded to the sender.
+    /// `sender_id` is reported by the token contract, so it is only trusted for
+    /// accepted reward tokens.
+    pub(crate) fn fund_reward_program(
+        &mut self,
+        sender_id: AccountId,
+        reward_token: AccountId,
+        amount: U128,
+        token_type: TokenType,
+        reward_rate: U128,
+        duration_sec: u64,
+    ) -> U128 {
+        require!(self.is_reward_token(&reward_token), "Reward token is not accepted");
+        require!(sender_id == self.config.owner_id, "Only owner can fund rewards");
+        require!(reward_rate.0 > 0, "Reward rate must be greater than zero");
+        require!(duration_sec > 0, "Duration must be greater than zero");
+        require!(duration_sec <= MAX_REWARD_DURATION_SEC, "Duration cannot exceed one year");
+
+        let required = reward_rate
+            .0
+            .checked_mul(duration_sec as u128)
+            .unwrap_or_else(|| env::panic_str("Reward amount overflow"));
+        require!(amount.0 >= required, "Insufficient tokens for reward rate and duration");
+
+        let key = (token_type.clone(), reward_token.clone());
+        if self.reward_programs.get(&key).is_none() {
+            let class_programs = self
+                .reward_programs
+                .keys()
+                .filter(|(program_type, _)| program_type == &token_type)
+                .count();
+            require!(
+                class_programs < MAX_REWARD_PROGRAMS_PER_CLASS,
+                "Too many reward programs for this token type"
+            );
+        }
+        self.checkpoint_reward_program(&key);
+
+        let now = env::block_timestamp();
+        let mut program = self.reward_programs.get(&key).unwrap_or(RewardProgram {
+            token_type: token_type.clone(),
+            reward_token: reward_token.clone(),
+            reward_rate: U128(0),
+            period_start: now,
+            period_finish: now,
+            last_update_time: now,
+            reward_per_share: U128(0),
+            total_funded: U128(0),
+            unallocated: U128(0),
+        });
+        require!(now >= program.period_finish, "Reward period still active");
+        let period_finish = duration_sec
+            .checked_mul(NANOS_PER_SECOND as u64)
+            .and_then(|duration| now.checked_add(duration))
+            .unwrap_or_else(|| env::panic_str("Reward period overflow"));
+
+        program.reward_rate = reward_rate;
+        program.period_start = now;
+        program.period_finish = period_finish;
+        program.last_update_time = now;
+        program.total_funded = U128(program.total_funded.0 + required);
+        self.reward_programs.insert(&key, &program);
+        self.add_reward_liability(&reward_token, required);
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"rewards_funded\",\"token_type\":\"{:?}\",\"reward_token\":\"{}\",\"amount\":\"{}\",\"reward_rate\":\"{}\",\"period_finish\":{},\"timestamp\":{}}}",
+            token_type,
+            reward_token,
+            required,
+            reward_rate.0,
+            program.period_finish,
+            now
+        ));
+
+        U128(amount.0 - required)
+    }
+
+    /// Settles accrued rewards for an account before its shares of `token_type` change
+    pub(crate) fn update_rewards(&mut self, account_id: &AccountId, token_type: &TokenType) {
+        let keys: Vec<(TokenType, AccountId)> = self
+            .reward_programs
+            .keys()
+            .filter(|(program_type, _)| program_type == token_type)
+            .collect();
+
+        for key in keys {
+            let reward_per_share = self.checkpoint_reward_program(&key);
+            let reward_token = key.1;
+            let earned = self.earned_rewards(account_id, token_type, &reward_token, reward_per_share);
+
+            if earned > 0 {
+                let pending_key = (account_id.clone(), reward_token.clone());
+                let pending = self.pending_rewards.get(&pending_key).unwrap_or(U128(0));
+                self.pending_rewards.insert(&pending_key, &U128(pending.0 + earned));
+            }
+            self.reward_checkpoints.insert(
+                &(account_id.clone(), token_type.clone(), reward_token),
+                &U128(reward_per_share),
+            );
+        }
+    }
+
+    /// Shares `amount` of the class's underlying token among holders of `token_type`
+    /// through the reward accumulator, leaving out `excluded_account`, whose rewards
+    /// must already be settled. Returns false when there is nobody to distribute to.
+    pub(crate) fn distribute_to_holders(
+        &mut self,
+        token_type: &TokenType,
+        amount: u128,
+        excluded_account: &AccountId,
+    ) -> bool {
+        let share_supply = self.reward_supply(token_type);
+        let eligible_supply = share_supply.saturating_sub(self.reward_weight(excluded_account, token_type));
+        if eligible_supply == 0 || amount == 0 {
+            return false;
+        }
+
+        let reward_token = self.get_token_contract(token_type);
+        let key = (token_type.clone(), reward_token.clone());
+        self.checkpoint_reward_program(&key);
+
+        let now = env::block_timestamp();
+        let mut program = self.reward_programs.get(&key).unwrap_or(RewardProgram {
+            token_type: token_type.clone(),
+            reward_token: reward_token.clone(),
+            reward_rate: U128(0),
+            period_start: now,
+            period_finish: now,
+            last_update_time: now,
+            reward_per_share: U128(0),
+            total_funded: U128(0),
+            unallocated: U128(0),
+        });
+        program.allocate(amount, eligible_supply);
+        program.total_funded = U128(program.total_funded.0 + amount);
+        self.reward_programs.insert(&key, &program);
+        self.add_reward_liability(&reward_token, amount);
+
+        self.reward_checkpoints.insert(
+            &(excluded_account.clone(), token_type.clone(), reward_token),
+            &program.reward_per_share,
+        );
+        true
+    }
+
+    /// Funded rewards of `reward_token` that have not been paid out yet
+    pub(crate) fn reward_liability(&self, reward_token: &AccountId) -> u128 {
+        self.reward_liabilities.get(reward_token).unwrap_or(U128(0)).0
+    }
+
+    fn add_reward_liability(&mut self, reward_token: &AccountId, amount: u128) {
+        let liability = self.reward_liability(reward_token);
+        self.reward_liabilities.insert(reward_token, &U128(liability + amount));
+    }
+
+    fn release_reward_liability(&mut self, reward_token: &AccountId, amount: u128) {
+        let liability = self.reward_liability(reward_token);
+        self.reward_liabilities.insert(reward_token, &U128(liability.saturating_sub(amount)));
+    }
+
+    fn is_reward_token(&self, token_id: &AccountId) -> bool {
+        self.reward_tokens.contains(token_id)
+            || [&self.config.wnear_contract, &self.config.usdc_contract, &self.config.usdt_contract].contains(&token_id)
+    }
+
+    /// Advances the program accumulator to the current block and returns it
+    fn checkpoint_reward_program(&mut self, key: &(TokenType, AccountId)) -> u128 {
+        let mut program = match self.reward_programs.get(key) {
+            Some(program) => program,
+            None => return 0,
+        };
+
+        let now = env::block_timestamp();
+        let share_supply = self.reward_supply(&key.0);
+        program.allocate(program.emitted_until(now), share_supply);
+        program.last_update_time = program.last_update_time.max(now.min(program.period_finish));
+        self.reward_programs.insert(key, &program);
+
+        program.reward_per_share.0
+    }
+
+    fn earned_rewards(
+        &self,
+        account_id: &AccountId,
+        token_type: &TokenType,
+        reward_token: &AccountId,
+        reward_per_share: u128,
+    ) -> u128 {
+        let paid = self
+            .reward_checkpoints
+            .get(&(account_id.clone(), token_type.clone(), reward_token.clone()))
+            .unwrap_or(U128(0));
+        let weight = self.reward_weight(account_id, token_type);
+
+        mul_div(weight, reward_per_share.saturating_sub(paid.0), REWARD_PRECISION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn emissions_without_shares_are_unallocated() {
+        let mut contract = setup();
+        fund_usdc_rewards(&mut contract, 10, 100, 0);
+
+        // Nobody holds USDC shares for the first 40 seconds
+        let alice = account("alice");
+        deposit_usdc(&mut contract, &alice, 1_000, 40 * SECOND);
+        set_context(alice.clone(), 100 * SECOND);
+
+        assert_eq!(pending_usdc_rewards(&contract, &alice), 600);
+        let program = contract.get_reward_program(TokenType::USDC, usdc()).unwrap();
+        assert_eq!(program.unallocated, U128(400));
+    }
+
+    #[test]
+    fn rounding_dust_is_unallocated() {
+        let mut contract = setup();
+        deposit_usdc(&mut contract, &account("alice"), 3, 0);
+        deposit_usdc(&mut contract, &account("bob"), 3, 0);
+        fund_usdc_rewards(&mut contract, 1, 10, 0);
+
+        // Six shares split ten tokens at a precision that does not divide evenly
+        deposit_usdc(&mut contract, &account("carol"), 1, 10 * SECOND);
+
+        let program = contract.get_reward_program(TokenType::USDC, usdc()).unwrap();
+        assert_eq!(program.unallocated, U128(1));
+        assert_eq!(pending_usdc_rewards(&contract, &account("alice")), 4);
+    }
+
+    #[test]
+    fn sweep_releases_unallocated_liability() {
+        let mut contract = setup();
+        fund_usdc_rewards(&mut contract, 10, 100, 0);
+        assert_eq!(contract.reward_liability(&usdc()), 1_000);
+
+        set_context_with_deposit(owner(), 200 * SECOND, 1);
+        contract.sweep_unallocated_rewards(TokenType::USDC, usdc());
+
+        assert_eq!(contract.reward_liability(&usdc()), 0);
+        let program = contract.get_reward_program(TokenType::USDC, usdc()).unwrap();
+        assert_eq!(program.unallocated, U128(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "No unallocated rewards")]
+    fn sweep_leaves_claimable_rewards() {
+        let mut contract = setup();
+        deposit_usdc(&mut contract, &account("alice"), 1_000, 0);
+        fund_usdc_rewards(&mut contract, 10, 100, 0);
+
+        set_context_with_deposit(owner(), 200 * SECOND, 1);
+        contract.sweep_unallocated_rewards(TokenType::USDC, usdc());
+    }
+
+    #[test]
+    #[should_panic(expected = "Only owner can sweep rewards")]
+    fn only_owner_can_sweep() {
+        let mut contract = setup();
+        fund_usdc_rewards(&mut contract, 10, 100, 0);
+
+        set_context_with_deposit(account("alice"), 200 * SECOND, 1);
+        contract.sweep_unallocated_rewards(TokenType::USDC, usdc());
+    }
+
+    #[test]
+    #[should_panic(expected = "Duration cannot exceed one year")]
+    fn funding_rejects_periods_over_a_year() {
+        let mut contract = setup();
+        fund_usdc_rewards(&mut contract, 1, 366 * 24 * 60 * 60, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Reward period overflow")]
+    fn funding_rejects_a_period_past_the_timestamp_range() {
+        let mut contract = setup();
+        fund_usdc_rewards(&mut contract, 1, 24 * 60 * 60, u64::MAX - DAY / 2);
+    }
+}
@@ -0,0 +1,172 @@
+use bond_math::mul_div;
+use crate::*;
+
+const GAS_FOR_SWAP_TRANSFER_CALL: Gas = Gas::from_gas(80_000_000_000_000);
+const GAS_FOR_RESOLVE_SWAP: Gas = Gas::from_gas(20_000_000_000_000);
+
+/// Ref-Finance swap action
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapAction {
+    pub pool_id: u64,
+    pub token_in: AccountId,
+    pub amount_in: Option<U128>,
+    pub token_out: AccountId,
+    pub min_amount_out: U128,
+}
+
+/// Ref-Finance instant swap message sent with `ft_transfer_call`
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapMsg {
+    pub actions: Vec<SwapAction>,
+    pub swap_out_recipient: Option<AccountId>,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapConfig {
+    pub dex_contract: Option<AccountId>,
+}
+
+#[near_bindgen]
+impl VaultContract {
+    // View functions
+    pub fn get_swap_config(&self) -> SwapConfig {
+        self.swap_config.clone()
+    }
+
+    pub fn get_swap_pool(&self, token_in: TokenType, token_out: TokenType) -> Option<u64> {
+        self.swap_pools.get(&(token_in, token_out))
+    }
+
+    // Admin functions
+    pub fn set_dex_contract(&mut self, dex_contract: Option<AccountId>) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can set DEX contract"
+        );
+        self.swap_config.dex_contract = dex_contract;
+        log!("DEX contract updated: {:?}", self.swap_config.dex_contract);
+    }
+
+    pub fn set_swap_pool(&mut self, token_in: TokenType, token_out: TokenType, pool_id: Option<u64>) {
+        require!(
+            env::predecessor_account_id() == self.config.owner_id,
+            "Only owner can set swap pools"
+        );
+        require!(token_in != token_out, "Swap tokens must differ");
+
+        match pool_id {
+            Some(pool_id) => {
+                self.swap_pools.insert(&(token_in.clone(), token_out.clone()), &pool_id);
+            }
+            None => {
+                self.swap_pools.remove(&(token_in.clone(), token_out.clone()));
+            }
+        }
+        log!("Swap pool {:?} -> {:?} set to {:?}", token_in, token_out, pool_id);
+    }
+
+    /// Burns shares of `token_type`, swaps the withdrawn tokens into `token_out`
+    /// on the configured DEX and sends the output straight to the caller
+    pub fn withdraw_with_swap(
+        &mut self,
+        token_type: TokenType,
+        vault_shares_amount: U128,
+        token_out: TokenType,
+        min_amount_out: U128,
+    ) -> Promise {
+        require!(!self.config.is_paused, "Vault is paused");
+        require!(token_type != token_out, "Use withdraw for the same token");
+
+        let dex_contract = self
+            .swap_config
+            .dex_contract
+            .clone()
+            .unwrap_or_else(|| env::panic_str("Swap withdrawals are not enabled"));
+        let pool_id = self
+            .swap_pools
+            .get(&(token_type.clone(), token_out.clone()))
+            .unwrap_or_else(|| env::panic_str("No swap pool configured for this pair"));
+
+        let sender_id = env::predecessor_account_id();
+        let withdrawal = self.internal_withdraw(&sender_id, &token_type, vault_shares_amount, None);
+        let (vault_shares_amount, amount_in) = (withdrawal.vault_shares_burned, withdrawal.amount);
+
+        let swap_msg = SwapMsg {
+            actions: vec![SwapAction {
+                pool_id,
+                token_in: self.get_token_contract(&token_type),
+                amount_in: Some(amount_in),
+                token_out: self.get_token_contract(&token_out),
+                min_amount_out,
+            }],
+            swap_out_recipient: Some(sender_id.clone()),
+        };
+
+        log!(
+            "Swapping {} {:?} to {:?} for {} via pool {}",
+            amount_in.0,
+            token_type,
+            token_out,
+            sender_id,
+            pool_id
+        );
+
+        ext_fungible_token::ext(self.get_token_contract(&token_type))
+            .with_attached_deposit(NearToken::from_yoctonear(1))
+            .with_static_gas(GAS_FOR_SWAP_TRANSFER_CALL)
+            .ft_transfer_call(
+                dex_contract,
+                amount_in,
+                Some(format!("Swap withdraw {:?}", token_type)),
+                near_sdk::serde_json::to_string(&swap_msg).unwrap(),
+            )
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_SWAP)
+                    .on_swap_withdraw_resolved(sender_id, token_type, token_out, vault_shares_amount, amount_in, min_amount_out)
+            )
+    }
+
+    /// Restores shares for any input the DEX refunded
+    #[private]
+    pub fn on_swap_withdraw_resolved(
+        &mut self,
+        account_id: AccountId,
+        token_type: TokenType,
+        token_out: TokenType,
+        vault_shares_amount: U128,
+        amount_in: U128,
+        min_amount_out: U128,
+    ) -> U128 {
+        // ft_transfer_call resolves to the amount the receiver kept
+        let used_amount = match env::promise_result(0) {
+            PromiseResult::Successful(value) => near_sdk::serde_json::from_slice::<U128>(&value)
+                .map(|used| used.0.min(amount_in.0))
+                .unwrap_or(0),
+            _ => 0,
+        };
+
+        let refunded = amount_in.0 - used_amount;
+        if refunded > 0 {
+            let shares_restored = mul_div(vault_shares_amount.0, refunded, amount_in.0);
+            self.restore_withdrawal(&account_id, &token_type, U128(shares_restored), U128(refunded));
+        }
+
+        env::log_str(&format!(
+            "EVENT_JSON:{{\"type\":\"swap_withdraw\",\"account_id\":\"{}\",\"token_in\":\"{:?}\",\"token_out\":\"{:?}\",\"amount_in\":\"{}\",\"min_amount_out\":\"{}\",\"refunded\":\"{}\",\"status\":\"{}\",\"timestamp\":{}}}",
+            account_id,
+            token_type,
+            token_out,
+            used_amount,
+            min_amount_out.0,
+            refunded,
+            if used_amount > 0 { "swapped" } else { "refunded" },
+            env::block_timestamp()
+        ));
+
+        U128(used_amount)
+    }
+}
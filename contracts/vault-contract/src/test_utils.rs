@@ -0,0 +1,64 @@
+//! Shared setup for the vault's `testing_env` unit tests
+
+use crate::*;
+use near_sdk::test_utils::VMContextBuilder;
+use near_sdk::testing_env;
+
+pub(crate) const SECOND: Timestamp = 1_000_000_000;
+pub(crate) const DAY: Timestamp = 24 * 60 * 60 * SECOND;
+
+pub(crate) fn owner() -> AccountId {
+    "owner.near".parse().unwrap()
+}
+
+pub(crate) fn account(name: &str) -> AccountId {
+    format!("{}.near", name).parse().unwrap()
+}
+
+pub(crate) fn usdc() -> AccountId {
+    "usdc.near".parse().unwrap()
+}
+
+pub(crate) fn set_context(predecessor: AccountId, timestamp: Timestamp) {
+    set_context_with_deposit(predecessor, timestamp, 0);
+}
+
+pub(crate) fn set_context_with_deposit(predecessor: AccountId, timestamp: Timestamp, yocto: u128) {
+    testing_env!(VMContextBuilder::new()
+        .current_account_id("vault.near".parse().unwrap())
+        .predecessor_account_id(predecessor)
+        .block_timestamp(timestamp)
+        .attached_deposit(NearToken::from_yoctonear(yocto))
+        .build());
+}
+
+/// Vault without a deposit fee
+pub(crate) fn setup() -> VaultContract {
+    set_context(owner(), 0);
+    VaultContract::new(owner(), "wrap.near".parse().unwrap(), usdc(), "usdt.near".parse().unwrap(), 0)
+}
+
+/// Deposits `amount` USDC for `account_id` at `timestamp`, as the token contract would
+pub(crate) fn deposit_usdc(contract: &mut VaultContract, account_id: &AccountId, amount: u128, timestamp: Timestamp) {
+    set_context(usdc(), timestamp);
+    contract.ft_on_transfer(account_id.clone(), U128(amount), String::new());
+}
+
+/// Funds a USDC reward program for USDC share holders at `timestamp`
+pub(crate) fn fund_usdc_rewards(contract: &mut VaultContract, reward_rate: u128, duration_sec: u64, timestamp: Timestamp) {
+    set_context(usdc(), timestamp);
+    let msg = format!(
+        "{{\"action\":\"fund_rewards\",\"token_type\":\"USDC\",\"reward_rate\":\"{}\",\"duration_sec\":{}}}",
+        reward_rate, duration_sec
+    );
+    let refund = contract.ft_on_transfer(owner(), U128(reward_rate * duration_sec as u128), msg);
+    assert_eq!(refund, U128(0));
+}
+
+pub(crate) fn pending_usdc_rewards(contract: &VaultContract, account_id: &AccountId) -> u128 {
+    contract
+        .get_pending_rewards(account_id.clone())
+        .into_iter()
+        .find(|reward| reward.reward_token == usdc())
+        .map_or(0, |reward| reward.amount.0)
+}
@@ -0,0 +1,46 @@
+use crate::*;
+
+/// Instructions carried in the `msg` of an `ft_transfer_call` to the vault
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde", tag = "action", rename_all = "snake_case")]
+pub enum TokenReceiverMsg {
+    /// Deposit the transferred tokens and mint vault shares to the sender
+    Deposit {
+        min_shares_out: Option<U128>,
+        referrer: Option<AccountId>,
+    },
+    /// Fund a reward period for holders of `token_type` shares (owner only)
+    FundRewards {
+        token_type: TokenType,
+        reward_rate: U128,
+        duration_sec: u64,
+    },
+}
+
+#[near_bindgen]
+impl VaultContract {
+    /// NEP-141 receiver. Returns the amount of tokens to refund to the sender.
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> U128 {
+        require!(amount.0 > 0, "Amount must be greater than zero");
+
+        let token_id = env::predecessor_account_id();
+        let message: TokenReceiverMsg = if msg.is_empty() {
+            TokenReceiverMsg::Deposit { min_shares_out: None, referrer: None }
+        } else {
+            near_sdk::serde_json::from_str(&msg)
+                .unwrap_or_else(|_| env::panic_str("Invalid ft_on_transfer message"))
+        };
+
+        match message {
+            TokenReceiverMsg::Deposit { min_shares_out, referrer } => {
+                require!(!self.config.is_paused, "Vault is paused");
+                let token_type = self.get_token_type_from_contract(&token_id);
+                self.internal_deposit(sender_id, token_type, amount, referrer, min_shares_out);
+                U128(0)
+            }
+            TokenReceiverMsg::FundRewards { token_type, reward_rate, duration_sec } => {
+                self.fund_reward_program(sender_id, token_id, amount, token_type, reward_rate, duration_sec)
+            }
+        }
+    }
+}
@@ -72,7 +72,7 @@ deploy_contract "vault-contract-v0" $VAULT_CONTRACT_ID "new" "{\"owner_id\":\"$A
 
 # 2. Deploy Registry Contract  
 echo "=== 2. REGISTRY CONTRACT ==="
-deploy_contract "registry-contract-v0" $REGISTRY_CONTRACT_ID "new" "{\"owner_id\":\"$ACCOUNT_ID\"}"
+deploy_contract "registry-contract" $REGISTRY_CONTRACT_ID "new" "{\"owner_id\":\"$ACCOUNT_ID\"}"
 
 # 3. Deploy Opportunity Contracts
 echo "=== 3. OPPORTUNITY CONTRACTS ==="
@@ -91,17 +91,17 @@ echo "📝 Adding opportunities to registry..."
 
 # Add Staking Opportunity
 near call $REGISTRY_CONTRACT_ID add_opportunity \
-    --args "{\"opportunity\":{\"id\":1,\"name\":\"NEAR Staking Pool\",\"description\":\"Stake NEAR tokens to earn rewards from validators\",\"category\":\"staking\",\"apy_bps\":1220,\"contract_address\":\"$STAKING_CONTRACT_ID\",\"max_capacity\":\"1000000000000000000000000000\",\"current_tvl\":\"0\",\"trust_score\":87,\"status\":\"active\"}}" \
+    --args "{\"name\":\"NEAR Staking Pool\",\"description\":\"Stake NEAR tokens to earn rewards from validators\",\"category\":\"staking\",\"contract_id\":\"$STAKING_CONTRACT_ID\",\"token_id\":\"wrap.testnet\",\"apy\":1220,\"metrics\":{\"apy_7d\":1250,\"apy_30d\":1220,\"success_rate\":9550,\"avg_gas_used\":45000000000000,\"avg_latency_ms\":1800,\"total_intents\":150,\"is_audited\":true,\"last_audit_at\":null,\"has_incidents\":false,\"last_incident_at\":null},\"min_deposit\":\"0\",\"max_deposit\":\"1000000000000000000000000000\",\"total_capacity\":\"1000000000000000000000000000\"}" \
     --accountId $ACCOUNT_ID
 
 # Add Lending Opportunity
 near call $REGISTRY_CONTRACT_ID add_opportunity \
-    --args "{\"opportunity\":{\"id\":2,\"name\":\"USDC Lending Pool\",\"description\":\"Lend USDC tokens to earn interest from borrowers\",\"category\":\"lending\",\"apy_bps\":810,\"contract_address\":\"$LENDING_CONTRACT_ID\",\"max_capacity\":\"500000000000000000000000000\",\"current_tvl\":\"0\",\"trust_score\":72,\"status\":\"active\"}}" \
+    --args "{\"name\":\"USDC Lending Pool\",\"description\":\"Lend USDC tokens to earn interest from borrowers\",\"category\":\"lending\",\"contract_id\":\"$LENDING_CONTRACT_ID\",\"token_id\":\"usdc.testnet\",\"apy\":810,\"metrics\":{\"apy_7d\":830,\"apy_30d\":810,\"success_rate\":9800,\"avg_gas_used\":35000000000000,\"avg_latency_ms\":1200,\"total_intents\":320,\"is_audited\":true,\"last_audit_at\":null,\"has_incidents\":false,\"last_incident_at\":null},\"min_deposit\":\"0\",\"max_deposit\":\"500000000000000000000000000\",\"total_capacity\":\"500000000000000000000000000\"}" \
     --accountId $ACCOUNT_ID
 
 # Add Liquidity Opportunity
 near call $REGISTRY_CONTRACT_ID add_opportunity \
-    --args "{\"opportunity\":{\"id\":3,\"name\":\"Liquidity Provision Pool\",\"description\":\"Provide liquidity to earn trading fees and rewards\",\"category\":\"liquidity\",\"apy_bps\":1490,\"contract_address\":\"$LIQUIDITY_CONTRACT_ID\",\"max_capacity\":\"2000000000000000000000000000\",\"current_tvl\":\"0\",\"trust_score\":58,\"status\":\"active\"}}" \
+    --args "{\"name\":\"Liquidity Provision Pool\",\"description\":\"Provide liquidity to earn trading fees and rewards\",\"category\":\"liquidity\",\"contract_id\":\"$LIQUIDITY_CONTRACT_ID\",\"token_id\":\"wrap.testnet\",\"apy\":1490,\"metrics\":{\"apy_7d\":1520,\"apy_30d\":1490,\"success_rate\":9200,\"avg_gas_used\":65000000000000,\"avg_latency_ms\":2500,\"total_intents\":85,\"is_audited\":false,\"last_audit_at\":null,\"has_incidents\":true,\"last_incident_at\":null},\"min_deposit\":\"0\",\"max_deposit\":\"2000000000000000000000000000\",\"total_capacity\":\"2000000000000000000000000000\"}" \
     --accountId $ACCOUNT_ID
 
 echo "✅ All opportunities added to registry"
@@ -116,7 +116,7 @@ near view $VAULT_CONTRACT_ID get_config
 
 # Check registry contract  
 echo "Registry Contract:"
-near view $REGISTRY_CONTRACT_ID get_opportunities '{"limit": 10, "from_index": 0}'
+near view $REGISTRY_CONTRACT_ID get_opportunities '{"limit": 10, "offset": 0}'
 
 echo ""
 echo "🎉 DEPLOYMENT COMPLETE!"
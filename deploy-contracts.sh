@@ -86,17 +86,25 @@ near call registry-contract.testnet add_opportunity \
     '{
         "name": "NEAR Staking Pool",
         "description": "High-yield staking pool with automated compounding and risk management strategies.",
+        "category": "staking",
         "contract_id": "opportunity-contract.testnet",
+        "token_id": "wrap.testnet",
         "apy": 1250,
-        "trust_score": 92,
-        "performance": 37,
-        "reliability": 35,
-        "safety": 20,
-        "risk_level": "LOW",
-        "category": "staking",
+        "metrics": {
+            "apy_7d": 1280,
+            "apy_30d": 1250,
+            "success_rate": 9700,
+            "avg_gas_used": 30000000000000,
+            "avg_latency_ms": 900,
+            "total_intents": 200,
+            "is_audited": true,
+            "last_audit_at": null,
+            "has_incidents": false,
+            "last_incident_at": null
+        },
         "min_deposit": "1000000000000000000000000",
         "max_deposit": "100000000000000000000000000",
-        "tvl": "1000000000000000000000000000"
+        "total_capacity": "1000000000000000000000000000"
     }' \
     --accountId registry-contract.testnet
 
@@ -52,7 +52,8 @@ class ScoringUpdater {
       process.env.REGISTRY_CONTRACT_ID || '',
       {
         viewMethods: ['get_opportunities', 'get_opportunity'],
-        changeMethods: ['update_opportunity_score']
+        // The updater account must be a registered reporter; scores land through oracle rounds
+        changeMethods: ['submit_score']
       }
     );
 
@@ -64,12 +65,12 @@ class ScoringUpdater {
     console.log(chalk.gray('Manual score inputs + intent success rate calculation\n'));
 
     // Get opportunities from registry
-    const opportunities = await this.registryContract.get_opportunities({ limit: 10, from_index: 0 });
+    const opportunities = await this.registryContract.get_opportunities({ limit: 10, offset: 0 });
     
     const manualInputs = [];
 
     for (const opp of opportunities) {
-      console.log(chalk.yellow(`\n=== ${opp.name} (Current Score: ${opp.trust_score}/100) ===`));
+      console.log(chalk.yellow(`\n=== ${opp.name} (Current Score: ${opp.total_score}/100) ===`));
 
       const inputs = await inquirer.prompt([
         {
@@ -127,6 +128,7 @@ class ScoringUpdater {
       manualInputs.push({
         opportunityId: opp.id,
         name: opp.name,
+        currentScore: opp.total_score,
         ...inputs
       });
     }
@@ -205,39 +207,51 @@ class ScoringUpdater {
   }
 
   async updateRegistryScores(scoreData) {
-    console.log(chalk.blue.bold('\n🔄 Updating Registry Scores...\n'));
+    console.log(chalk.blue.bold('\n🔄 Submitting Scores to the Registry Oracle...\n'));
 
     for (const data of scoreData) {
-      const { opportunityId, name, oldScore, newScore } = data;
+      const { opportunityId, name, oldScore, newScore, performance, reliability, safety } = data;
       
       try {
-        // Update score in registry contract
-        await this.registryContract.update_opportunity_score({
+        // Submit this reporter's components to the opportunity's open oracle round
+        const round = await this.registryContract.submit_score({
           opportunity_id: opportunityId,
-          new_score: newScore
+          performance,
+          reliability,
+          safety
         });
 
-        const risk = this.getRiskLevel(newScore);
-        const change = newScore - oldScore;
+        if (round.status !== 'Finalized') {
+          console.log(chalk.gray(`⏳ ${name}: submitted to round ${round.round_id}, waiting for quorum`));
+          logger.info('Score submitted', { opportunityId, name, roundId: round.round_id, performance, reliability, safety });
+          continue;
+        }
+
+        // The round stores the reporters' medians, which may differ from this submission
+        const finalScore = round.performance + round.reliability + round.safety;
+        const risk = this.getRiskLevel(finalScore);
+        const change = finalScore - oldScore;
         const changeStr = change > 0 ? `+${change}` : change.toString();
 
-        console.log(chalk[risk.color](`${risk.emoji} ${name}: ${oldScore} → ${newScore} (${changeStr})`));
+        console.log(chalk[risk.color](`${risk.emoji} ${name}: ${oldScore} → ${finalScore} (${changeStr})`));
         
-        logger.info('Score updated', {
+        logger.info('Score round finalized', {
           opportunityId,
+          roundId: round.round_id,
           name,
           oldScore,
-          newScore,
+          submittedScore: newScore,
+          finalScore,
           change
         });
 
       } catch (error) {
-        console.log(chalk.red(`❌ Failed to update ${name}: ${error.message}`));
-        logger.error('Score update failed', { opportunityId, name, error: error.message });
+        console.log(chalk.red(`❌ Failed to submit ${name}: ${error.message}`));
+        logger.error('Score submission failed', { opportunityId, name, error: error.message });
       }
     }
 
-    console.log(chalk.green.bold('\n✅ All scores updated successfully!'));
+    console.log(chalk.green.bold('\n✅ All scores submitted!'));
   }
 
   async run() {
@@ -266,7 +280,10 @@ class ScoringUpdater {
           opportunityId: inputs.opportunityId,
           name: inputs.name,
           oldScore: inputs.currentScore || 0,
-          newScore: scoreData.totalScore
+          newScore: scoreData.totalScore,
+          performance: scoreData.performanceScore,
+          reliability: scoreData.reliabilityScore,
+          safety: scoreData.safetyScore
         });
       }
 
@@ -7,6 +7,35 @@ export const CONTRACT_ADDRESSES = {
   OPPORTUNITY: 'opportunity-contract.testnet',
 } as const;
 
+// Inputs the registry scores a new opportunity from
+export interface RiskMetrics {
+  apy_7d?: number | null; // Realized APY in basis points
+  apy_30d?: number | null;
+  success_rate: number; // Basis points (10000 = 100%)
+  avg_gas_used: number;
+  avg_latency_ms: number;
+  total_intents: number;
+  is_audited: boolean;
+  last_audit_at?: number | null; // Nanoseconds
+  has_incidents: boolean;
+  last_incident_at?: number | null;
+}
+
+// Arguments of the registry's add_opportunity
+export interface AddOpportunityArgs {
+  name: string;
+  description: string;
+  category: string;
+  contract_id: string;
+  token_id?: string | null;
+  apy: number; // Basis points
+  metrics: RiskMetrics;
+  min_deposit: string; // U128 as string
+  max_deposit: string;
+  total_capacity: string;
+  tags?: string[] | null;
+}
+
 // Helper function to get contract instance
 export async function getContract(account: Account, contractId: string) {
   return new Account(account.connection, account.accountId);
@@ -215,12 +244,12 @@ export class RegistryContract {
   }
 
   // Add new opportunity (admin only)
-  async addOpportunity(opportunity: any) {
+  async addOpportunity(opportunity: AddOpportunityArgs) {
     try {
       const result = await this.account.functionCall({
         contractId: this.contractId,
         methodName: 'add_opportunity',
-        args: opportunity
+        args: { ...opportunity }
       });
       return result;
     } catch (error) {
@@ -81,20 +81,20 @@ export class ContractDataService {
       const result = await account.viewFunction({
         contractId,
         methodName: 'get_opportunities',
-        args: { limit, from_index: offset }
+        args: { limit, offset }
       });
 
       return result.map((opp: any) => ({
         id: opp.id,
         name: opp.name,
         description: opp.description,
-        apy: opp.apy_bps / 100, // Convert basis points to percentage
-        trustScore: opp.trust_score,
-        contractAddress: opp.contract_address,
+        apy: opp.apy / 100, // Convert basis points to percentage
+        trustScore: opp.total_score,
+        contractAddress: opp.contract_id,
         category: opp.category,
         minDeposit: opp.min_deposit ? parseFloat(utils.format.formatNearAmount(opp.min_deposit)) : undefined,
         maxDeposit: opp.max_deposit ? parseFloat(utils.format.formatNearAmount(opp.max_deposit)) : undefined,
-        tvl: opp.current_tvl ? parseFloat(utils.format.formatNearAmount(opp.current_tvl)) : undefined,
+        tvl: opp.tvl ? parseFloat(utils.format.formatNearAmount(opp.tvl)) : undefined,
         status: opp.status
       }));
     } catch (error) {